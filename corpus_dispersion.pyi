@@ -20,6 +20,13 @@ CorpusWordAnalyzer
 DispersionMetrics
     Container for all computed dispersion metrics with read-only access.
 
+CorpusPartition
+    Corpus part sizes with derived quantities precomputed once, reusable across calls.
+
+FrequencyTable
+    Word x part frequency table read from a CSV/TSV file or counted from a
+    directory of text files, analyzed without Python lists.
+
 Supported Metrics
 -----------------
 Classical metrics:
@@ -28,12 +35,18 @@ Classical metrics:
 - Juilland's D: Normalized coefficient of variation
 - Carroll's D2: Entropy-based dispersion
 - Rosengren's S (adjusted): Weighted frequency distribution
+- Adjusted frequencies: Juilland's U, Carroll's Um, Rosengren's AF
+- Lyne's D3: Chi-square based dispersion
 - DP and DP normalized: Deviation of proportions
+- DP_nofreq: Frequency-independent DP (Gries 2024)
 
 Modern metrics:
 - Kullback-Leibler divergence: Information-theoretic measure
+- Normalized KL divergence: 1 - 2^(-KL), bounded to [0, 1)
+- Zhang's DC: Distributional consistency
 - Jensen-Shannon divergence: Symmetric version of KL divergence
 - Hellinger distance: Geometric measure of distributional similarity
+- Diversity indices: Gini coefficient, Shannon entropy, Pielou evenness, Simpson's index, Hill numbers
 - Evenness (DA): Pairwise difference-based evenness
 - Mean text frequency (FT): Average normalized frequency
 - Pervasiveness (PT): Proportion of partitions with the word
@@ -43,6 +56,9 @@ References
 ----------
 - Gries, S. Th. (2020). Analyzing Dispersion. In: A Practical Handbook of Corpus Linguistics.
 - Egbert, J., & Burch, B. (2023). Which words matter most? Operationalizing lexical prevalence for rank-ordered word lists. Applied Linguistics, 44(1), 103–126. <https://doi.org/10.1093/applin/amac030>
+- Zhang, H., Huang, C., & Yu, S. (2004). Distributional consistency: As a general method for defining a core lexicon. In Proceedings of LREC 2004 (pp. 1119–1122).
+- Hill, M. O. (1973). Diversity and evenness: A unifying notation and its consequences. Ecology, 54(2), 427–432.
+- Lyne, A. A. (1985). Dispersion. In The Vocabulary of French Business Correspondence (pp. 101–124). Geneva: Slatkine.
 - Carroll (1970), Juilland et al. (1970), Rosengren (1971), Biber et al. (2016), etc.
 
 Examples
@@ -70,7 +86,10 @@ Examples
 
 """
 
-from typing import List, Optional
+from os import PathLike
+from typing import Dict, Iterator, List, Optional, Sequence, Tuple, Union, overload
+
+import numpy as np
 
 __version__ = "0.1.0"
 __author__ = "Haobo Zhang"
@@ -78,11 +97,101 @@ __author__ = "Haobo Zhang"
 __all__ = [
     "CorpusWordAnalyzer",
     "DispersionMetrics",
-    "PyValueError",
+    "CorpusPartition",
+    "MetricBounds",
+    "set_num_threads",
+    "get_num_threads",
+    "DispersionError",
+    "LengthMismatchError",
+    "EmptyInputError",
+    "NonPositiveTotalError",
+    "NegativeValueError",
+    "NonFiniteValueError",
+    "UnknownMetricError",
+    "MalformedSparseError",
+    "InvalidPositionError",
+    "InvalidParameterError",
+    "MalformedTableError",
+    "PositionAnalyzer",
+    "PositionMetrics",
+    "MetricIntervals",
+    "NullDistribution",
+    "FrequencyResiduals",
+    "GoodnessOfFit",
+    "WordListEntry",
+    "LabelledMetrics",
+    "FrequencyTable",
+    "chi_square_sf",
 ]
 
-class PyValueError(Exception):
-    """Raised when input parameters are invalid."""
+class DispersionError(ValueError):
+    """
+    Base class for all input validation errors.
+
+    Attributes
+    ----------
+    row : Optional[int]
+        Index of the offending row in batch calls, None otherwise.
+    word : Optional[str]
+        Word label of the offending row when the batch call was given
+        ``words``, None otherwise.
+    """
+
+    row: Optional[int]
+    word: Optional[str]
+
+class LengthMismatchError(DispersionError):
+    """Raised when a frequency vector length does not match the number of corpus parts,
+    or the number of word labels does not match the number of frequency rows."""
+
+    ...
+
+class EmptyInputError(DispersionError):
+    """Raised when input vectors are empty."""
+
+    ...
+
+class NonPositiveTotalError(DispersionError):
+    """Raised when the total corpus size is zero or negative."""
+
+    ...
+
+class NegativeValueError(DispersionError):
+    """Raised when a frequency or corpus part size is negative."""
+
+    ...
+
+class NonFiniteValueError(DispersionError):
+    """Raised when an input value is NaN or infinite."""
+
+    ...
+
+class UnknownMetricError(DispersionError):
+    """Raised when a metric name is not recognised."""
+
+    ...
+
+class MalformedSparseError(DispersionError):
+    """Raised when sparse matrix index arrays (indptr/indices) are inconsistent."""
+
+    ...
+
+class InvalidPositionError(DispersionError):
+    """Raised when a token position is outside the corpus or not strictly increasing."""
+
+    ...
+
+class InvalidParameterError(DispersionError):
+    """Raised when a tuning parameter (e.g. a bootstrap setting) is outside its valid range."""
+
+    ...
+
+class MalformedTableError(DispersionError):
+    """
+    Raised when a frequency table file cannot be parsed (wrong number of
+    fields, a cell that is not a number, an unterminated quoted field, ...).
+    The message gives the 1-based line of the offending record.
+    """
 
     ...
 
@@ -92,6 +201,8 @@ class DispersionMetrics:
 
     Attributes
     ----------
+    word : Optional[str]
+        Word label, set when batch calls are given ``words``.
     range : int
         Number of partitions in which the word occurs (range).
     sd_population : Optional[float]
@@ -100,20 +211,44 @@ class DispersionMetrics:
         Coefficient of variation (standard deviation / mean).
     juilland_d : Optional[float]
         Juilland's D dispersion index (see Gries 2020, eq. 10).
+    juilland_u : Optional[float]
+        Juilland's usage coefficient U (f * D).
     carroll_d2 : Optional[float]
         Carroll's D2 entropy-based dispersion index (see Gries 2020, eq. 11).
+    carroll_um : Optional[float]
+        Carroll's usage coefficient Um (f * D2 + (1 - D2) * f / n).
+    lyne_d3 : Optional[float]
+        Lyne's D3 (1 - chi-square / 4f, expected frequencies f * s_i).
     roschengren_s_adj : Optional[float]
         Rosengren's adjusted S index (see Gries 2020, eq. 12).
+    roschengren_af : Optional[float]
+        Rosengren's adjusted frequency AF ((sum of sqrt(s_i * v_i))^2, i.e. S_adj * f).
+    zhang_dc : Optional[float]
+        Zhang et al.'s distributional consistency (1/n = clumped, 1 = perfectly even).
     dp : Optional[float]
         DP (Deviation of Proportions) index (see Gries 2020, eq. 13).
     dp_norm : Optional[float]
         Normalized DP index (DP / (1 - min(s)), see Gries 2020, eq. 13).
+    dp_nofreq : Optional[float]
+        Frequency-independent DP (Gries 2024): DP scaled to [0, 1] between the
+        most even and the most clumped distribution of the word's frequency.
+        None when the two bounds coincide (e.g. a single partition).
     kl_divergence : Optional[float]
         Kullback-Leibler divergence (see Gries 2020, eq. 14).
+    kl_divergence_norm : Optional[float]
+        KL divergence normalized as 1 - 2^(-KL) (0 = even, approaches 1 when clumped).
     jsd_dispersion : Optional[float]
         Jensen-Shannon divergence based dispersion measure (1 - normalized JSD).
     hellinger_dispersion : Optional[float]
         Hellinger distance based dispersion measure (1 - normalized distance).
+    gini_coefficient : Optional[float]
+        Gini coefficient of the normalized frequencies (0 = even, (n-1)/n = one part).
+    shannon_entropy : Optional[float]
+        Shannon entropy in nats of the proportions p_i / sum(p).
+    pielou_evenness : Optional[float]
+        Pielou's evenness H / ln(n) (identical to Carroll's D2).
+    simpson_index : Optional[float]
+        Simpson's index, sum of squared proportions (1/n = even, 1 = one part).
     mean_text_frequency_ft : Optional[float]
         Mean normalized frequency across partitions (Egbert & Burch FT).
     pervasiveness_pt : Optional[float]
@@ -126,17 +261,29 @@ class DispersionMetrics:
         Frequency adjusted by evenness (FT * DA).
     """
 
+    word: Optional[str]
     range: int
     sd_population: Optional[float]
     vc_population: Optional[float]
     juilland_d: Optional[float]
+    juilland_u: Optional[float]
     carroll_d2: Optional[float]
+    carroll_um: Optional[float]
+    lyne_d3: Optional[float]
     roschengren_s_adj: Optional[float]
+    roschengren_af: Optional[float]
+    zhang_dc: Optional[float]
     dp: Optional[float]
     dp_norm: Optional[float]
+    dp_nofreq: Optional[float]
     kl_divergence: Optional[float]
+    kl_divergence_norm: Optional[float]
     jsd_dispersion: Optional[float]
     hellinger_dispersion: Optional[float]
+    gini_coefficient: Optional[float]
+    shannon_entropy: Optional[float]
+    pielou_evenness: Optional[float]
+    simpson_index: Optional[float]
     mean_text_frequency_ft: Optional[float]
     pervasiveness_pt: Optional[float]
     evenness_da: Optional[float]
@@ -147,6 +294,580 @@ class DispersionMetrics:
         """Return string representation of the metrics."""
         ...
 
+class LabelledMetrics:
+    """
+    Batch results labelled by word, returned by ``calculate_batch_metrics``
+    when ``words`` is given.
+
+    Behaves like the list of results in row order (``len``, iteration,
+    integer indexing) and can also be indexed by word. If a word occurs more
+    than once, lookups by word return its first row.
+    """
+
+    @property
+    def words(self) -> List[str]:
+        """Word labels in row order."""
+        ...
+
+    def get(
+        self, word: str, default: Optional[DispersionMetrics] = None
+    ) -> Optional[DispersionMetrics]:
+        """Metrics of ``word``, or ``default`` if it is not in the batch."""
+        ...
+
+    def position(self, word: str) -> Optional[int]:
+        """Row index of ``word``, or None."""
+        ...
+
+    def to_list(self) -> List[DispersionMetrics]:
+        """Results as a plain list in row order."""
+        ...
+
+    def __len__(self) -> int: ...
+    def __getitem__(self, key: Union[int, str]) -> DispersionMetrics:
+        """
+        Row by index (negative counts from the end) or by word.
+
+        Raises
+        ------
+        IndexError
+            If an integer index is out of range.
+        KeyError
+            If a word is not in the batch.
+        """
+        ...
+
+    def __contains__(self, word: object) -> bool: ...
+    def __iter__(self) -> Iterator[DispersionMetrics]: ...
+    def __repr__(self) -> str: ...
+
+class PositionMetrics:
+    """
+    Position-based dispersion metrics of a single word (Savický & Hlaváčová 2002).
+
+    Attributes
+    ----------
+    frequency : int
+        Number of occurrences.
+    arf : Optional[float]
+        Average reduced frequency: (1/v) * sum(min(d_i, v)) with v = N / f.
+        Between 1 (all occurrences adjacent) and f (evenly spaced); 0 if absent.
+    awt : Optional[float]
+        Average waiting time: (1 + sum(d_i ** 2) / N) / 2. None if absent.
+    ald : Optional[float]
+        Average logarithmic distance: sum(d_i / N * log10(d_i)). None if absent.
+    self_dispersion : Optional[float]
+        Washtell's (2009) self-dispersion: mean nearest-neighbour distance over
+        the N / (2f) expected at random (< 1 clustered, ~1 random, 2 evenly
+        spaced). None with fewer than two occurrences.
+    mean_distance : Optional[float]
+        Mean distance between consecutive occurrences. None if absent.
+    distance_variance : Optional[float]
+        Population variance of those distances. None if absent.
+    burstiness : Optional[float]
+        (sigma - mu) / (sigma + mu) of the distances: -1 periodic, ~0 random,
+        approaching 1 bursty. None with fewer than two occurrences.
+    """
+
+    frequency: int
+    arf: Optional[float]
+    awt: Optional[float]
+    ald: Optional[float]
+    self_dispersion: Optional[float]
+    mean_distance: Optional[float]
+    distance_variance: Optional[float]
+    burstiness: Optional[float]
+
+    def __repr__(self) -> str: ...
+
+class PositionAnalyzer:
+    """
+    Dispersion of a word from its token positions in a running corpus.
+
+    Distances d_i are taken between consecutive occurrences, with the first one
+    wrapping around from the last occurrence (the corpus is treated as circular).
+
+    Parameters
+    ----------
+    positions : List[int]
+        Strictly increasing 0-based token offsets of the word.
+    corpus_length : int
+        Number of tokens in the corpus (N).
+
+    Raises
+    ------
+    InvalidPositionError
+        If a position is outside the corpus or not strictly increasing.
+    NonPositiveTotalError
+        If corpus_length is 0.
+    """
+
+    def __init__(self, positions: List[int], corpus_length: int) -> None: ...
+    def get_frequency(self) -> int: ...
+    def get_corpus_length(self) -> int: ...
+    def get_distances(self) -> List[float]:
+        """Distances between consecutive occurrences, wrap-around distance first."""
+        ...
+    def get_arf(self) -> Optional[float]:
+        """Get average reduced frequency (ARF)."""
+        ...
+    def get_awt(self) -> Optional[float]:
+        """Get average waiting time (AWT)."""
+        ...
+    def get_ald(self) -> Optional[float]:
+        """Get average logarithmic distance (ALD)."""
+        ...
+    def get_self_dispersion(self) -> Optional[float]:
+        """Get Washtell's self-dispersion."""
+        ...
+    def get_mean_distance(self) -> Optional[float]:
+        """Get the mean distance between consecutive occurrences."""
+        ...
+    def get_distance_variance(self) -> Optional[float]:
+        """Get the population variance of the distances."""
+        ...
+    def get_burstiness(self) -> Optional[float]:
+        """Get the burstiness coefficient (sigma - mu) / (sigma + mu)."""
+        ...
+    def calculate_all_metrics(self) -> PositionMetrics: ...
+    @staticmethod
+    def calculate_batch_metrics(
+        positions: List[List[int]],
+        corpus_length: int,
+        *,
+        n_threads: Optional[int] = None,
+    ) -> List[PositionMetrics]:
+        """
+        Calculate position metrics for many words in parallel (GIL released).
+
+        Raises
+        ------
+        DispersionError
+            If any word's positions are invalid; the word index is ``err.row``.
+        """
+        ...
+
+class MetricBounds:
+    """
+    Metric values at the theoretical extremes for a word's total frequency.
+
+    Attributes
+    ----------
+    most_even : List[float]
+        Allocation of the word's f tokens closest to f * s (largest remainder method).
+    most_clumped : List[float]
+        All f tokens in the smallest non-empty partition.
+    even_metrics : DispersionMetrics
+        Metrics of the most even distribution.
+    clumped_metrics : DispersionMetrics
+        Metrics of the most clumped distribution.
+    """
+
+    most_even: List[float]
+    most_clumped: List[float]
+    even_metrics: DispersionMetrics
+    clumped_metrics: DispersionMetrics
+
+    def normalize(self, metric_name: str, value: float) -> Optional[float]:
+        """
+        Min-max normalize a metric value between the two extremes.
+
+        Returns 0 at the most even and 1 at the most clumped distribution for
+        every metric; values beyond a bound are clamped to [0, 1]. Returns None
+        for sd_population, vc_population and the FT family (mean_text_frequency_ft,
+        ft_adjusted_by_pt, ft_adjusted_by_da), whose extremes are not these
+        distributions when part sizes differ, or if the bounds are undefined
+        or coincide.
+
+        Raises
+        ------
+        UnknownMetricError
+            If metric_name is not a DispersionMetrics field.
+        """
+        ...
+
+    def __repr__(self) -> str: ...
+
+class MetricIntervals:
+    """
+    Percentile bootstrap confidence intervals of a word's metrics.
+
+    Parts are resampled with replacement (frequency and size together) and
+    the metrics recomputed on every resample. Resamples that draw none of
+    the word's occurrences are skipped, so a word that never occurs has no
+    intervals.
+
+    Attributes
+    ----------
+    estimate : DispersionMetrics
+        Metrics of the observed frequencies.
+    lower : DispersionMetrics
+        Lower bounds, the (1 - confidence_level) / 2 percentile of the resamples.
+    upper : DispersionMetrics
+        Upper bounds, the (1 + confidence_level) / 2 percentile of the resamples.
+    confidence_level : float
+        Coverage of the intervals.
+    n_resamples : int
+        Number of resamples drawn.
+    n_valid_resamples : int
+        Number of resamples the intervals were computed from.
+    seed : int
+        Seed the resamples were drawn from; pass it back to reproduce them.
+    """
+
+    estimate: DispersionMetrics
+    lower: DispersionMetrics
+    upper: DispersionMetrics
+    confidence_level: float
+    n_resamples: int
+    n_valid_resamples: int
+    seed: int
+
+    def interval(self, metric_name: str) -> Optional[Tuple[float, float]]:
+        """
+        (lower, upper) bounds of a metric, or None if it was not requested.
+
+        Raises
+        ------
+        UnknownMetricError
+            If metric_name is not a DispersionMetrics field.
+        """
+        ...
+
+    def __repr__(self) -> str: ...
+
+class GoodnessOfFit:
+    """
+    Chi-square and G-test (log-likelihood) of a word's frequencies against
+    the size-proportional expectation f * s_i.
+
+    Attributes
+    ----------
+    chi_square : float
+        Pearson's chi-square statistic sum((v_i - f*s_i)^2 / (f*s_i)).
+    g_statistic : float
+        Log-likelihood statistic G = 2 * sum(v_i * ln(v_i / (f*s_i))).
+    degrees_of_freedom : int
+        Number of parts with a positive size, minus one.
+    chi_square_p_value : Optional[float]
+        Upper-tail probability of chi_square. None without degrees of freedom.
+    g_p_value : Optional[float]
+        Upper-tail probability of g_statistic. None without degrees of freedom.
+    """
+
+    chi_square: float
+    g_statistic: float
+    degrees_of_freedom: int
+    chi_square_p_value: Optional[float]
+    g_p_value: Optional[float]
+
+    def __repr__(self) -> str: ...
+
+class NullDistribution:
+    """
+    Simulated multinomial null distribution of a word's metrics.
+
+    Each simulation scatters the word's (rounded) frequency over the parts
+    with probabilities proportional to the part sizes, the expectation under
+    perfectly even dispersion, and recomputes the selected metrics.
+
+    Attributes
+    ----------
+    observed : DispersionMetrics
+        Metrics of the observed frequencies.
+    expected : DispersionMetrics
+        Mean of the simulated metrics.
+    std_dev : DispersionMetrics
+        Sample standard deviation of the simulated metrics.
+    z_score : DispersionMetrics
+        (observed - expected) / std_dev; None where the simulations do not vary.
+    p_value_greater : DispersionMetrics
+        Empirical P(simulated >= observed), computed as (k + 1) / (n_simulations + 1).
+    p_value_less : DispersionMetrics
+        Empirical P(simulated <= observed), computed the same way.
+    n_simulations : int
+        Number of simulated words.
+    seed : int
+        Seed the simulations were drawn from; pass it back to reproduce them.
+    """
+
+    observed: DispersionMetrics
+    expected: DispersionMetrics
+    std_dev: DispersionMetrics
+    z_score: DispersionMetrics
+    p_value_greater: DispersionMetrics
+    p_value_less: DispersionMetrics
+    n_simulations: int
+    seed: int
+
+    def __repr__(self) -> str: ...
+
+class FrequencyResiduals:
+    """
+    A word's metrics relative to words of similar frequency.
+
+    Words are grouped into bands of roughly equal size by total frequency
+    (a frequency is never split across bands). The band medians, placed at
+    each band's median log frequency, give a piecewise-linear trend of every
+    metric against log frequency.
+
+    Attributes
+    ----------
+    observed : DispersionMetrics
+        Metrics of the word.
+    predicted : DispersionMetrics
+        Trend value at the word's log frequency.
+    residual : DispersionMetrics
+        observed - predicted: dispersion beyond what frequency predicts.
+    percentile : DispersionMetrics
+        Mid-rank percentile (0-100) of the observed value within the word's band.
+    frequency : float
+        Total frequency of the word.
+    band : Optional[int]
+        Frequency band, 0 for the rarest words. None (with all predicted,
+        residual and percentile values None) if the word never occurs.
+    """
+
+    observed: DispersionMetrics
+    predicted: DispersionMetrics
+    residual: DispersionMetrics
+    percentile: DispersionMetrics
+    frequency: float
+    band: Optional[int]
+
+    def __repr__(self) -> str: ...
+
+class WordListEntry:
+    """
+    One row of a rank-ordered word list (Egbert & Burch lexical prevalence).
+
+    Attributes
+    ----------
+    rank : Optional[int]
+        1-based rank. None if the score was not computed or is NaN; such
+        words follow the ranked ones.
+    word : str
+        The word label.
+    score : Optional[float]
+        Value of the ranking metric (FT x DA by default).
+    frequency : float
+        Total frequency of the word.
+    mean_text_frequency_ft : Optional[float]
+        Mean text frequency FT.
+    pervasiveness_pt : Optional[float]
+        Pervasiveness PT.
+    evenness_da : Optional[float]
+        Evenness DA.
+    """
+
+    rank: Optional[int]
+    word: str
+    score: Optional[float]
+    frequency: float
+    mean_text_frequency_ft: Optional[float]
+    pervasiveness_pt: Optional[float]
+    evenness_da: Optional[float]
+
+    def __repr__(self) -> str: ...
+
+def chi_square_sf(statistic: float, degrees_of_freedom: int) -> Optional[float]:
+    """Upper-tail probability of a chi-square statistic (None for zero degrees of freedom)."""
+    ...
+
+def set_num_threads(n_threads: Optional[int] = None) -> None:
+    """
+    Set the number of threads used by batch calls that do not pass ``n_threads``.
+
+    ``None`` restores rayon's default (one thread per core); ``1`` processes
+    batches sequentially on a single worker thread.
+    """
+    ...
+
+def get_num_threads() -> int:
+    """Number of threads batch calls without ``n_threads`` currently use."""
+    ...
+
+class CorpusPartition:
+    """
+    Corpus part sizes with the relative sizes and other per-part quantities
+    precomputed once.
+
+    Pass an instance in place of ``corpus_part_sizes`` (and leave
+    ``total_corpus_words`` as None) to reuse it across analyzers and batch calls.
+
+    Parameters
+    ----------
+    part_sizes : List[float]
+        Total number of words in each partition (must be non-negative).
+    total_corpus_words : Optional[float]
+        Total corpus size; defaults to the sum of part_sizes.
+
+    Raises
+    ------
+    DispersionError
+        If part sizes are empty or invalid, or the total is not positive.
+    """
+
+    n_parts: int
+    part_sizes: List[float]
+    total_corpus_words: float
+    relative_sizes: List[float]
+    min_s: float
+
+    def __init__(
+        self, part_sizes: List[float], total_corpus_words: Optional[float] = None
+    ) -> None: ...
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
+PartitionLike = Union[List[float], CorpusPartition]
+
+class FrequencyTable:
+    """
+    Word x part frequency table read from a CSV/TSV file (see also
+    ``FrequencyTable.from_directory``).
+
+    Each record holds a word followed by one frequency per corpus part. The
+    file is parsed in Rust into a contiguous buffer that the batch engine
+    reads in place; empty cells count as 0, blank lines are skipped.
+
+    Parameters
+    ----------
+    path : Union[str, PathLike]
+        File to read.
+    delimiter : str
+        Field separator, a single ASCII character (default tab; "," for CSV).
+    has_header : bool
+        Whether the first record names the parts (its first cell is ignored).
+        Without a header the parts are named ``part_1``, ``part_2``, ...
+    quote : Optional[str]
+        Quote character (default '"'). Quoted fields may contain the
+        delimiter and line breaks; a doubled quote is a literal one. None
+        disables quoting.
+    size_row : Optional[str]
+        Word-column label of a row holding the part sizes (e.g. "__size__").
+        Without it, each part size is the sum of its column.
+
+    Raises
+    ------
+    MalformedTableError
+        If a record cannot be parsed.
+    InvalidParameterError
+        If ``delimiter`` or ``quote`` is not a single ASCII character.
+    OSError
+        If the file cannot be read.
+    DispersionError
+        If a frequency is negative or not finite (``err.row`` and
+        ``err.word`` identify it), or the part sizes are invalid (e.g. they
+        sum to zero).
+    """
+
+    words: List[str]
+    part_names: List[str]
+    n_words: int
+    n_parts: int
+    partition: CorpusPartition
+    """Partition built from the part sizes, reusable in other calls."""
+    matrix: Union[np.ndarray, "scipy.sparse.csr_matrix"]
+    """
+    Read-only (n_words, n_parts) float64 view of the frequencies, without
+    copying; pass it to any batch method (e.g. ``calculate_batch_bootstrap``)
+    together with ``partition``. Tables built by ``from_directory`` store
+    only the non-zero counts and return a SciPy CSR matrix instead (a copy).
+    """
+
+    def __init__(
+        self,
+        path: Union[str, PathLike],
+        *,
+        delimiter: str = "\t",
+        has_header: bool = True,
+        quote: Optional[str] = '"',
+        size_row: Optional[str] = None,
+    ) -> None: ...
+    @staticmethod
+    def from_directory(
+        path: Union[str, PathLike],
+        *,
+        partition_by: str = "file",
+        extensions: Optional[Sequence[str]] = ("txt",),
+        lowercase: bool = True,
+        keep_numbers: bool = True,
+        joiners: str = "'\u2019-",
+        split_cjk: bool = True,
+        min_length: int = 1,
+        n_threads: Optional[int] = None,
+    ) -> "FrequencyTable":
+        """
+        Tokenize and count the UTF-8 text files of a directory in parallel.
+
+        Parameters
+        ----------
+        path : Union[str, PathLike]
+            Corpus directory. Entries whose names start with "." are skipped.
+        partition_by : str
+            "file": each file directly in ``path`` is a part. "directory":
+            each subdirectory is a part, made of all the files below it.
+            Symbolic links to directories inside a part are not followed, and
+            dangling links are skipped.
+        extensions : Optional[Sequence[str]]
+            File extensions to read (without the dot, case-insensitive);
+            None reads every file.
+        lowercase : bool
+            Lowercase the tokens.
+        keep_numbers : bool
+            Keep tokens made only of numeric characters.
+        joiners : str
+            Characters that join two word characters into one token
+            ("don't", "well-known").
+        split_cjk : bool
+            Make every Han, Hiragana and Katakana character a token.
+        min_length : int
+            Minimum token length in characters.
+        n_threads : Optional[int]
+            Size of a dedicated thread pool for the counting.
+
+        A token is a run of Unicode alphabetic or numeric characters. Parts
+        are ordered by name, words lexicographically, and the part sizes are
+        the token counts of the parts. Only the non-zero counts are stored, so
+        ``matrix`` is a SciPy CSR matrix.
+
+        Raises
+        ------
+        InvalidParameterError
+            If ``partition_by`` is not "file" or "directory".
+        OSError
+            If a directory or file cannot be read, or a file is not UTF-8.
+        EmptyInputError
+            If the directory holds no parts.
+        NonPositiveTotalError
+            If no tokens were counted.
+        """
+        ...
+
+    def row(self, index: int) -> Optional[List[float]]:
+        """Frequencies of the word in row ``index``, or None if out of range."""
+        ...
+
+    def calculate_metrics(
+        self,
+        metrics: Optional[List[str]] = None,
+        n_threads: Optional[int] = None,
+    ) -> LabelledMetrics:
+        """
+        Compute the selected metrics (all by default) for every word in parallel.
+
+        Raises
+        ------
+        DispersionError
+            If a row holds a negative or non-finite frequency; ``err.row``
+            and ``err.word`` identify it.
+        """
+        ...
+
+    def __len__(self) -> int: ...
+    def __repr__(self) -> str: ...
+
 class CorpusWordAnalyzer:
     """
     Analyze word frequency distributions across corpus partitions and compute multiple dispersion metrics.
@@ -158,17 +879,19 @@ class CorpusWordAnalyzer:
     ----------
     v : List[float]
         Frequency of the target word in each partition (must be non-negative).
-    corpus_part_sizes_words : List[float]
-        Total number of words in each partition (must be positive).
-        Length must match the frequency vector.
-    total_corpus_words : float
+    corpus_part_sizes_words : Union[List[float], CorpusPartition]
+        Total number of words in each partition (must be positive), or a
+        prebuilt CorpusPartition. Length must match the frequency vector.
+    total_corpus_words : Optional[float]
         Total number of words in the entire corpus (must be positive).
-        Should equal the sum of corpus_part_sizes_words.
+        Defaults to the sum of the part sizes; must be None when a
+        CorpusPartition is given.
 
     Raises
     ------
-    ValueError
-        If input vectors have different lengths, are empty, or contain invalid values.
+    DispersionError
+        If input vectors have different lengths, are empty, or contain invalid values
+        (see the subclasses for the specific failure kinds).
 
     Notes
     -----
@@ -188,51 +911,123 @@ class CorpusWordAnalyzer:
         Coefficient of variation (standard deviation / mean frequency).
     get_juilland_d() -> Optional[float]
         Juilland's D dispersion index (0 = maximally dispersed, 1 = perfectly even).
+    get_juilland_u() -> Optional[float]
+        Juilland's usage coefficient U (frequency adjusted by D).
     get_carroll_d2() -> Optional[float]
         Carroll's D2 entropy-based dispersion index (0-1 scale).
+    get_carroll_um() -> Optional[float]
+        Carroll's usage coefficient Um (frequency adjusted by D2).
+    get_lyne_d3() -> Optional[float]
+        Lyne's chi-square based D3 (1 = frequencies proportional to part sizes).
     get_roschengren_s_adj() -> Optional[float]
         Rosengren's adjusted S index, weighted by partition sizes.
+    get_roschengren_af() -> Optional[float]
+        Rosengren's adjusted frequency AF.
+    get_zhang_dc() -> Optional[float]
+        Zhang et al.'s distributional consistency DC.
     get_dp() -> Optional[float]
         DP (Deviation of Proportions) index (0-1 scale, 0 = perfect match with expected).
     get_dp_norm() -> Optional[float]
         Normalized DP index, adjusted for minimum possible dispersion.
+    get_dp_nofreq() -> Optional[float]
+        Frequency-independent DP (Gries 2024), min-max scaled for the word's frequency.
     get_kl_divergence() -> Optional[float]
         Kullback-Leibler divergence from expected to observed distribution.
+    get_kl_divergence_norm() -> Optional[float]
+        KL divergence mapped onto [0, 1) as 1 - 2^(-KL), comparable across words.
     get_jsd_dispersion() -> Optional[float]
         Jensen-Shannon divergence based dispersion (1 - normalized JSD).
     get_hellinger_dispersion() -> Optional[float]
         Hellinger distance based dispersion (1 - normalized distance).
+    get_gini_coefficient() -> Optional[float]
+        Gini coefficient of the normalized frequencies.
+    get_shannon_entropy() -> Optional[float]
+        Shannon entropy (nats) of the normalized frequencies.
+    get_pielou_evenness() -> Optional[float]
+        Pielou's evenness J = H / ln(n).
+    get_simpson_index() -> Optional[float]
+        Simpson's index (sum of squared proportions).
+    get_hill_number(q: float) -> Optional[float]
+        Hill number (effective number of parts) of order q; raises NonFiniteValueError for NaN/inf q.
     get_evenness_da() -> Optional[float]
         Evenness index based on pairwise differences (Egbert & Burch DA).
     get_mean_text_frequency_ft() -> Optional[float]
         Mean normalized frequency across partitions (Egbert & Burch FT).
     get_pervasiveness_pt() -> Optional[float]
         Proportion of partitions containing the word (Egbert & Burch PT).
-    calculate_all_metrics() -> DispersionMetrics
-        Compute and return all supported dispersion metrics in a single call.
+    get_theoretical_bounds() -> MetricBounds
+        Most even / most clumped distributions of the word's frequency and their metrics.
+    get_frequency_normalized_metric(metric_name) -> Optional[float]
+        Metric min-max normalized between those bounds (0 = most even, 1 = most clumped).
+    get_goodness_of_fit() -> GoodnessOfFit
+        Chi-square and G-test against the part sizes, with degrees of freedom and p-values.
+    bootstrap_metrics(metrics=None, *, n_resamples=1000, confidence_level=0.95, seed=None, n_threads=None) -> MetricIntervals
+        Percentile bootstrap confidence intervals from resampling the parts with replacement.
+    simulate_null_distribution(metrics=None, *, n_simulations=1000, seed=None, n_threads=None) -> NullDistribution
+        Expected values, z-scores and empirical p-values under a size-proportional multinomial null.
+    calculate_all_metrics(metrics=None) -> DispersionMetrics
+        Compute and return all supported dispersion metrics in a single call,
+        or only the metrics named in `metrics` (the others are None).
 
     Static Methods
     --------------
-    calculate_batch_metrics(frequency_matrix, corpus_part_sizes, total_corpus_words) -> List[DispersionMetrics]
+    calculate_batch_metrics(frequency_matrix, corpus_part_sizes, total_corpus_words, *, metrics=None, words=None, n_threads=None) -> List[DispersionMetrics] | LabelledMetrics
         Efficiently compute metrics for multiple words using parallel processing.
+        The GIL is released during the computation, so other Python threads keep running.
 
         Parameters:
-        - frequency_matrix: List[List[float]], each row is a word's frequency vector
-        - corpus_part_sizes: List[float], partition sizes (same for all words)
-        - total_corpus_words: float, total corpus size
+        - frequency_matrix: List[List[float]], 2-D NumPy array (float64, float32 or int64)
+          or SciPy sparse CSR/CSC matrix; each row is a word's frequency vector.
+          C- or Fortran-contiguous arrays are read in place without copying. Sparse
+          matrices are processed by visiting only non-zero cells (CSC is converted
+          to CSR once); int32 index arrays are read in place, other index dtypes
+          are converted to int64.
+        - corpus_part_sizes: List[float] or CorpusPartition (same for all words)
+        - total_corpus_words: Optional[float], total corpus size (None with a CorpusPartition)
+        - metrics: Optional[Sequence[str]], metric names to compute; unrequested
+          metrics are skipped and left as None ('range' is always computed)
+        - words: Optional[Sequence[str]], one word label per row; each result
+          carries its word (`word` attribute) and row errors name the word
+        - n_threads: Optional[int], run on a dedicated pool of this many threads
+          (1 = sequential, None = the default set with set_num_threads); pools
+          for the last few distinct sizes are kept, older ones are shut down
+
+        Returns list of DispersionMetrics objects, one per input word, or with
+        `words` a LabelledMetrics that can also be indexed by word.
+
+    calculate_batch_metrics_columnar(frequency_matrix, corpus_part_sizes, total_corpus_words, *, metrics=None, words=None, n_threads=None) -> Dict[str, np.ndarray]
+        Same computation as calculate_batch_metrics, returned column-wise.
+        With `words`, the dict also holds a 'word' list.
+
+        Returns a dict mapping each metric name to a 1-D NumPy array with one entry per word:
+        'range' is int32, all other metrics are float64 with NaN for missing values.
+        With `metrics`, only 'range' and the requested columns are present.
+        Suitable for passing straight to pandas.DataFrame or polars.DataFrame.
+
+    calculate_batch_residuals(frequency_matrix, corpus_part_sizes, total_corpus_words=None, *, metrics=None, n_bands=10, n_threads=None) -> List[FrequencyResiduals]
+        Same computation as calculate_batch_metrics, then each metric is fitted
+        against log total frequency across the word list. Returns residuals and
+        within-band percentiles per word; pass the whole vocabulary.
 
-        Returns list of DispersionMetrics objects, one per input word.
+    calculate_batch_word_list(words, frequency_matrix, corpus_part_sizes, total_corpus_words=None, *, score="ft_adjusted_by_da", descending=True, tie_break="frequency", shared_ranks=False, n_threads=None) -> List[WordListEntry]
+        Rank-ordered word list: one word label per matrix row, ranked by the
+        score metric (FT x DA by default, "ft_adjusted_by_pt" for FT x PT).
+        Equal scores are ordered by tie_break: "frequency" (higher total
+        frequency first, then alphabetical), "word" (alphabetical) or "input"
+        (row order). With shared_ranks, tied words get the same rank (1, 2, 2, 4).
 
     calculate_single_metric(frequency_vector, corpus_part_sizes, total_corpus_words, metric_name) -> Optional[float]
         Calculate a single specific metric without creating a full analyzer instance.
 
         Parameters:
         - frequency_vector: List[float], word frequencies per partition
-        - corpus_part_sizes: List[float], partition sizes
-        - total_corpus_words: float, total corpus size
-        - metric_name: str, one of: 'juilland_d', 'carroll_d2', 'dp', 'dp_norm',
-          'kl_divergence', 'jsd_dispersion', 'hellinger_dispersion', 'evenness_da',
-          'mean_text_frequency_ft', 'pervasiveness_pt'
+        - corpus_part_sizes: List[float] or CorpusPartition
+        - total_corpus_words: Optional[float], total corpus size (None with a CorpusPartition)
+        - metric_name: str, one of: 'juilland_d', 'juilland_u', 'carroll_d2', 'carroll_um',
+          'lyne_d3', 'roschengren_af', 'zhang_dc', 'dp', 'dp_norm', 'dp_nofreq',
+          'kl_divergence', 'kl_divergence_norm', 'jsd_dispersion', 'hellinger_dispersion',
+          'gini_coefficient', 'shannon_entropy', 'pielou_evenness', 'simpson_index',
+          'evenness_da', 'mean_text_frequency_ft', 'pervasiveness_pt'
 
         Returns the computed metric value or None if computation fails.
     """
@@ -240,15 +1035,15 @@ class CorpusWordAnalyzer:
     def __init__(
         self,
         v: List[float],
-        corpus_part_sizes_words: List[float],
-        total_corpus_words: float,
+        corpus_part_sizes_words: PartitionLike,
+        total_corpus_words: Optional[float] = None,
     ) -> None:
         """
         Initialize the analyzer with word frequencies and corpus structure.
 
         Raises
         ------
-        PyValueError
+        DispersionError
             If vectors have different lengths, are empty, or contain invalid values.
         """
         ...
@@ -269,14 +1064,34 @@ class CorpusWordAnalyzer:
         """Get Juilland's D dispersion index."""
         ...
 
+    def get_juilland_u(self) -> Optional[float]:
+        """Get Juilland's usage coefficient U."""
+        ...
+
     def get_carroll_d2(self) -> Optional[float]:
         """Get Carroll's D2 entropy-based dispersion."""
         ...
 
+    def get_carroll_um(self) -> Optional[float]:
+        """Get Carroll's usage coefficient Um."""
+        ...
+
+    def get_lyne_d3(self) -> Optional[float]:
+        """Get Lyne's D3 chi-square based dispersion."""
+        ...
+
     def get_roschengren_s_adj(self) -> Optional[float]:
         """Get Rosengren's adjusted S index."""
         ...
 
+    def get_roschengren_af(self) -> Optional[float]:
+        """Get Rosengren's adjusted frequency AF."""
+        ...
+
+    def get_zhang_dc(self) -> Optional[float]:
+        """Get Zhang et al.'s distributional consistency DC."""
+        ...
+
     def get_dp(self) -> Optional[float]:
         """Get DP (Deviation of Proportions) index."""
         ...
@@ -285,10 +1100,18 @@ class CorpusWordAnalyzer:
         """Get normalized DP index."""
         ...
 
+    def get_dp_nofreq(self) -> Optional[float]:
+        """Get frequency-independent DP (Gries 2024)."""
+        ...
+
     def get_kl_divergence(self) -> Optional[float]:
         """Get Kullback-Leibler divergence."""
         ...
 
+    def get_kl_divergence_norm(self) -> Optional[float]:
+        """Get KL divergence normalized as 1 - 2^(-KL)."""
+        ...
+
     def get_jsd_dispersion(self) -> Optional[float]:
         """Get Jensen-Shannon divergence based dispersion."""
         ...
@@ -297,6 +1120,32 @@ class CorpusWordAnalyzer:
         """Get Hellinger distance based dispersion."""
         ...
 
+    def get_gini_coefficient(self) -> Optional[float]:
+        """Get the Gini coefficient of the normalized frequencies."""
+        ...
+
+    def get_shannon_entropy(self) -> Optional[float]:
+        """Get the Shannon entropy (nats) of the normalized frequencies."""
+        ...
+
+    def get_pielou_evenness(self) -> Optional[float]:
+        """Get Pielou's evenness (H / ln n)."""
+        ...
+
+    def get_simpson_index(self) -> Optional[float]:
+        """Get Simpson's index (sum of squared proportions)."""
+        ...
+
+    def get_hill_number(self, q: float) -> Optional[float]:
+        """
+        Get the Hill number of order q.
+
+        q = 0 counts occupied parts, q = 1 is exp(Shannon entropy), q = 2 is
+        the inverse Simpson index; higher q weights dominant parts more.
+        Raises NonFiniteValueError if q is NaN or infinite.
+        """
+        ...
+
     def get_evenness_da(self) -> Optional[float]:
         """Get evenness index (DA) based on pairwise differences."""
         ...
@@ -321,35 +1170,228 @@ class CorpusWordAnalyzer:
         """Get the relative partition sizes (s vector)."""
         ...
 
+    def get_partition(self) -> CorpusPartition:
+        """Get the (shared) corpus partition used by this analyzer."""
+        ...
+
+    def get_theoretical_bounds(self) -> MetricBounds:
+        """Get the most even / most clumped distributions and their metrics."""
+        ...
+
+    def get_frequency_normalized_metric(self, metric_name: str) -> Optional[float]:
+        """
+        Get a metric min-max normalized for the word's frequency.
+
+        Raises
+        ------
+        UnknownMetricError
+            If metric_name is unknown.
+        """
+        ...
+
+    def get_goodness_of_fit(self) -> GoodnessOfFit:
+        """Get the chi-square and G-test of the word against the part sizes."""
+        ...
+
+    def bootstrap_metrics(
+        self,
+        metrics: Optional[Sequence[str]] = None,
+        *,
+        n_resamples: int = 1000,
+        confidence_level: float = 0.95,
+        seed: Optional[int] = None,
+        n_threads: Optional[int] = None,
+    ) -> MetricIntervals:
+        """
+        Bootstrap percentile confidence intervals for the selected metrics.
+
+        Parts are resampled with replacement ``n_resamples`` times. With
+        ``seed=None`` a fresh seed is drawn and reported as ``result.seed``.
+        Resamples run in parallel with the GIL released.
+
+        Raises
+        ------
+        InvalidParameterError
+            If n_resamples is 0 or confidence_level is not strictly between 0 and 1.
+        UnknownMetricError
+            If a metric name is unknown.
+        """
+        ...
+
+    def simulate_null_distribution(
+        self,
+        metrics: Optional[Sequence[str]] = None,
+        *,
+        n_simulations: int = 1000,
+        seed: Optional[int] = None,
+        n_threads: Optional[int] = None,
+    ) -> NullDistribution:
+        """
+        Compare the selected metrics with a simulated multinomial null.
+
+        The word's frequency is scattered over the parts proportionally to
+        their sizes ``n_simulations`` times. With ``seed=None`` a fresh seed
+        is drawn and reported as ``result.seed``. Simulations run in parallel
+        with the GIL released.
+
+        Raises
+        ------
+        InvalidParameterError
+            If n_simulations is 0.
+        UnknownMetricError
+            If a metric name is unknown.
+        """
+        ...
+
     def get_normalized_frequencies(self) -> List[float]:
         """Get the normalized frequencies (p vector)."""
         ...
 
-    def calculate_all_metrics(self) -> DispersionMetrics:
-        """Calculate and return all dispersion metrics."""
+    def calculate_all_metrics(
+        self, metrics: Optional[Sequence[str]] = None
+    ) -> DispersionMetrics:
+        """
+        Calculate and return all dispersion metrics, or only those named in `metrics`.
+
+        Raises
+        ------
+        UnknownMetricError
+            If a name in `metrics` is unknown.
+        """
         ...
 
+    @overload
     @staticmethod
     def calculate_batch_metrics(
-        frequency_matrix: List[List[float]],
-        corpus_part_sizes: List[float],
-        total_corpus_words: float,
-    ) -> List[DispersionMetrics]:
+        frequency_matrix: Union[List[List[float]], np.ndarray, "scipy.sparse.spmatrix"],
+        corpus_part_sizes: PartitionLike,
+        total_corpus_words: Optional[float] = None,
+        *,
+        metrics: Optional[Sequence[str]] = None,
+        words: None = None,
+        n_threads: Optional[int] = None,
+    ) -> List[DispersionMetrics]: ...
+    @overload
+    @staticmethod
+    def calculate_batch_metrics(
+        frequency_matrix: Union[List[List[float]], np.ndarray, "scipy.sparse.spmatrix"],
+        corpus_part_sizes: PartitionLike,
+        total_corpus_words: Optional[float] = None,
+        *,
+        metrics: Optional[Sequence[str]] = None,
+        words: Sequence[str],
+        n_threads: Optional[int] = None,
+    ) -> LabelledMetrics:
         """
         Calculate metrics for multiple words using parallel processing.
 
+        With ``words`` (one label per row) the results are returned as a
+        LabelledMetrics: every result carries its ``word`` and can be looked
+        up by it, and row errors also report ``err.word``.
+
+        Raises
+        ------
+        LengthMismatchError
+            If there is not one word per matrix row.
+        DispersionError
+            If matrix rows have inconsistent lengths or invalid values.
+            The index of the offending row is available as ``err.row``.
+        """
+        ...
+
+    @staticmethod
+    def calculate_batch_metrics_columnar(
+        frequency_matrix: Union[List[List[float]], np.ndarray, "scipy.sparse.spmatrix"],
+        corpus_part_sizes: PartitionLike,
+        total_corpus_words: Optional[float] = None,
+        *,
+        metrics: Optional[Sequence[str]] = None,
+        words: Optional[Sequence[str]] = None,
+        n_threads: Optional[int] = None,
+    ) -> Dict[str, np.ndarray]:
+        """
+        Calculate metrics for multiple words and return them column-wise.
+
+        Returns
+        -------
+        Dict[str, np.ndarray]
+            Metric name -> 1-D array with one entry per word ('range' as int32,
+            other metrics as float64 with NaN where a metric is undefined).
+            With ``words``, 'word' maps to the list of word labels.
+
+        Raises
+        ------
+        DispersionError
+            If matrix rows have inconsistent lengths or invalid values.
+            The index of the offending row is available as ``err.row``.
+        """
+        ...
+
+    @staticmethod
+    def calculate_batch_residuals(
+        frequency_matrix: Union[List[List[float]], np.ndarray, "scipy.sparse.spmatrix"],
+        corpus_part_sizes: PartitionLike,
+        total_corpus_words: Optional[float] = None,
+        *,
+        metrics: Optional[Sequence[str]] = None,
+        n_bands: int = 10,
+        n_threads: Optional[int] = None,
+    ) -> List[FrequencyResiduals]:
+        """
+        Frequency-adjusted dispersion across a word list.
+
+        Each selected metric is fitted against the log of the row totals with
+        ``n_bands`` equal-count frequency bands, so the rows should cover a
+        whole vocabulary (or at least a broad frequency range).
+
+        Raises
+        ------
+        InvalidParameterError
+            If n_bands is 0.
+        DispersionError
+            If matrix rows have inconsistent lengths or invalid values.
+            The index of the offending row is available as ``err.row``.
+        """
+        ...
+
+    @staticmethod
+    def calculate_batch_word_list(
+        words: Sequence[str],
+        frequency_matrix: Union[List[List[float]], np.ndarray, "scipy.sparse.spmatrix"],
+        corpus_part_sizes: PartitionLike,
+        total_corpus_words: Optional[float] = None,
+        *,
+        score: str = "ft_adjusted_by_da",
+        descending: bool = True,
+        tie_break: str = "frequency",
+        shared_ranks: bool = False,
+        n_threads: Optional[int] = None,
+    ) -> List[WordListEntry]:
+        """
+        Rank words by lexical prevalence and return the list in rank order.
+
+        ``words[i]`` labels row ``i`` of the matrix. Set ``descending=False``
+        for metrics where smaller values mean more even dispersion (e.g. 'dp').
+
         Raises
         ------
-        PyValueError
+        LengthMismatchError
+            If there is not one word per matrix row.
+        UnknownMetricError
+            If score is not a metric name.
+        InvalidParameterError
+            If tie_break is not 'frequency', 'word' or 'input'.
+        DispersionError
             If matrix rows have inconsistent lengths or invalid values.
+            The index of the offending row is available as ``err.row``.
         """
         ...
 
     @staticmethod
     def calculate_single_metric(
         frequency_vector: List[float],
-        corpus_part_sizes: List[float],
-        total_corpus_words: float,
+        corpus_part_sizes: PartitionLike,
+        total_corpus_words: Optional[float],
         metric_name: str,
     ) -> Optional[float]:
         """
@@ -357,7 +1399,90 @@ class CorpusWordAnalyzer:
 
         Raises
         ------
-        PyValueError
-            If metric_name is unknown or inputs are invalid.
+        UnknownMetricError
+            If metric_name is unknown.
+        DispersionError
+            If inputs are invalid.
+        """
+        ...
+
+    @staticmethod
+    def calculate_batch_bootstrap(
+        frequency_matrix: Union[List[List[float]], np.ndarray, "scipy.sparse.spmatrix"],
+        corpus_part_sizes: PartitionLike,
+        total_corpus_words: Optional[float] = None,
+        *,
+        metrics: Optional[Sequence[str]] = None,
+        n_resamples: int = 1000,
+        confidence_level: float = 0.95,
+        seed: Optional[int] = None,
+        n_threads: Optional[int] = None,
+    ) -> List[MetricIntervals]:
+        """
+        Bootstrap confidence intervals for multiple words in parallel.
+
+        A single seed drives the batch; every row uses its own random streams
+        and is reproducible from the seed reported in its result.
+        ``frequency_matrix`` is read like in ``calculate_batch_metrics``:
+        NumPy arrays and SciPy CSR/CSC matrices are borrowed without copying.
+
+        Raises
+        ------
+        InvalidParameterError
+            If n_resamples is 0 or confidence_level is not strictly between 0 and 1.
+        DispersionError
+            If matrix rows have inconsistent lengths or invalid values.
+            The index of the offending row is available as ``err.row``.
+        """
+        ...
+
+    @staticmethod
+    def calculate_batch_null_distribution(
+        frequency_matrix: Union[List[List[float]], np.ndarray, "scipy.sparse.spmatrix"],
+        corpus_part_sizes: PartitionLike,
+        total_corpus_words: Optional[float] = None,
+        *,
+        metrics: Optional[Sequence[str]] = None,
+        n_simulations: int = 1000,
+        seed: Optional[int] = None,
+        n_threads: Optional[int] = None,
+    ) -> List[NullDistribution]:
+        """
+        Simulated null distributions for multiple words in parallel.
+
+        A single seed drives the batch; every row uses its own random streams
+        and is reproducible from the seed reported in its result.
+        ``frequency_matrix`` is read like in ``calculate_batch_metrics``:
+        NumPy arrays and SciPy CSR/CSC matrices are borrowed without copying.
+
+        Raises
+        ------
+        InvalidParameterError
+            If n_simulations is 0.
+        DispersionError
+            If matrix rows have inconsistent lengths or invalid values.
+            The index of the offending row is available as ``err.row``.
+        """
+        ...
+
+    @staticmethod
+    def calculate_batch_goodness_of_fit(
+        frequency_matrix: Union[List[List[float]], np.ndarray, "scipy.sparse.spmatrix"],
+        corpus_part_sizes: PartitionLike,
+        total_corpus_words: Optional[float] = None,
+        *,
+        n_threads: Optional[int] = None,
+    ) -> List[GoodnessOfFit]:
+        """
+        Chi-square and G-tests for multiple words in parallel.
+
+        ``frequency_matrix`` is read like in ``calculate_batch_metrics``:
+        NumPy arrays and SciPy CSR/CSC matrices are borrowed without copying.
+
+        Raises
+        ------
+        DispersionError
+            If matrix rows have inconsistent lengths or invalid values.
+            The index of the offending row is available as ``err.row``.
         """
         ...
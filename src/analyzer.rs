@@ -1,24 +1,29 @@
 //! Core analyzer implementation for corpus dispersion metrics
 
-use crate::metrics::DispersionMetrics;
-use pyo3::prelude::*;
+use crate::bootstrap::{BootstrapConfig, MetricIntervals, bootstrap_word};
+use crate::error::{DispersionError, Result, validate_frequencies};
+use crate::matrix::{FrequencyMatrix, FrequencyRows, FrequencyValue};
+use crate::metrics::{DispersionMetrics, LabelledMetrics, MetricBounds, MetricSet};
+use crate::parallel::run_batch;
+use crate::partition::{CorpusPartition, dp_nofreq};
+use crate::residuals::{FrequencyResiduals, ResidualConfig, frequency_residuals};
+use crate::significance::{GoodnessOfFit, NullDistribution, SimulationConfig, simulate_null};
+use crate::sparse::{CsrMatrix, SparseIndex, sparse_row_metrics, sum_abs_pairwise_diff_sorted};
+use crate::wordlist::{WordListConfig, WordListEntry, build_word_list};
 use std::f64::consts::LN_2;
+use std::sync::Arc;
 
 /// Analyzer for computing lexical dispersion metrics from corpus word frequencies
-#[pyclass]
+#[derive(Debug, Clone)]
 pub struct CorpusWordAnalyzer {
     /// Word frequency vector across partitions
     v: Vec<f64>,
-    /// Corpus partition sizes in words
-    corpus_part_sizes_words: Vec<f64>,
-    /// Total corpus size in words
-    total_corpus_words: f64,
+    /// Shared corpus partition (part sizes, s vector and derived values)
+    partition: Arc<CorpusPartition>,
     /// Number of partitions
     n: usize,
     /// Total frequency of the word
     f: f64,
-    /// Relative partition sizes (s vector)
-    s: Vec<f64>,
     /// Normalized frequencies (p vector)
     p: Vec<f64>,
     // Cached values for efficiency
@@ -26,10 +31,9 @@ pub struct CorpusWordAnalyzer {
     cached_mean_v: Option<f64>,
     cached_mean_p: Option<f64>,
     cached_sum_p: Option<f64>,
-    cached_min_s: Option<f64>,
+    cached_entropy: Option<f64>,
 }
 
-#[pymethods]
 impl CorpusWordAnalyzer {
     /// Create a new corpus word analyzer
     ///
@@ -41,64 +45,56 @@ impl CorpusWordAnalyzer {
     ///
     /// # Errors
     ///
-    /// Returns `PyValueError` if:
+    /// Returns `DispersionError` if:
     /// - Input vectors have different lengths
     /// - Input vectors are empty
     /// - Total corpus words is not positive
-    #[new]
-    #[allow(clippy::many_single_char_names)] // Mathematical notation is standard in corpus linguistics
+    /// - Any frequency or part size is negative, NaN or infinite
     pub fn new(
         v: Vec<f64>,
         corpus_part_sizes_words: Vec<f64>,
         total_corpus_words: f64,
-    ) -> PyResult<Self> {
-        if v.len() != corpus_part_sizes_words.len() {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Frequency vector and part sizes vector must have the same length.",
-            ));
-        }
-
-        if v.is_empty() {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Input vectors cannot be empty.",
-            ));
-        }
+    ) -> Result<Self> {
+        let partition = CorpusPartition::new(corpus_part_sizes_words, total_corpus_words)?;
+        Self::with_partition(v, Arc::new(partition))
+    }
 
-        if total_corpus_words <= 0.0 {
-            return Err(pyo3::exceptions::PyValueError::new_err(
-                "Total corpus words must be positive.",
-            ));
-        }
+    /// Create an analyzer over a shared, precomputed corpus partition
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError` if the frequency vector length differs from
+    /// the number of parts, or any frequency is negative, NaN or infinite.
+    pub fn with_partition(v: Vec<f64>, partition: Arc<CorpusPartition>) -> Result<Self> {
+        validate_frequencies(&v, partition.n_parts())?;
+        Ok(Self::from_validated(v, partition))
+    }
 
+    // Build an analyzer from a frequency vector already checked against the partition
+    #[allow(clippy::many_single_char_names)] // Mathematical notation is standard in corpus linguistics
+    fn from_validated(v: Vec<f64>, partition: Arc<CorpusPartition>) -> Self {
         let n = v.len();
         let f = v.iter().sum();
 
-        // Pre-compute s and p vectors
-        let s: Vec<f64> = corpus_part_sizes_words
-            .iter()
-            .map(|&size| size / total_corpus_words)
-            .collect();
-
+        // Pre-compute p vector (s lives in the shared partition)
         let p: Vec<f64> = v
             .iter()
-            .zip(corpus_part_sizes_words.iter())
+            .zip(partition.part_sizes())
             .map(|(&freq, &size)| if size > 0.0 { freq / size } else { 0.0 })
             .collect();
 
-        Ok(Self {
+        Self {
             v,
-            corpus_part_sizes_words,
-            total_corpus_words,
+            partition,
             n,
             f,
-            s,
             p,
             cached_range: None,
             cached_mean_v: None,
             cached_mean_p: None,
             cached_sum_p: None,
-            cached_min_s: None,
-        })
+            cached_entropy: None,
+        }
     }
 
     // Optimized range calculation with caching
@@ -168,35 +164,68 @@ impl CorpusWordAnalyzer {
         }
 
         // Use natural log and convert to log2 for efficiency
-        let entropy = self
-            .p
-            .iter()
-            .map(|&p_i| {
-                let norm_prop = p_i / sum_p;
-                if norm_prop > 1e-12 {
-                    -norm_prop * norm_prop.ln()
-                } else {
-                    0.0
-                }
-            })
-            .sum::<f64>();
-
+        let entropy = self.get_entropy();
         let log2_n = (self.n as f64).ln() / LN_2;
         Some(entropy / (log2_n * LN_2))
     }
 
+    /// Juilland's usage coefficient U = f × D
+    pub fn get_juilland_u(&mut self) -> Option<f64> {
+        let f = self.f;
+        self.get_juilland_d().map(|d| f * d)
+    }
+
+    /// Carroll's usage coefficient Um = f·D2 + (1 − D2)·f/n
+    #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+    pub fn get_carroll_um(&mut self) -> Option<f64> {
+        let f = self.f;
+        let n = self.n as f64;
+        self.get_carroll_d2()
+            .map(|d2| f.mul_add(d2, (1.0 - d2) * f / n))
+    }
+
+    /// Lyne's D3 = 1 − χ² / 4f, with expected frequencies f·s_i
+    #[must_use]
+    pub fn get_lyne_d3(&self) -> Option<f64> {
+        if self.f == 0.0 {
+            return Some(0.0);
+        }
+        Some(1.0 - self.chi_square() / (4.0 * self.f))
+    }
+
     #[must_use]
     pub fn get_roschengren_s_adj(&self) -> Option<f64> {
+        if self.f == 0.0 {
+            return Some(0.0);
+        }
+        self.get_roschengren_af().map(|af| af / self.f)
+    }
+
+    /// Rosengren's adjusted frequency AF = (Σ sqrt(s_i · v_i))²
+    #[must_use]
+    pub fn get_roschengren_af(&self) -> Option<f64> {
         if self.f == 0.0 {
             return Some(0.0);
         }
         let sum_sqrt = self
-            .s
+            .partition
+            .sqrt_s()
             .iter()
             .zip(self.v.iter())
-            .map(|(&s_i, &v_i)| (s_i * v_i).sqrt())
+            .map(|(&sqrt_s_i, &v_i)| sqrt_s_i * v_i.sqrt())
             .sum::<f64>();
-        Some((sum_sqrt * sum_sqrt) / self.f)
+        Some(sum_sqrt * sum_sqrt)
+    }
+
+    /// Zhang et al.'s distributional consistency DC = (Σ√p_i / n)² / (Σp_i / n)
+    #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+    pub fn get_zhang_dc(&mut self) -> Option<f64> {
+        let sum_p = self.get_sum_p();
+        if sum_p.abs() < 1e-12 {
+            return Some(0.0);
+        }
+        let sum_sqrt = self.p.iter().map(|&p_i| p_i.sqrt()).sum::<f64>();
+        Some((sum_sqrt * sum_sqrt) / (self.n as f64 * sum_p))
     }
 
     #[must_use]
@@ -208,7 +237,7 @@ impl CorpusWordAnalyzer {
         let sum_abs_diff = self
             .v
             .iter()
-            .zip(self.s.iter())
+            .zip(self.partition.s())
             .map(|(&v_i, &s_i)| (v_i / self.f - s_i).abs())
             .sum::<f64>();
 
@@ -217,7 +246,7 @@ impl CorpusWordAnalyzer {
 
     pub fn get_dp_norm(&mut self) -> Option<f64> {
         let dp = self.get_dp()?;
-        let min_s = self.get_min_s();
+        let min_s = self.partition.min_s();
         let denom = 1.0 - min_s;
         if denom.abs() < 1e-12 {
             return Some(0.0);
@@ -225,22 +254,41 @@ impl CorpusWordAnalyzer {
         Some(dp / denom)
     }
 
+    /// Frequency-independent DP (Gries 2024)
+    ///
+    /// Observed DP min–max scaled between the smallest DP reachable by `f`
+    /// tokens (most even allocation given `s`) and the largest (all tokens in
+    /// the smallest part): 0 = as even as `f` allows, 1 = maximally clumped.
+    /// `None` when both bounds coincide.
+    #[must_use]
+    pub fn get_dp_nofreq(&self) -> Option<f64> {
+        dp_nofreq(&self.partition, self.get_dp()?, self.f)
+    }
+
     #[must_use]
     pub fn get_kl_divergence(&self) -> Option<f64> {
         if self.f == 0.0 {
             return Some(0.0);
         }
         let mut kl = 0.0;
-        for (&v_i, &s_i) in self.v.iter().zip(self.s.iter()) {
+        let partition = &self.partition;
+        for ((&v_i, &s_i), &ln_s_i) in self.v.iter().zip(partition.s()).zip(partition.ln_s()) {
             let p = if self.f > 0.0 { v_i / self.f } else { 0.0 };
-            let q = s_i;
-            if p > 0.0 && q > 0.0 {
-                kl += p * (p / q).ln() / LN_2;
+            if p > 0.0 && s_i > 0.0 {
+                kl += p * (p.ln() - ln_s_i) / LN_2;
             }
         }
         Some(kl)
     }
 
+    /// KL divergence mapped onto [0, 1) as 1 − 2^(−KL) (Gries 2024)
+    ///
+    /// 0 = distributed exactly like the part sizes; approaches 1 as the word clumps.
+    #[must_use]
+    pub fn get_kl_divergence_norm(&self) -> Option<f64> {
+        self.get_kl_divergence().map(|kl| 1.0 - (-kl).exp2())
+    }
+
     #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
     pub fn get_evenness_da(&mut self) -> Option<f64> {
         if self.n == 0 {
@@ -259,13 +307,10 @@ impl CorpusWordAnalyzer {
             return Some(if all_same { 1.0 } else { 0.0 });
         }
 
-        // Optimized pairwise difference calculation
-        let mut sum_abs_diff: f64 = 0.0;
-        for i in 0..self.n {
-            for j in (i + 1)..self.n {
-                sum_abs_diff += (self.p[i] - self.p[j]).abs();
-            }
-        }
+        // Σ_{i<j} |p_i − p_j| in O(n log n) from the sorted values
+        let mut sorted: Vec<f64> = self.p.clone();
+        sorted.sort_by(f64::total_cmp);
+        let sum_abs_diff: f64 = sum_abs_pairwise_diff_sorted(&sorted);
 
         let num_pairs = (self.n * (self.n - 1)) / 2;
         if num_pairs == 0 {
@@ -284,7 +329,7 @@ impl CorpusWordAnalyzer {
             return Some(0.0);
         }
         let p_dist: Vec<f64> = self.v.iter().map(|&v_i| v_i / self.f).collect();
-        let q_dist: &Vec<f64> = &self.s;
+        let q_dist: &[f64] = self.partition.s();
         let m_dist: Vec<f64> = p_dist
             .iter()
             .zip(q_dist.iter())
@@ -313,7 +358,7 @@ impl CorpusWordAnalyzer {
             return Some(0.0);
         }
         let p_dist: Vec<f64> = self.v.iter().map(|&v_i| v_i / self.f).collect();
-        let q_dist: &Vec<f64> = &self.s;
+        let q_dist: &[f64] = self.partition.s();
         let mut bc: f64 = 0.0;
         for i in 0..self.n {
             bc += (p_dist[i] * q_dist[i]).sqrt();
@@ -323,6 +368,75 @@ impl CorpusWordAnalyzer {
         Some(1.0 - hellinger_distance)
     }
 
+    /// Gini coefficient of the normalized frequencies `p`
+    ///
+    /// Mean absolute difference over all ordered pairs divided by twice the
+    /// mean: 0 = perfectly even, (n − 1)/n = all occurrences in one part.
+    #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+    pub fn get_gini_coefficient(&mut self) -> Option<f64> {
+        let sum_p = self.get_sum_p();
+        if self.n <= 1 || sum_p.abs() < 1e-12 {
+            return Some(0.0);
+        }
+        let mut sorted = self.p.clone();
+        sorted.sort_by(f64::total_cmp);
+        Some(sum_abs_pairwise_diff_sorted(&sorted) / (self.n as f64 * sum_p))
+    }
+
+    /// Shannon entropy (in nats) of the proportions p_i / Σp
+    pub fn get_shannon_entropy(&mut self) -> Option<f64> {
+        Some(self.get_entropy())
+    }
+
+    /// Pielou's evenness J = H / ln n
+    ///
+    /// Over `p` this is the same quantity as Carroll's D2.
+    pub fn get_pielou_evenness(&mut self) -> Option<f64> {
+        self.get_carroll_d2()
+    }
+
+    /// Simpson's index λ = Σ (p_i / Σp)², the chance two draws hit the same part
+    pub fn get_simpson_index(&mut self) -> Option<f64> {
+        let sum_p = self.get_sum_p();
+        if sum_p.abs() < 1e-12 {
+            return Some(0.0);
+        }
+        Some(self.p.iter().map(|&p_i| (p_i / sum_p).powi(2)).sum())
+    }
+
+    /// Hill number (effective number of parts) of order `q`
+    ///
+    /// ᵠD = (Σ π_i^q)^(1/(1−q)) over the occupied parts, with π_i = p_i / Σp;
+    /// q = 0 is the range, q = 1 is exp(H) and q = 2 is 1/λ. A word with zero
+    /// frequency yields 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::NonFiniteValue` if `q` is NaN or infinite.
+    pub fn get_hill_number(&mut self, q: f64) -> Result<Option<f64>> {
+        if !q.is_finite() {
+            return Err(DispersionError::NonFiniteValue {
+                field: "Hill number order q",
+                index: None,
+                value: q,
+            });
+        }
+        let sum_p = self.get_sum_p();
+        if sum_p.abs() < 1e-12 {
+            return Ok(Some(0.0));
+        }
+        if (q - 1.0).abs() < 1e-12 {
+            return Ok(Some(self.get_entropy().exp()));
+        }
+        let sum_pow = self
+            .p
+            .iter()
+            .filter(|&&p_i| p_i > 0.0)
+            .map(|&p_i| (p_i / sum_p).powf(q))
+            .sum::<f64>();
+        Ok(Some(sum_pow.powf(1.0 / (1.0 - q))))
+    }
+
     pub fn get_mean_text_frequency_ft(&mut self) -> Option<f64> {
         if self.n == 0 {
             return None;
@@ -340,18 +454,23 @@ impl CorpusWordAnalyzer {
 
     // Getter methods for original corpus data (useful for debugging and analysis)
     #[must_use]
-    pub const fn get_corpus_part_sizes(&self) -> &Vec<f64> {
-        &self.corpus_part_sizes_words
+    pub fn get_corpus_part_sizes(&self) -> &[f64] {
+        self.partition.part_sizes()
     }
 
     #[must_use]
-    pub const fn get_total_corpus_words(&self) -> f64 {
-        self.total_corpus_words
+    pub fn get_total_corpus_words(&self) -> f64 {
+        self.partition.total_corpus_words()
     }
 
     #[must_use]
-    pub const fn get_relative_partition_sizes(&self) -> &Vec<f64> {
-        &self.s
+    pub fn get_relative_partition_sizes(&self) -> &[f64] {
+        self.partition.s()
+    }
+
+    #[must_use]
+    pub const fn get_partition(&self) -> &Arc<CorpusPartition> {
+        &self.partition
     }
 
     #[must_use]
@@ -360,22 +479,123 @@ impl CorpusWordAnalyzer {
     }
 
     pub fn calculate_all_metrics(&mut self) -> DispersionMetrics {
-        let ft: Option<f64> = self.get_mean_text_frequency_ft();
-        let pt: Option<f64> = self.get_pervasiveness_pt();
-        let da: Option<f64> = self.get_evenness_da();
+        self.calculate_metrics(MetricSet::ALL)
+    }
+
+    /// Calculate only the metrics in `selection`
+    ///
+    /// Unselected metrics are not computed and are left as `None`; `range`
+    /// is always filled in.
+    pub fn calculate_metrics(&mut self, selection: MetricSet) -> DispersionMetrics {
+        let ft: Option<f64> = if selection.intersects(
+            MetricSet::MEAN_TEXT_FREQUENCY_FT
+                | MetricSet::FT_ADJUSTED_BY_PT
+                | MetricSet::FT_ADJUSTED_BY_DA,
+        ) {
+            self.get_mean_text_frequency_ft()
+        } else {
+            None
+        };
+        let pt: Option<f64> =
+            if selection.intersects(MetricSet::PERVASIVENESS_PT | MetricSet::FT_ADJUSTED_BY_PT) {
+                self.get_pervasiveness_pt()
+            } else {
+                None
+            };
+        let da: Option<f64> =
+            if selection.intersects(MetricSet::EVENNESS_DA | MetricSet::FT_ADJUSTED_BY_DA) {
+                self.get_evenness_da()
+            } else {
+                None
+            };
 
         DispersionMetrics {
+            word: None,
             range: self.get_range(),
-            sd_population: self.get_sd_population(),
-            vc_population: self.get_vc_population(),
-            juilland_d: self.get_juilland_d(),
-            carroll_d2: self.get_carroll_d2(),
-            roschengren_s_adj: self.get_roschengren_s_adj(),
-            dp: self.get_dp(),
-            dp_norm: self.get_dp_norm(),
-            kl_divergence: self.get_kl_divergence(),
-            jsd_dispersion: self.get_jsd_dispersion(),
-            hellinger_dispersion: self.get_hellinger_dispersion(),
+            sd_population: selection
+                .contains(MetricSet::SD_POPULATION)
+                .then(|| self.get_sd_population())
+                .flatten(),
+            vc_population: selection
+                .contains(MetricSet::VC_POPULATION)
+                .then(|| self.get_vc_population())
+                .flatten(),
+            juilland_d: selection
+                .contains(MetricSet::JUILLAND_D)
+                .then(|| self.get_juilland_d())
+                .flatten(),
+            juilland_u: selection
+                .contains(MetricSet::JUILLAND_U)
+                .then(|| self.get_juilland_u())
+                .flatten(),
+            carroll_d2: selection
+                .contains(MetricSet::CARROLL_D2)
+                .then(|| self.get_carroll_d2())
+                .flatten(),
+            carroll_um: selection
+                .contains(MetricSet::CARROLL_UM)
+                .then(|| self.get_carroll_um())
+                .flatten(),
+            lyne_d3: selection
+                .contains(MetricSet::LYNE_D3)
+                .then(|| self.get_lyne_d3())
+                .flatten(),
+            roschengren_s_adj: selection
+                .contains(MetricSet::ROSCHENGREN_S_ADJ)
+                .then(|| self.get_roschengren_s_adj())
+                .flatten(),
+            roschengren_af: selection
+                .contains(MetricSet::ROSCHENGREN_AF)
+                .then(|| self.get_roschengren_af())
+                .flatten(),
+            zhang_dc: selection
+                .contains(MetricSet::ZHANG_DC)
+                .then(|| self.get_zhang_dc())
+                .flatten(),
+            dp: selection
+                .contains(MetricSet::DP)
+                .then(|| self.get_dp())
+                .flatten(),
+            dp_norm: selection
+                .contains(MetricSet::DP_NORM)
+                .then(|| self.get_dp_norm())
+                .flatten(),
+            dp_nofreq: selection
+                .contains(MetricSet::DP_NOFREQ)
+                .then(|| self.get_dp_nofreq())
+                .flatten(),
+            kl_divergence: selection
+                .contains(MetricSet::KL_DIVERGENCE)
+                .then(|| self.get_kl_divergence())
+                .flatten(),
+            kl_divergence_norm: selection
+                .contains(MetricSet::KL_DIVERGENCE_NORM)
+                .then(|| self.get_kl_divergence_norm())
+                .flatten(),
+            jsd_dispersion: selection
+                .contains(MetricSet::JSD_DISPERSION)
+                .then(|| self.get_jsd_dispersion())
+                .flatten(),
+            hellinger_dispersion: selection
+                .contains(MetricSet::HELLINGER_DISPERSION)
+                .then(|| self.get_hellinger_dispersion())
+                .flatten(),
+            gini_coefficient: selection
+                .contains(MetricSet::GINI_COEFFICIENT)
+                .then(|| self.get_gini_coefficient())
+                .flatten(),
+            shannon_entropy: selection
+                .contains(MetricSet::SHANNON_ENTROPY)
+                .then(|| self.get_shannon_entropy())
+                .flatten(),
+            pielou_evenness: selection
+                .contains(MetricSet::PIELOU_EVENNESS)
+                .then(|| self.get_pielou_evenness())
+                .flatten(),
+            simpson_index: selection
+                .contains(MetricSet::SIMPSON_INDEX)
+                .then(|| self.get_simpson_index())
+                .flatten(),
             mean_text_frequency_ft: ft,
             pervasiveness_pt: pt,
             evenness_da: da,
@@ -388,6 +608,105 @@ impl CorpusWordAnalyzer {
                 _ => None,
             },
         }
+        .masked(selection)
+    }
+
+    /// Most even and most clumped distributions of this word's total frequency
+    /// with every metric computed for both
+    ///
+    /// See `CorpusPartition::most_even_distribution` and
+    /// `CorpusPartition::most_clumped_distribution`.
+    #[must_use]
+    pub fn get_theoretical_bounds(&self) -> MetricBounds {
+        let most_even = self.partition.most_even_distribution(self.f);
+        let most_clumped = self.partition.most_clumped_distribution(self.f);
+        let metrics_of = |v: &[f64]| {
+            Self::from_validated(v.to_vec(), Arc::clone(&self.partition)).calculate_all_metrics()
+        };
+        MetricBounds {
+            even_metrics: metrics_of(&most_even),
+            clumped_metrics: metrics_of(&most_clumped),
+            most_even,
+            most_clumped,
+        }
+    }
+
+    /// Metric `name` min–max normalized for this word's frequency
+    ///
+    /// 0 = as even as the frequency allows, 1 = maximally clumped, whatever
+    /// the orientation of the raw metric (see `MetricBounds::normalize`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::UnknownMetric` if the name is not recognised.
+    pub fn get_frequency_normalized_metric(&mut self, name: &str) -> Result<Option<f64>> {
+        let Some(value) = self.calculate_all_metrics().get(name)? else {
+            return Ok(None);
+        };
+        self.get_theoretical_bounds().normalize(name, value)
+    }
+
+    /// Chi-square and G-test of the word's frequencies against the part sizes
+    ///
+    /// Expected frequencies are `f · s_i`; parts of size zero are left out and
+    /// the degrees of freedom are the remaining parts minus one. A word with
+    /// zero frequency has both statistics at 0 (p-value 1).
+    #[must_use]
+    pub fn get_goodness_of_fit(&self) -> GoodnessOfFit {
+        let s = self.partition.s();
+        let g_statistic = if self.f == 0.0 {
+            0.0
+        } else {
+            2.0 * s
+                .iter()
+                .zip(self.v.iter())
+                .filter(|&(&s_i, &v_i)| s_i > 0.0 && v_i > 0.0)
+                .map(|(&s_i, &v_i)| v_i * (v_i / (self.f * s_i)).ln())
+                .sum::<f64>()
+        };
+        let sized_parts = s.iter().filter(|&&s_i| s_i > 0.0).count();
+        GoodnessOfFit::new(
+            self.chi_square(),
+            g_statistic,
+            sized_parts.saturating_sub(1),
+        )
+    }
+
+    /// Bootstrap percentile confidence intervals for the metrics in `selection`
+    ///
+    /// Parts are resampled with replacement `config.n_resamples` times and
+    /// the metrics recomputed on each resample; the estimate is the metrics
+    /// of the observed frequencies. Resamples run in parallel.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::InvalidParameter` for an invalid `config`.
+    pub fn bootstrap_metrics(
+        &mut self,
+        selection: MetricSet,
+        config: &BootstrapConfig,
+    ) -> Result<MetricIntervals> {
+        config.validate()?;
+        Ok(self.bootstrap_row(selection, config, config.resolve_seed(), 0))
+    }
+
+    /// Locate the metrics in `selection` in their simulated null distribution
+    ///
+    /// The null scatters the word's `f` tokens (rounded) over the parts by
+    /// multinomial sampling with probabilities `s`, `config.n_simulations`
+    /// times, and recomputes the metrics on each simulated word. Simulation
+    /// costs O(f · log n) per word; simulations run in parallel.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::InvalidParameter` for an invalid `config`.
+    pub fn simulate_null_distribution(
+        &mut self,
+        selection: MetricSet,
+        config: &SimulationConfig,
+    ) -> Result<NullDistribution> {
+        config.validate()?;
+        Ok(self.null_distribution_row(selection, config, config.resolve_seed(), 0))
     }
 
     /// Calculate metrics for multiple words using parallel processing
@@ -400,44 +719,451 @@ impl CorpusWordAnalyzer {
     ///
     /// # Errors
     ///
-    /// Returns `PyValueError` if:
+    /// Returns `DispersionError` if:
     /// - Matrix rows have inconsistent lengths
     /// - Input validation fails for any word
-    // Enhanced batch processing with better memory management
-    #[staticmethod]
-    #[allow(clippy::needless_pass_by_value)] // PyO3 requires owned Vec for Python bindings
+    ///
+    /// Row-level failures are wrapped in `DispersionError::Row` with the row index.
     pub fn calculate_batch_metrics(
         frequency_matrix: Vec<Vec<f64>>,
         corpus_part_sizes: Vec<f64>,
         total_corpus_words: f64,
-    ) -> PyResult<Vec<DispersionMetrics>> {
+    ) -> Result<Vec<DispersionMetrics>> {
+        if frequency_matrix.is_empty() {
+            return Ok(Vec::new());
+        }
+        let partition = Arc::new(CorpusPartition::new(corpus_part_sizes, total_corpus_words)?);
+        Self::calculate_batch_metrics_with_partition(frequency_matrix, &partition, MetricSet::ALL)
+    }
+
+    /// Calculate metrics for multiple words over a shared corpus partition
+    ///
+    /// Only the metrics in `selection` are computed (see `calculate_metrics`).
+    /// Rows are processed on the default pool of `crate::parallel`, or on the
+    /// enclosing pool when called under `Parallelism::install`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::Row` if any row has the wrong length or
+    /// contains negative or non-finite frequencies.
+    // Enhanced batch processing with better memory management
+    pub fn calculate_batch_metrics_with_partition(
+        frequency_matrix: Vec<Vec<f64>>,
+        partition: &Arc<CorpusPartition>,
+        selection: MetricSet,
+    ) -> Result<Vec<DispersionMetrics>> {
         use rayon::prelude::*;
 
         // Pre-validate inputs to avoid repeated validation in parallel processing
-        if frequency_matrix.is_empty() {
+        let expected_len: usize = partition.n_parts();
+        for (i, row) in frequency_matrix.iter().enumerate() {
+            validate_frequencies(row, expected_len).map_err(|e| e.in_row(i))?;
+        }
+
+        Ok(run_batch(|| {
+            frequency_matrix
+                .into_par_iter()
+                .map(|v: Vec<f64>| {
+                    Self::from_validated(v, Arc::clone(partition)).calculate_metrics(selection)
+                })
+                .collect()
+        }))
+    }
+
+    /// Calculate metrics for multiple words labelled by `words`
+    ///
+    /// Same computation as `calculate_batch_metrics_with_partition`; each
+    /// result carries its word, results can be looked up by word, and row
+    /// errors name the offending word.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::LabelCountMismatch` if there is not one word
+    /// per row, or `DispersionError::Row` (with the word) if any row has the
+    /// wrong length or contains negative or non-finite frequencies.
+    pub fn calculate_batch_metrics_labelled(
+        words: Vec<String>,
+        frequency_matrix: Vec<Vec<f64>>,
+        partition: &Arc<CorpusPartition>,
+        selection: MetricSet,
+    ) -> Result<LabelledMetrics> {
+        if words.len() != frequency_matrix.len() {
+            return Err(DispersionError::LabelCountMismatch {
+                expected: frequency_matrix.len(),
+                found: words.len(),
+            });
+        }
+        let metrics =
+            Self::calculate_batch_metrics_with_partition(frequency_matrix, partition, selection)
+                .map_err(|e| e.with_words(&words))?;
+        LabelledMetrics::new(words, metrics)
+    }
+
+    /// Bootstrap percentile confidence intervals for multiple words
+    ///
+    /// One seed drives the whole batch: row `i` draws from random streams
+    /// keyed by `(seed, i)`, so every row is reproducible from the seed
+    /// reported in its result. Rows, and the resamples within each row, run
+    /// in parallel.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::InvalidParameter` for an invalid `config`, or
+    /// `DispersionError::Row` if any row has the wrong length or contains
+    /// negative or non-finite frequencies.
+    pub fn calculate_batch_bootstrap(
+        frequency_matrix: Vec<Vec<f64>>,
+        partition: &Arc<CorpusPartition>,
+        selection: MetricSet,
+        config: &BootstrapConfig,
+    ) -> Result<Vec<MetricIntervals>> {
+        use rayon::prelude::*;
+
+        config.validate()?;
+        let expected_len: usize = partition.n_parts();
+        for (i, row) in frequency_matrix.iter().enumerate() {
+            validate_frequencies(row, expected_len).map_err(|e| e.in_row(i))?;
+        }
+
+        let seed = config.resolve_seed();
+        Ok(run_batch(|| {
+            frequency_matrix
+                .into_par_iter()
+                .enumerate()
+                .map(|(row, v)| {
+                    let mut analyzer = Self::from_validated(v, Arc::clone(partition));
+                    analyzer.bootstrap_row(selection, config, seed, row)
+                })
+                .collect()
+        }))
+    }
+
+    /// Bootstrap intervals for every row of a borrowed dense or CSR matrix
+    ///
+    /// Same results as `calculate_batch_bootstrap` for the same seed; the
+    /// matrix buffer is read in place and only the rows being resampled are
+    /// materialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::InvalidParameter` for an invalid `config`,
+    /// `LengthMismatch` if the matrix column count differs from the number
+    /// of corpus parts, or `DispersionError::Row` for a negative or
+    /// non-finite cell.
+    pub fn calculate_batch_bootstrap_matrix<M: FrequencyRows + ?Sized>(
+        frequency_matrix: &M,
+        partition: &Arc<CorpusPartition>,
+        selection: MetricSet,
+        config: &BootstrapConfig,
+    ) -> Result<Vec<MetricIntervals>> {
+        use rayon::prelude::*;
+
+        config.validate()?;
+        if frequency_matrix.n_rows() == 0 {
+            return Ok(Vec::new());
+        }
+        validate_rows(frequency_matrix, partition)?;
+
+        let seed = config.resolve_seed();
+        Ok(run_batch(|| {
+            (0..frequency_matrix.n_rows())
+                .into_par_iter()
+                .map(|row| {
+                    let v = frequency_matrix.dense_row(row);
+                    let mut analyzer = Self::from_validated(v, Arc::clone(partition));
+                    analyzer.bootstrap_row(selection, config, seed, row)
+                })
+                .collect()
+        }))
+    }
+
+    /// Simulated null distributions for multiple words
+    ///
+    /// One seed drives the whole batch, as in `calculate_batch_bootstrap`.
+    /// Rows, and the simulations within each row, run in parallel.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::InvalidParameter` for an invalid `config`, or
+    /// `DispersionError::Row` if any row has the wrong length or contains
+    /// negative or non-finite frequencies.
+    pub fn calculate_batch_null_distribution(
+        frequency_matrix: Vec<Vec<f64>>,
+        partition: &Arc<CorpusPartition>,
+        selection: MetricSet,
+        config: &SimulationConfig,
+    ) -> Result<Vec<NullDistribution>> {
+        use rayon::prelude::*;
+
+        config.validate()?;
+        let expected_len: usize = partition.n_parts();
+        for (i, row) in frequency_matrix.iter().enumerate() {
+            validate_frequencies(row, expected_len).map_err(|e| e.in_row(i))?;
+        }
+
+        let seed = config.resolve_seed();
+        Ok(run_batch(|| {
+            frequency_matrix
+                .into_par_iter()
+                .enumerate()
+                .map(|(row, v)| {
+                    let mut analyzer = Self::from_validated(v, Arc::clone(partition));
+                    analyzer.null_distribution_row(selection, config, seed, row)
+                })
+                .collect()
+        }))
+    }
+
+    /// Simulated null distributions for every row of a borrowed dense or CSR matrix
+    ///
+    /// Same results as `calculate_batch_null_distribution` for the same seed;
+    /// the matrix buffer is read in place and only the rows being simulated
+    /// are materialized.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::InvalidParameter` for an invalid `config`,
+    /// `LengthMismatch` if the matrix column count differs from the number
+    /// of corpus parts, or `DispersionError::Row` for a negative or
+    /// non-finite cell.
+    pub fn calculate_batch_null_distribution_matrix<M: FrequencyRows + ?Sized>(
+        frequency_matrix: &M,
+        partition: &Arc<CorpusPartition>,
+        selection: MetricSet,
+        config: &SimulationConfig,
+    ) -> Result<Vec<NullDistribution>> {
+        use rayon::prelude::*;
+
+        config.validate()?;
+        if frequency_matrix.n_rows() == 0 {
             return Ok(Vec::new());
         }
+        validate_rows(frequency_matrix, partition)?;
 
-        let expected_len: usize = corpus_part_sizes.len();
+        let seed = config.resolve_seed();
+        Ok(run_batch(|| {
+            (0..frequency_matrix.n_rows())
+                .into_par_iter()
+                .map(|row| {
+                    let v = frequency_matrix.dense_row(row);
+                    let mut analyzer = Self::from_validated(v, Arc::clone(partition));
+                    analyzer.null_distribution_row(selection, config, seed, row)
+                })
+                .collect()
+        }))
+    }
+
+    /// Chi-square and G-tests for multiple words over a shared corpus partition
+    ///
+    /// Rows are processed in parallel like `calculate_batch_metrics_with_partition`.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::Row` if any row has the wrong length or
+    /// contains negative or non-finite frequencies.
+    pub fn calculate_batch_goodness_of_fit(
+        frequency_matrix: Vec<Vec<f64>>,
+        partition: &Arc<CorpusPartition>,
+    ) -> Result<Vec<GoodnessOfFit>> {
+        use rayon::prelude::*;
+
+        let expected_len: usize = partition.n_parts();
         for (i, row) in frequency_matrix.iter().enumerate() {
-            if row.len() != expected_len {
-                return Err(pyo3::exceptions::PyValueError::new_err(format!(
-                    "Row {} has length {}, expected {}",
-                    i,
-                    row.len(),
-                    expected_len
-                )));
-            }
+            validate_frequencies(row, expected_len).map_err(|e| e.in_row(i))?;
         }
 
-        frequency_matrix
-            .into_par_iter()
-            .map(|v: Vec<f64>| {
-                let mut analyzer: Self =
-                    Self::new(v, corpus_part_sizes.clone(), total_corpus_words)?;
-                Ok(analyzer.calculate_all_metrics())
-            })
-            .collect()
+        Ok(run_batch(|| {
+            frequency_matrix
+                .into_par_iter()
+                .map(|v: Vec<f64>| {
+                    Self::from_validated(v, Arc::clone(partition)).get_goodness_of_fit()
+                })
+                .collect()
+        }))
+    }
+
+    /// Chi-square and G-tests for every row of a borrowed dense or CSR matrix
+    ///
+    /// Same results as `calculate_batch_goodness_of_fit`; the matrix buffer is
+    /// read in place and only the row being tested is materialized per worker.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError` if:
+    /// - The matrix column count differs from the number of corpus parts
+    /// - Any cell is negative or non-finite (wrapped in `DispersionError::Row`)
+    pub fn calculate_batch_goodness_of_fit_matrix<M: FrequencyRows + ?Sized>(
+        frequency_matrix: &M,
+        partition: &Arc<CorpusPartition>,
+    ) -> Result<Vec<GoodnessOfFit>> {
+        use rayon::prelude::*;
+
+        if frequency_matrix.n_rows() == 0 {
+            return Ok(Vec::new());
+        }
+        validate_rows(frequency_matrix, partition)?;
+
+        Ok(run_batch(|| {
+            (0..frequency_matrix.n_rows())
+                .into_par_iter()
+                .map(|i| {
+                    Self::from_validated(frequency_matrix.dense_row(i), Arc::clone(partition))
+                        .get_goodness_of_fit()
+                })
+                .collect()
+        }))
+    }
+
+    /// Frequency-adjusted residuals of the selected metrics across a word list
+    ///
+    /// Computes the metrics like `calculate_batch_metrics_with_partition`, then
+    /// fits each one against the log of the row totals (see
+    /// `crate::residuals`). The rows should cover a whole vocabulary, or at
+    /// least a broad frequency range, for the trend to be meaningful.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::InvalidParameter` for an invalid `config`, or
+    /// `DispersionError::Row` if any row has the wrong length or contains
+    /// negative or non-finite frequencies.
+    pub fn calculate_batch_residuals(
+        frequency_matrix: Vec<Vec<f64>>,
+        partition: &Arc<CorpusPartition>,
+        selection: MetricSet,
+        config: &ResidualConfig,
+    ) -> Result<Vec<FrequencyResiduals>> {
+        config.validate()?;
+        let frequencies: Vec<f64> = frequency_matrix
+            .iter()
+            .map(|row| row.iter().sum())
+            .collect();
+        let metrics =
+            Self::calculate_batch_metrics_with_partition(frequency_matrix, partition, selection)?;
+        frequency_residuals(metrics, &frequencies, config)
+    }
+
+    /// Rank-ordered word list by lexical prevalence
+    ///
+    /// `words[i]` labels row `i` of `frequency_matrix`. Computes the score
+    /// metric and FT, PT and DA for every row like
+    /// `calculate_batch_metrics_with_partition`, then ranks the words (see
+    /// `crate::wordlist`).
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::LabelCountMismatch` if there is not one word
+    /// per row, `UnknownMetric` for an unknown score, or
+    /// `DispersionError::Row` (with the word) if any row has the wrong length
+    /// or contains negative or non-finite frequencies.
+    pub fn calculate_batch_word_list(
+        words: Vec<String>,
+        frequency_matrix: Vec<Vec<f64>>,
+        partition: &Arc<CorpusPartition>,
+        config: &WordListConfig,
+    ) -> Result<Vec<WordListEntry>> {
+        if words.len() != frequency_matrix.len() {
+            return Err(DispersionError::LabelCountMismatch {
+                expected: frequency_matrix.len(),
+                found: words.len(),
+            });
+        }
+        let selection = config.selection()?;
+        let frequencies: Vec<f64> = frequency_matrix
+            .iter()
+            .map(|row| row.iter().sum())
+            .collect();
+        let metrics =
+            Self::calculate_batch_metrics_with_partition(frequency_matrix, partition, selection)
+                .map_err(|e| e.with_words(&words))?;
+        build_word_list(words, &metrics, &frequencies, config)
+    }
+
+    /// Calculate metrics for every row of a borrowed dense matrix in parallel
+    ///
+    /// Unlike `calculate_batch_metrics`, the matrix buffer is read in place
+    /// (row- or column-major, any `FrequencyValue` element type); only the
+    /// current row is materialized per worker. Only the metrics in
+    /// `selection` are computed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError` if:
+    /// - The matrix column count differs from the number of corpus parts
+    /// - Any cell is negative or non-finite (wrapped in `DispersionError::Row`)
+    pub fn calculate_batch_metrics_matrix<T: FrequencyValue>(
+        frequency_matrix: &FrequencyMatrix<'_, T>,
+        partition: &Arc<CorpusPartition>,
+        selection: MetricSet,
+    ) -> Result<Vec<DispersionMetrics>> {
+        use rayon::prelude::*;
+
+        if frequency_matrix.n_rows() == 0 {
+            return Ok(Vec::new());
+        }
+
+        if frequency_matrix.n_cols() != partition.n_parts() {
+            return Err(DispersionError::LengthMismatch {
+                expected: partition.n_parts(),
+                found: frequency_matrix.n_cols(),
+            });
+        }
+        frequency_matrix.validate()?;
+
+        Ok(run_batch(|| {
+            (0..frequency_matrix.n_rows())
+                .into_par_iter()
+                .map(|i| {
+                    let v: Vec<f64> = frequency_matrix.row(i).collect();
+                    Self::from_validated(v, Arc::clone(partition)).calculate_metrics(selection)
+                })
+                .collect()
+        }))
+    }
+
+    /// Calculate metrics for every row of a borrowed CSR matrix in parallel
+    ///
+    /// Only stored cells are visited; zero cells enter each metric through
+    /// closed-form per-partition sums, so the cost per word is proportional
+    /// to its number of non-zero parts. `dp_nofreq` additionally visits the
+    /// parts an even spread of the word's tokens would reach (at most its
+    /// frequency; see `CorpusPartition::dp_range`), using the size order
+    /// cached in the partition. Results match the dense computation
+    /// within floating-point tolerance. Only the metrics in `selection` are
+    /// reported.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError` if:
+    /// - The matrix column count differs from the number of corpus parts
+    /// - Any stored value is negative or non-finite (wrapped in `DispersionError::Row`)
+    pub fn calculate_batch_metrics_sparse<T: FrequencyValue, I: SparseIndex>(
+        frequency_matrix: &CsrMatrix<'_, T, I>,
+        partition: &Arc<CorpusPartition>,
+        selection: MetricSet,
+    ) -> Result<Vec<DispersionMetrics>> {
+        use rayon::prelude::*;
+
+        if frequency_matrix.n_rows() == 0 {
+            return Ok(Vec::new());
+        }
+
+        if frequency_matrix.n_cols() != partition.n_parts() {
+            return Err(DispersionError::LengthMismatch {
+                expected: partition.n_parts(),
+                found: frequency_matrix.n_cols(),
+            });
+        }
+        frequency_matrix.validate()?;
+
+        Ok(run_batch(|| {
+            (0..frequency_matrix.n_rows())
+                .into_par_iter()
+                .map(|i| {
+                    let cells: Vec<(usize, f64)> = frequency_matrix.row(i).collect();
+                    sparse_row_metrics(partition, &cells, selection)
+                })
+                .collect()
+        }))
     }
 
     /// Calculate a single metric without full analyzer initialization
@@ -451,43 +1177,128 @@ impl CorpusWordAnalyzer {
     ///
     /// # Errors
     ///
-    /// Returns `PyValueError` if:
+    /// Returns `DispersionError` if:
     /// - Metric name is unknown
     /// - Input validation fails
     // Additional utility method for single metric calculation
-    #[staticmethod]
     pub fn calculate_single_metric(
         frequency_vector: Vec<f64>,
         corpus_part_sizes: Vec<f64>,
         total_corpus_words: f64,
         metric_name: &str,
-    ) -> PyResult<Option<f64>> {
+    ) -> Result<Option<f64>> {
         let mut analyzer = Self::new(frequency_vector, corpus_part_sizes, total_corpus_words)?;
+        analyzer.get_metric(metric_name)
+    }
 
+    /// Compute one metric by name
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::UnknownMetric` if the name is not recognised.
+    pub fn get_metric(&mut self, metric_name: &str) -> Result<Option<f64>> {
         let result = match metric_name {
-            "juilland_d" => analyzer.get_juilland_d(),
-            "carroll_d2" => analyzer.get_carroll_d2(),
-            "dp" => analyzer.get_dp(),
-            "dp_norm" => analyzer.get_dp_norm(),
-            "kl_divergence" => analyzer.get_kl_divergence(),
-            "jsd_dispersion" => analyzer.get_jsd_dispersion(),
-            "hellinger_dispersion" => analyzer.get_hellinger_dispersion(),
-            "evenness_da" => analyzer.get_evenness_da(),
-            "mean_text_frequency_ft" => analyzer.get_mean_text_frequency_ft(),
-            "pervasiveness_pt" => analyzer.get_pervasiveness_pt(),
-            _ => {
-                return Err(pyo3::exceptions::PyValueError::new_err(format!(
-                    "Unknown metric: {metric_name}"
-                )));
-            }
+            "juilland_d" => self.get_juilland_d(),
+            "juilland_u" => self.get_juilland_u(),
+            "carroll_d2" => self.get_carroll_d2(),
+            "carroll_um" => self.get_carroll_um(),
+            "lyne_d3" => self.get_lyne_d3(),
+            "roschengren_af" => self.get_roschengren_af(),
+            "zhang_dc" => self.get_zhang_dc(),
+            "dp" => self.get_dp(),
+            "dp_norm" => self.get_dp_norm(),
+            "dp_nofreq" => self.get_dp_nofreq(),
+            "kl_divergence" => self.get_kl_divergence(),
+            "kl_divergence_norm" => self.get_kl_divergence_norm(),
+            "jsd_dispersion" => self.get_jsd_dispersion(),
+            "hellinger_dispersion" => self.get_hellinger_dispersion(),
+            "gini_coefficient" => self.get_gini_coefficient(),
+            "shannon_entropy" => self.get_shannon_entropy(),
+            "pielou_evenness" => self.get_pielou_evenness(),
+            "simpson_index" => self.get_simpson_index(),
+            "evenness_da" => self.get_evenness_da(),
+            "mean_text_frequency_ft" => self.get_mean_text_frequency_ft(),
+            "pervasiveness_pt" => self.get_pervasiveness_pt(),
+            _ => return Err(DispersionError::UnknownMetric(metric_name.to_string())),
         };
 
         Ok(result)
     }
 }
 
+// Column count and cell checks of a borrowed matrix against the partition
+fn validate_rows<M: FrequencyRows + ?Sized>(
+    frequency_matrix: &M,
+    partition: &CorpusPartition,
+) -> Result<()> {
+    if frequency_matrix.n_cols() != partition.n_parts() {
+        return Err(DispersionError::LengthMismatch {
+            expected: partition.n_parts(),
+            found: frequency_matrix.n_cols(),
+        });
+    }
+    frequency_matrix.validate()
+}
+
 // Helper methods implementation
 impl CorpusWordAnalyzer {
+    // Null simulation with an already resolved seed; `row` picks the random streams
+    fn null_distribution_row(
+        &mut self,
+        selection: MetricSet,
+        config: &SimulationConfig,
+        seed: u64,
+        row: usize,
+    ) -> NullDistribution {
+        let observed = self.calculate_metrics(selection);
+        simulate_null(
+            self.f,
+            &self.partition,
+            selection,
+            config,
+            seed,
+            row,
+            observed,
+        )
+    }
+
+    // Bootstrap with an already resolved seed; `row` picks the random streams
+    fn bootstrap_row(
+        &mut self,
+        selection: MetricSet,
+        config: &BootstrapConfig,
+        seed: u64,
+        row: usize,
+    ) -> MetricIntervals {
+        let estimate = self.calculate_metrics(selection);
+        bootstrap_word(
+            &self.v,
+            &self.partition,
+            selection,
+            config,
+            seed,
+            row,
+            estimate,
+        )
+    }
+
+    // Pearson's χ² of v against the expected frequencies f·s_i (parts with s_i > 0)
+    fn chi_square(&self) -> f64 {
+        if self.f == 0.0 {
+            return 0.0;
+        }
+        self.partition
+            .s()
+            .iter()
+            .zip(self.v.iter())
+            .filter(|&(&s_i, _)| s_i > 0.0)
+            .map(|(&s_i, &v_i)| {
+                let expected = self.f * s_i;
+                (v_i - expected).powi(2) / expected
+            })
+            .sum::<f64>()
+    }
+
     // Helper method to get cached mean_v
     #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
     fn get_mean_v(&mut self) -> f64 {
@@ -520,13 +1331,28 @@ impl CorpusWordAnalyzer {
         sum
     }
 
-    // Helper method to get cached min_s
-    fn get_min_s(&mut self) -> f64 {
-        if let Some(min_s) = self.cached_min_s {
-            return min_s;
+    // Helper method to get cached Shannon entropy (nats) of p / Σp
+    fn get_entropy(&mut self) -> f64 {
+        if let Some(entropy) = self.cached_entropy {
+            return entropy;
         }
-        let min_s = self.s.iter().copied().fold(f64::INFINITY, f64::min);
-        self.cached_min_s = Some(min_s);
-        min_s
+        let sum_p = self.get_sum_p();
+        let entropy = if sum_p.abs() < 1e-12 {
+            0.0
+        } else {
+            self.p
+                .iter()
+                .map(|&p_i| {
+                    let norm_prop = p_i / sum_p;
+                    if norm_prop > 1e-12 {
+                        -norm_prop * norm_prop.ln()
+                    } else {
+                        0.0
+                    }
+                })
+                .sum::<f64>()
+        };
+        self.cached_entropy = Some(entropy);
+        entropy
     }
 }
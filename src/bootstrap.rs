@@ -0,0 +1,230 @@
+//! Bootstrap confidence intervals for dispersion metrics
+//!
+//! Corpus parts are resampled with replacement (each resample keeps the
+//! number of parts, and every drawn part brings its frequency and its size);
+//! the metrics of all resamples give percentile confidence intervals. Each
+//! resample uses its own generator derived from the seed, the batch row and
+//! the resample index, so results do not depend on how rayon schedules work.
+
+use crate::analyzer::CorpusWordAnalyzer;
+use crate::error::{DispersionError, Result};
+use crate::metrics::{DispersionMetrics, MetricSet};
+use crate::parallel::run_batch;
+use crate::partition::CorpusPartition;
+use crate::rng::{SplitMix64, fresh_seed, stream_seed};
+use std::fmt;
+
+/// Settings of a bootstrap run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct BootstrapConfig {
+    /// Number of resamples (default 1000)
+    pub n_resamples: usize,
+    /// Coverage of the percentile interval, strictly between 0 and 1 (default 0.95)
+    pub confidence_level: f64,
+    /// Seed of the random generator; `None` draws a fresh seed per call
+    pub seed: Option<u64>,
+}
+
+impl Default for BootstrapConfig {
+    fn default() -> Self {
+        Self {
+            n_resamples: 1000,
+            confidence_level: 0.95,
+            seed: None,
+        }
+    }
+}
+
+impl BootstrapConfig {
+    /// Check the settings
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::InvalidParameter` if `n_resamples` is zero or
+    /// `confidence_level` is not strictly between 0 and 1.
+    #[allow(clippy::cast_precision_loss)] // Only used in the error message
+    pub fn validate(&self) -> Result<()> {
+        if self.n_resamples == 0 {
+            return Err(DispersionError::InvalidParameter {
+                name: "n_resamples",
+                value: self.n_resamples as f64,
+                reason: "at least 1",
+            });
+        }
+        if !(self.confidence_level > 0.0 && self.confidence_level < 1.0) {
+            return Err(DispersionError::InvalidParameter {
+                name: "confidence_level",
+                value: self.confidence_level,
+                reason: "strictly between 0 and 1",
+            });
+        }
+        Ok(())
+    }
+
+    // Configured seed, or a fresh random one
+    pub(crate) fn resolve_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(fresh_seed)
+    }
+}
+
+/// Percentile bootstrap intervals of a single word's metrics
+///
+/// `lower` and `upper` hold the interval bounds of every metric that was
+/// requested; a bound is `None` when no resample produced the metric.
+/// Resamples that draw none of the word's occurrences (or only empty parts)
+/// are skipped, as a word absent from the resample says nothing about its
+/// dispersion; a word that never occurs therefore has no intervals.
+#[cfg_attr(feature = "python", pyo3::pyclass(get_all))]
+#[derive(Debug, Clone)]
+pub struct MetricIntervals {
+    /// Metrics of the observed frequencies
+    pub estimate: DispersionMetrics,
+    /// Lower bounds, the (1 − level) / 2 percentile of the resamples
+    pub lower: DispersionMetrics,
+    /// Upper bounds, the (1 + level) / 2 percentile of the resamples
+    pub upper: DispersionMetrics,
+    /// Coverage of the intervals
+    pub confidence_level: f64,
+    /// Number of resamples drawn
+    pub n_resamples: usize,
+    /// Number of resamples the intervals were computed from
+    pub n_valid_resamples: usize,
+    /// Seed the resamples were drawn from (pass it back to reproduce them)
+    pub seed: u64,
+}
+
+impl MetricIntervals {
+    /// `(lower, upper)` bounds of a metric by name
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::UnknownMetric` if the name is not a metric field.
+    pub fn interval(&self, name: &str) -> Result<Option<(f64, f64)>> {
+        Ok(self.lower.get(name)?.zip(self.upper.get(name)?))
+    }
+}
+
+impl fmt::Display for MetricIntervals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "MetricIntervals(confidence_level={}, n_resamples={}, n_valid_resamples={}, seed={})",
+            self.confidence_level, self.n_resamples, self.n_valid_resamples, self.seed
+        )
+    }
+}
+
+/// Bootstrap one word whose `estimate` has already been computed
+///
+/// `row` selects the random streams, so batch rows are independent of each
+/// other yet reproducible from a single seed.
+pub(crate) fn bootstrap_word(
+    v: &[f64],
+    partition: &CorpusPartition,
+    selection: MetricSet,
+    config: &BootstrapConfig,
+    seed: u64,
+    row: usize,
+    estimate: DispersionMetrics,
+) -> MetricIntervals {
+    use rayon::prelude::*;
+
+    let samples: Vec<DispersionMetrics> = run_batch(|| {
+        (0..config.n_resamples)
+            .into_par_iter()
+            .filter_map(|resample| {
+                let mut rng = SplitMix64::new(stream_seed(seed, row, resample));
+                resample_metrics(v, partition, selection, &mut rng)
+            })
+            .collect()
+    });
+
+    let alpha = 1.0 - config.confidence_level;
+    let mut lower = DispersionMetrics::default();
+    let mut upper = DispersionMetrics::default();
+
+    let mut ranges: Vec<f64> = samples.iter().map(|m| f64::from(m.range)).collect();
+    ranges.sort_by(f64::total_cmp);
+    if !ranges.is_empty() {
+        lower.range = round_range(percentile(&ranges, alpha / 2.0));
+        upper.range = round_range(percentile(&ranges, 1.0 - alpha / 2.0));
+    }
+
+    for (index, (low, high)) in lower
+        .float_values_mut()
+        .into_iter()
+        .zip(upper.float_values_mut())
+        .enumerate()
+    {
+        let mut values: Vec<f64> = samples
+            .iter()
+            .filter_map(|m| m.float_values()[index])
+            .filter(|v| !v.is_nan())
+            .collect();
+        if values.is_empty() {
+            continue;
+        }
+        values.sort_by(f64::total_cmp);
+        *low = Some(percentile(&values, alpha / 2.0));
+        *high = Some(percentile(&values, 1.0 - alpha / 2.0));
+    }
+
+    MetricIntervals {
+        estimate,
+        lower,
+        upper,
+        confidence_level: config.confidence_level,
+        n_resamples: config.n_resamples,
+        n_valid_resamples: samples.len(),
+        seed,
+    }
+}
+
+// Metrics of one resample; `None` if the word does not occur in it or every
+// drawn part has size zero
+fn resample_metrics(
+    v: &[f64],
+    partition: &CorpusPartition,
+    selection: MetricSet,
+    rng: &mut SplitMix64,
+) -> Option<DispersionMetrics> {
+    let sizes = partition.part_sizes();
+    let n = v.len();
+    let mut resampled_v = Vec::with_capacity(n);
+    let mut resampled_sizes = Vec::with_capacity(n);
+    for _ in 0..n {
+        let i = rng.next_index(n);
+        resampled_v.push(v[i]);
+        resampled_sizes.push(sizes[i]);
+    }
+
+    // Keep the share of the corpus outside the listed parts unchanged
+    let size_sum: f64 = sizes.iter().sum();
+    let resampled_sum: f64 = resampled_sizes.iter().sum();
+    if resampled_sum <= 0.0 || size_sum <= 0.0 || resampled_v.iter().all(|&x| x == 0.0) {
+        return None;
+    }
+    let total = partition.total_corpus_words() * resampled_sum / size_sum;
+
+    CorpusWordAnalyzer::new(resampled_v, resampled_sizes, total)
+        .ok()
+        .map(|mut analyzer| analyzer.calculate_metrics(selection))
+}
+
+// Linear interpolation between order statistics (Hyndman & Fan type 7)
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn percentile(sorted: &[f64], q: f64) -> f64 {
+    let h = (sorted.len() - 1) as f64 * q;
+    let lo = h.floor() as usize;
+    let hi = (lo + 1).min(sorted.len() - 1);
+    (h - lo as f64).mul_add(sorted[hi] - sorted[lo], sorted[lo])
+}
+
+#[allow(clippy::cast_possible_truncation)] // A range never exceeds the number of parts
+fn round_range(value: f64) -> i32 {
+    value.round() as i32
+}
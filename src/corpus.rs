@@ -0,0 +1,309 @@
+//! Frequency tables counted from a directory of plain-text files
+//!
+//! Every file (or every subdirectory) of the corpus directory is one corpus
+//! part. Files are tokenized and counted in parallel; the part sizes are the
+//! numbers of tokens counted in each part, so the frequencies and sizes come
+//! from the same tokenization.
+
+use crate::error::{DispersionError, Result};
+use crate::parallel::run_batch;
+use crate::table::FrequencyTable;
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Unicode-aware word tokenizer
+///
+/// A token is a run of alphabetic or numeric characters (Unicode `Alphabetic`
+/// and `Numeric` properties), optionally joined by one of `joiners` between
+/// two such characters ("don't", "well-known"). Everything else separates
+/// tokens.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tokenizer {
+    /// Lowercase tokens (default `true`)
+    pub lowercase: bool,
+    /// Keep tokens made only of numeric characters (default `true`)
+    pub keep_numbers: bool,
+    /// Characters that join two word characters into one token
+    /// (default apostrophes and the hyphen: `'’-`)
+    pub joiners: String,
+    /// Make every Han, Hiragana and Katakana character a token of its own
+    /// (default `true`), since these scripts do not separate words by spaces
+    pub split_cjk: bool,
+    /// Minimum token length in characters (default 1)
+    pub min_length: usize,
+}
+
+impl Default for Tokenizer {
+    fn default() -> Self {
+        Self {
+            lowercase: true,
+            keep_numbers: true,
+            joiners: "'\u{2019}-".to_string(),
+            split_cjk: true,
+            min_length: 1,
+        }
+    }
+}
+
+impl Tokenizer {
+    /// Split `text` into tokens in reading order
+    #[must_use]
+    pub fn tokenize(&self, text: &str) -> Vec<String> {
+        let mut tokens = Vec::new();
+        self.for_each_token(text, |token| tokens.push(token));
+        tokens
+    }
+
+    fn for_each_token(&self, text: &str, mut emit: impl FnMut(String)) {
+        let mut emit_kept = |token: &str| {
+            if token.chars().count() < self.min_length
+                || (!self.keep_numbers && token.chars().all(char::is_numeric))
+            {
+                return;
+            }
+            emit(if self.lowercase {
+                token.to_lowercase()
+            } else {
+                token.to_string()
+            });
+        };
+
+        let is_word = |c: char| c.is_alphanumeric() && !(self.split_cjk && is_cjk(c));
+        let mut start = None;
+        let mut chars = text.char_indices().peekable();
+        while let Some((index, c)) = chars.next() {
+            if is_word(c) {
+                start.get_or_insert(index);
+                continue;
+            }
+            let joins = start.is_some()
+                && self.joiners.contains(c)
+                && chars.peek().is_some_and(|&(_, next)| is_word(next));
+            if joins {
+                continue;
+            }
+            if let Some(token_start) = start.take() {
+                emit_kept(&text[token_start..index]);
+            }
+            if self.split_cjk && is_cjk(c) {
+                emit_kept(&text[index..index + c.len_utf8()]);
+            }
+        }
+        if let Some(token_start) = start {
+            emit_kept(&text[token_start..]);
+        }
+    }
+}
+
+// Han ideographs, Hiragana and Katakana
+fn is_cjk(c: char) -> bool {
+    matches!(
+        c,
+        '\u{3040}'..='\u{30FF}'
+            | '\u{3400}'..='\u{4DBF}'
+            | '\u{4E00}'..='\u{9FFF}'
+            | '\u{F900}'..='\u{FAFF}'
+            | '\u{20000}'..='\u{2FA1F}'
+    )
+}
+
+/// What makes up one corpus part
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum PartitionBy {
+    /// Each file directly in the corpus directory is a part
+    #[default]
+    File,
+    /// Each subdirectory of the corpus directory is a part, made of all the
+    /// files below it
+    Directory,
+}
+
+impl PartitionBy {
+    /// Parse a partitioning by name: `"file"` or `"directory"`
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "file" => Some(Self::File),
+            "directory" => Some(Self::Directory),
+            _ => None,
+        }
+    }
+}
+
+/// Settings for counting a corpus directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CorpusConfig {
+    /// What makes up one corpus part
+    pub partition_by: PartitionBy,
+    /// File extensions to read, without the dot and compared case-insensitively
+    /// (default `["txt"]`); empty reads every file
+    pub extensions: Vec<String>,
+    /// How file contents are split into tokens
+    pub tokenizer: Tokenizer,
+}
+
+impl Default for CorpusConfig {
+    fn default() -> Self {
+        Self {
+            partition_by: PartitionBy::File,
+            extensions: vec!["txt".to_string()],
+            tokenizer: Tokenizer::default(),
+        }
+    }
+}
+
+impl CorpusConfig {
+    fn reads(&self, path: &Path) -> bool {
+        self.extensions.is_empty()
+            || path.extension().is_some_and(|extension| {
+                self.extensions
+                    .iter()
+                    .any(|wanted| extension.eq_ignore_ascii_case(wanted.as_str()))
+            })
+    }
+}
+
+/// Count every word of a corpus directory per part
+///
+/// Parts are named after their file or subdirectory and ordered by name;
+/// words are in lexicographic order. Only the non-zero counts are stored, so
+/// the table is sparse (see `FrequencyTable::from_csr`). Entries whose names start with `.` are
+/// skipped, as are files without a wanted extension. Part sizes are the
+/// token counts of the parts, and a part without tokens has size 0. Files
+/// must be UTF-8.
+///
+/// Symbolic links directly in `root` are followed, so a linked file or
+/// directory is a part like any other. Below a part directory, linked files
+/// are read but linked directories are skipped: they could loop back up the
+/// tree or count another part's files twice. Links whose target cannot be
+/// resolved (dangling links) are skipped wherever they are.
+///
+/// # Errors
+///
+/// Returns `DispersionError::Io` if a directory or file cannot be read (or a
+/// file is not UTF-8), `EmptyInput` if there are no parts, or
+/// `NonPositiveTotal` if no tokens were counted.
+pub fn build_frequency_table(
+    root: impl AsRef<Path>,
+    config: &CorpusConfig,
+) -> Result<FrequencyTable> {
+    use rayon::prelude::*;
+
+    let mut part_names = Vec::new();
+    let mut files = Vec::new();
+    for (name, path, is_dir, _) in list_dir(root.as_ref())? {
+        match config.partition_by {
+            PartitionBy::File if !is_dir && config.reads(&path) => {
+                files.push((part_names.len(), path))
+            }
+            PartitionBy::Directory if is_dir => {
+                let part = part_names.len();
+                let mut part_files = Vec::new();
+                collect_files(&path, config, &mut part_files)?;
+                files.extend(part_files.into_iter().map(|path| (part, path)));
+            }
+            _ => continue,
+        }
+        part_names.push(name);
+    }
+    if part_names.is_empty() {
+        return Err(DispersionError::EmptyInput);
+    }
+
+    let counts: Vec<(usize, HashMap<String, usize>)> = run_batch(|| {
+        files
+            .par_iter()
+            .map(|(part, path)| {
+                let text = fs::read_to_string(path).map_err(|e| io_error(path, &e))?;
+                let mut counts: HashMap<String, usize> = HashMap::new();
+                config
+                    .tokenizer
+                    .for_each_token(&text, |token| *counts.entry(token).or_default() += 1);
+                Ok((*part, counts))
+            })
+            .collect::<Result<_>>()
+    })?;
+
+    // Stored cells per word; files come in part order, so each word's cells
+    // are pushed with non-decreasing parts and repeats are merged in place
+    let mut rows: HashMap<String, Vec<(usize, f64)>> = HashMap::new();
+    let mut part_sizes = vec![0.0; part_names.len()];
+    for (part, file_counts) in counts {
+        for (word, count) in file_counts {
+            #[allow(clippy::cast_precision_loss)] // Token counts are far below 2^52
+            let count = count as f64;
+            let cells = rows.entry(word).or_default();
+            match cells.last_mut() {
+                Some((last, total)) if *last == part => *total += count,
+                _ => cells.push((part, count)),
+            }
+            part_sizes[part] += count;
+        }
+    }
+    let mut rows: Vec<(String, Vec<(usize, f64)>)> = rows.into_iter().collect();
+    rows.sort_unstable_by(|a, b| a.0.cmp(&b.0));
+    let mut words = Vec::with_capacity(rows.len());
+    let mut indptr = Vec::with_capacity(rows.len() + 1);
+    let mut indices = Vec::new();
+    let mut values = Vec::new();
+    indptr.push(0);
+    for (word, cells) in rows {
+        words.push(word);
+        for (part, count) in cells {
+            indices.push(part);
+            values.push(count);
+        }
+        indptr.push(indices.len());
+    }
+    FrequencyTable::from_csr(words, part_names, indptr, indices, values, part_sizes)
+}
+
+// Visible entries of a directory as (name, path, is directory, is symbolic
+// link), sorted by name
+fn list_dir(dir: &Path) -> Result<Vec<(String, PathBuf, bool, bool)>> {
+    let mut entries = Vec::new();
+    for entry in fs::read_dir(dir).map_err(|e| io_error(dir, &e))? {
+        let entry = entry.map_err(|e| io_error(dir, &e))?;
+        let path = entry.path();
+        let name = path
+            .file_name()
+            .map(|name| name.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        if name.starts_with('.') {
+            continue;
+        }
+        let is_link = entry
+            .file_type()
+            .map_err(|e| io_error(&path, &e))?
+            .is_symlink();
+        // Follows symbolic links; a dangling link has nothing to read
+        let metadata = match fs::metadata(&path) {
+            Ok(metadata) => metadata,
+            Err(_) if is_link => continue,
+            Err(e) => return Err(io_error(&path, &e)),
+        };
+        entries.push((name, path, metadata.is_dir(), is_link));
+    }
+    entries.sort_by(|a, b| a.0.cmp(&b.0));
+    Ok(entries)
+}
+
+// Wanted files anywhere below `dir`, without entering linked directories
+fn collect_files(dir: &Path, config: &CorpusConfig, files: &mut Vec<PathBuf>) -> Result<()> {
+    for (_, path, is_dir, is_link) in list_dir(dir)? {
+        if is_dir {
+            // A linked directory may lead back up the tree or into another part
+            if !is_link {
+                collect_files(&path, config, files)?;
+            }
+        } else if config.reads(&path) {
+            files.push(path);
+        }
+    }
+    Ok(())
+}
+
+fn io_error(path: &Path, err: &std::io::Error) -> DispersionError {
+    DispersionError::Io(format!("{}: {err}", path.display()))
+}
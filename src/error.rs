@@ -0,0 +1,254 @@
+//! Error type for dispersion computations
+
+use std::fmt;
+
+/// Errors raised while validating analyzer inputs or dispatching metrics
+#[derive(Debug, Clone, PartialEq)]
+pub enum DispersionError {
+    /// Frequency vector and part sizes vector have different lengths
+    LengthMismatch { expected: usize, found: usize },
+    /// Number of word labels differs from the number of frequency rows
+    LabelCountMismatch { expected: usize, found: usize },
+    /// Input vectors are empty
+    EmptyInput,
+    /// Total corpus size is zero or negative
+    NonPositiveTotal(f64),
+    /// A word frequency is negative
+    NegativeFrequency { index: usize, value: f64 },
+    /// A corpus part size is negative
+    NegativePartSize { index: usize, value: f64 },
+    /// An input value is NaN or infinite (`index` is `None` for scalar inputs)
+    NonFiniteValue {
+        field: &'static str,
+        index: Option<usize>,
+        value: f64,
+    },
+    /// Metric name passed to `calculate_single_metric` is not recognised
+    UnknownMetric(String),
+    /// Sparse matrix index arrays are inconsistent
+    MalformedSparse(&'static str),
+    /// A token position is out of range or not strictly increasing
+    InvalidPosition {
+        index: usize,
+        value: usize,
+        reason: &'static str,
+    },
+    /// A tuning parameter (e.g. a bootstrap setting) is outside its valid range
+    InvalidParameter {
+        name: &'static str,
+        value: f64,
+        reason: &'static str,
+    },
+    /// A dedicated rayon thread pool could not be created
+    ThreadPool(String),
+    /// A frequency table file could not be parsed (`line` is 1-based)
+    MalformedTable { line: usize, reason: String },
+    /// An input file could not be opened or read
+    Io(String),
+    /// Validation failed for one row of a batch computation
+    Row {
+        index: usize,
+        /// Label of the row, if the batch was given word labels
+        word: Option<String>,
+        source: Box<DispersionError>,
+    },
+}
+
+impl DispersionError {
+    /// Attach the batch row index to an error
+    #[must_use]
+    pub fn in_row(self, index: usize) -> Self {
+        Self::Row {
+            index,
+            word: None,
+            source: Box::new(self),
+        }
+    }
+
+    /// Attach the word label of the offending row (`words[index]`) to a row error
+    #[must_use]
+    pub fn with_words(self, words: &[String]) -> Self {
+        match self {
+            Self::Row { index, source, .. } => Self::Row {
+                index,
+                word: words.get(index).cloned(),
+                source,
+            },
+            other => other,
+        }
+    }
+
+    /// Row index of the offending batch row, if any
+    #[must_use]
+    pub const fn row(&self) -> Option<usize> {
+        match self {
+            Self::Row { index, .. } => Some(*index),
+            _ => None,
+        }
+    }
+
+    /// Word label of the offending batch row, if known
+    #[must_use]
+    pub fn word(&self) -> Option<&str> {
+        match self {
+            Self::Row { word, .. } => word.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// Underlying error kind with any row context stripped
+    #[must_use]
+    pub fn kind(&self) -> &Self {
+        match self {
+            Self::Row { source, .. } => source.kind(),
+            other => other,
+        }
+    }
+}
+
+impl fmt::Display for DispersionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::LengthMismatch { expected, found } => write!(
+                f,
+                "Frequency vector has length {found}, expected {expected} (one entry per corpus part)."
+            ),
+            Self::LabelCountMismatch { expected, found } => write!(
+                f,
+                "Got {found} word labels, expected {expected} (one per frequency row)."
+            ),
+            Self::EmptyInput => write!(f, "Input vectors cannot be empty."),
+            Self::NonPositiveTotal(total) => {
+                write!(f, "Total corpus words must be positive, got {total}.")
+            }
+            Self::NegativeFrequency { index, value } => {
+                write!(f, "Frequency at position {index} is negative ({value}).")
+            }
+            Self::NegativePartSize { index, value } => write!(
+                f,
+                "Corpus part size at position {index} is negative ({value})."
+            ),
+            Self::NonFiniteValue {
+                field,
+                index: Some(index),
+                value,
+            } => write!(f, "{field} at position {index} is not finite ({value})."),
+            Self::NonFiniteValue {
+                field,
+                index: None,
+                value,
+            } => write!(f, "{field} is not finite ({value})."),
+            Self::UnknownMetric(name) => write!(f, "Unknown metric: {name}"),
+            Self::MalformedSparse(reason) => write!(f, "Malformed sparse matrix: {reason}."),
+            Self::InvalidPosition {
+                index,
+                value,
+                reason,
+            } => write!(f, "Token position {value} at index {index} {reason}."),
+            Self::InvalidParameter {
+                name,
+                value,
+                reason,
+            } => write!(f, "{name} must be {reason}, got {value}."),
+            Self::ThreadPool(reason) => write!(f, "Failed to build thread pool: {reason}"),
+            Self::MalformedTable { line, reason } => {
+                write!(f, "Malformed frequency table at line {line}: {reason}.")
+            }
+            Self::Io(reason) => write!(f, "I/O error: {reason}"),
+            Self::Row {
+                index,
+                word: Some(word),
+                source,
+            } => write!(f, "Row {index} ({word:?}): {source}"),
+            Self::Row {
+                index,
+                word: None,
+                source,
+            } => write!(f, "Row {index}: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for DispersionError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Self::Row { source, .. } => Some(source.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Convenience alias used throughout the crate
+pub type Result<T> = std::result::Result<T, DispersionError>;
+
+/// Validate the frequency vector, part sizes and total shared by every analyzer
+///
+/// # Errors
+///
+/// Returns the first validation failure, checking the partition (part sizes
+/// and total) before the frequency vector.
+pub fn validate_inputs(v: &[f64], corpus_part_sizes_words: &[f64], total: f64) -> Result<()> {
+    validate_partition(corpus_part_sizes_words, total)?;
+    validate_frequencies(v, corpus_part_sizes_words.len())
+}
+
+/// Validate part sizes and total corpus size
+///
+/// # Errors
+///
+/// Returns `EmptyInput`, `NonFiniteValue`, `NonPositiveTotal` or `NegativePartSize`.
+pub fn validate_partition(corpus_part_sizes_words: &[f64], total: f64) -> Result<()> {
+    if corpus_part_sizes_words.is_empty() {
+        return Err(DispersionError::EmptyInput);
+    }
+    if !total.is_finite() {
+        return Err(DispersionError::NonFiniteValue {
+            field: "Total corpus words",
+            index: None,
+            value: total,
+        });
+    }
+    if total <= 0.0 {
+        return Err(DispersionError::NonPositiveTotal(total));
+    }
+    for (index, &value) in corpus_part_sizes_words.iter().enumerate() {
+        if !value.is_finite() {
+            return Err(DispersionError::NonFiniteValue {
+                field: "Corpus part size",
+                index: Some(index),
+                value,
+            });
+        }
+        if value < 0.0 {
+            return Err(DispersionError::NegativePartSize { index, value });
+        }
+    }
+    Ok(())
+}
+
+/// Validate a single word's frequency vector against the number of parts
+///
+/// # Errors
+///
+/// Returns `LengthMismatch`, `NonFiniteValue` or `NegativeFrequency`.
+pub fn validate_frequencies(v: &[f64], n_parts: usize) -> Result<()> {
+    if v.len() != n_parts {
+        return Err(DispersionError::LengthMismatch {
+            expected: n_parts,
+            found: v.len(),
+        });
+    }
+    for (index, &value) in v.iter().enumerate() {
+        if !value.is_finite() {
+            return Err(DispersionError::NonFiniteValue {
+                field: "Frequency",
+                index: Some(index),
+                value,
+            });
+        }
+        if value < 0.0 {
+            return Err(DispersionError::NegativeFrequency { index, value });
+        }
+    }
+    Ok(())
+}
@@ -1,24 +1,42 @@
 //! Corpus Dispersion Analysis Library
 //!
-//! A high-performance Python extension for advanced lexical dispersion metrics,
-//! powered by Rust and `PyO3`.
-
-use pyo3::Bound;
-use pyo3::prelude::*;
-use pyo3::types::PyModule;
+//! A high-performance library for advanced lexical dispersion metrics.
+//! The metric core is plain Rust; the Python extension (powered by `PyO3`)
+//! is compiled only with the `python` cargo feature.
 
 // Public modules
 pub mod analyzer;
+pub mod bootstrap;
+pub mod corpus;
+pub mod error;
+pub mod matrix;
 pub mod metrics;
+pub mod parallel;
+pub mod partition;
+pub mod position;
+pub mod residuals;
+pub mod significance;
+pub mod sparse;
+pub mod table;
+pub mod wordlist;
+
+mod rng;
+
+#[cfg(feature = "python")]
+mod python;
 
 // Re-exports for convenience
 pub use analyzer::CorpusWordAnalyzer;
-pub use metrics::DispersionMetrics;
-
-/// Python module definition
-#[pymodule]
-fn corpus_dispersion(m: &Bound<'_, PyModule>) -> PyResult<()> {
-    m.add_class::<CorpusWordAnalyzer>()?;
-    m.add_class::<DispersionMetrics>()?;
-    Ok(())
-}
+pub use bootstrap::{BootstrapConfig, MetricIntervals};
+pub use corpus::{CorpusConfig, PartitionBy, Tokenizer, build_frequency_table};
+pub use error::DispersionError;
+pub use matrix::{FrequencyMatrix, FrequencyRows, FrequencyValue, MatrixLayout};
+pub use metrics::{DispersionMetrics, LabelledMetrics, MetricBounds, MetricColumns, MetricSet};
+pub use parallel::{Parallelism, set_default_parallelism};
+pub use partition::CorpusPartition;
+pub use position::{PositionAnalyzer, PositionMetrics};
+pub use residuals::{FrequencyResiduals, ResidualConfig, frequency_residuals};
+pub use significance::{GoodnessOfFit, NullDistribution, SimulationConfig, chi_square_sf};
+pub use sparse::{CsrMatrix, SparseIndex};
+pub use table::{FrequencyTable, TableConfig};
+pub use wordlist::{TieBreak, WordListConfig, WordListEntry, build_word_list};
@@ -0,0 +1,180 @@
+//! Borrowed dense frequency matrices (word × corpus part)
+
+use crate::error::{DispersionError, Result};
+
+/// Memory order of a dense matrix buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MatrixLayout {
+    /// Rows are contiguous (C order)
+    RowMajor,
+    /// Columns are contiguous (Fortran order)
+    ColumnMajor,
+}
+
+/// Element types accepted in frequency matrices
+pub trait FrequencyValue: Copy + Send + Sync {
+    fn to_f64(self) -> f64;
+}
+
+impl FrequencyValue for f64 {
+    #[inline]
+    fn to_f64(self) -> f64 {
+        self
+    }
+}
+
+impl FrequencyValue for f32 {
+    #[inline]
+    fn to_f64(self) -> f64 {
+        f64::from(self)
+    }
+}
+
+impl FrequencyValue for i64 {
+    #[inline]
+    #[allow(clippy::cast_precision_loss)] // Token counts are far below 2^53
+    fn to_f64(self) -> f64 {
+        self as f64
+    }
+}
+
+/// Dense word × part frequency matrix borrowed from an existing buffer
+///
+/// Rows are words and columns are corpus parts. The buffer is never copied;
+/// rows are read element by element according to `layout`.
+#[derive(Debug, Clone, Copy)]
+pub struct FrequencyMatrix<'a, T> {
+    data: &'a [T],
+    n_rows: usize,
+    n_cols: usize,
+    layout: MatrixLayout,
+}
+
+impl<'a, T: FrequencyValue> FrequencyMatrix<'a, T> {
+    /// Wrap a contiguous buffer as an `n_rows` × `n_cols` matrix
+    ///
+    /// # Errors
+    ///
+    /// Returns `LengthMismatch` if `data.len() != n_rows * n_cols`.
+    pub const fn new(
+        data: &'a [T],
+        n_rows: usize,
+        n_cols: usize,
+        layout: MatrixLayout,
+    ) -> Result<Self> {
+        if data.len() != n_rows * n_cols {
+            return Err(DispersionError::LengthMismatch {
+                expected: n_rows * n_cols,
+                found: data.len(),
+            });
+        }
+        Ok(Self {
+            data,
+            n_rows,
+            n_cols,
+            layout,
+        })
+    }
+
+    #[must_use]
+    pub const fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    #[must_use]
+    pub const fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    #[must_use]
+    pub const fn layout(&self) -> MatrixLayout {
+        self.layout
+    }
+
+    /// Value at (`row`, `col`) converted to `f64`
+    #[inline]
+    #[must_use]
+    pub fn get(&self, row: usize, col: usize) -> f64 {
+        let idx = match self.layout {
+            MatrixLayout::RowMajor => row * self.n_cols + col,
+            MatrixLayout::ColumnMajor => col * self.n_rows + row,
+        };
+        self.data[idx].to_f64()
+    }
+
+    /// Iterate over one row as `f64` values
+    pub fn row(&self, row: usize) -> impl Iterator<Item = f64> + '_ {
+        (0..self.n_cols).map(move |col| self.get(row, col))
+    }
+
+    /// Check every cell for negative or non-finite values
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid cell (in row order) wrapped in `DispersionError::Row`.
+    pub fn validate(&self) -> Result<()> {
+        for row in 0..self.n_rows {
+            for (index, value) in self.row(row).enumerate() {
+                if !value.is_finite() {
+                    return Err(DispersionError::NonFiniteValue {
+                        field: "Frequency",
+                        index: Some(index),
+                        value,
+                    }
+                    .in_row(row));
+                }
+                if value < 0.0 {
+                    return Err(DispersionError::NegativeFrequency { index, value }.in_row(row));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Word × part matrices whose rows can be read as dense frequency vectors
+///
+/// Implemented by `FrequencyMatrix` and `CsrMatrix`, so batch computations
+/// that need a word's full frequency vector accept either layout.
+pub trait FrequencyRows: Sync {
+    /// Number of rows (words)
+    fn n_rows(&self) -> usize;
+
+    /// Number of columns (corpus parts)
+    fn n_cols(&self) -> usize;
+
+    /// Frequencies of one row, one per column
+    fn dense_row(&self, row: usize) -> Vec<f64>;
+
+    /// Total frequency of one row
+    fn row_total(&self, row: usize) -> f64;
+
+    /// Check every cell for negative or non-finite values
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid cell (in row order) wrapped in `DispersionError::Row`.
+    fn validate(&self) -> Result<()>;
+}
+
+impl<T: FrequencyValue> FrequencyRows for FrequencyMatrix<'_, T> {
+    fn n_rows(&self) -> usize {
+        Self::n_rows(self)
+    }
+
+    fn n_cols(&self) -> usize {
+        Self::n_cols(self)
+    }
+
+    fn dense_row(&self, row: usize) -> Vec<f64> {
+        self.row(row).collect()
+    }
+
+    fn row_total(&self, row: usize) -> f64 {
+        self.row(row).sum()
+    }
+
+    fn validate(&self) -> Result<()> {
+        Self::validate(self)
+    }
+}
@@ -1,61 +1,76 @@
 //! Dispersion metrics data structures and implementations
 
-use pyo3::prelude::*;
+use crate::error::{DispersionError, Result};
+use std::collections::HashMap;
+use std::fmt;
+use std::ops::BitOr;
 
-#[pyclass]
-#[derive(Debug, Clone)]
+/// Container for all computed dispersion metrics of a single word
+///
+/// Metrics left out of a `MetricSet` selection are `None`.
+#[cfg_attr(feature = "python", pyo3::pyclass(get_all))]
+#[derive(Debug, Clone, Default)]
 pub struct DispersionMetrics {
-    #[pyo3(get)]
+    /// Word label, set by labelled batch calls (see `LabelledMetrics`)
+    pub word: Option<String>,
     pub range: i32,
-    #[pyo3(get)]
     pub sd_population: Option<f64>,
-    #[pyo3(get)]
     pub vc_population: Option<f64>,
-    #[pyo3(get)]
     pub juilland_d: Option<f64>,
-    #[pyo3(get)]
+    pub juilland_u: Option<f64>,
     pub carroll_d2: Option<f64>,
-    #[pyo3(get)]
+    pub carroll_um: Option<f64>,
+    pub lyne_d3: Option<f64>,
     pub roschengren_s_adj: Option<f64>,
-    #[pyo3(get)]
+    pub roschengren_af: Option<f64>,
+    pub zhang_dc: Option<f64>,
     pub dp: Option<f64>,
-    #[pyo3(get)]
     pub dp_norm: Option<f64>,
-    #[pyo3(get)]
+    pub dp_nofreq: Option<f64>,
     pub kl_divergence: Option<f64>,
-    #[pyo3(get)]
+    pub kl_divergence_norm: Option<f64>,
     pub jsd_dispersion: Option<f64>,
-    #[pyo3(get)]
     pub hellinger_dispersion: Option<f64>,
-    #[pyo3(get)]
+    pub gini_coefficient: Option<f64>,
+    pub shannon_entropy: Option<f64>,
+    pub pielou_evenness: Option<f64>,
+    pub simpson_index: Option<f64>,
     pub mean_text_frequency_ft: Option<f64>,
-    #[pyo3(get)]
     pub pervasiveness_pt: Option<f64>,
-    #[pyo3(get)]
     pub evenness_da: Option<f64>,
-    #[pyo3(get)]
     pub ft_adjusted_by_pt: Option<f64>,
-    #[pyo3(get)]
     pub ft_adjusted_by_da: Option<f64>,
 }
 
-#[pymethods]
-impl DispersionMetrics {
-    fn __repr__(&self) -> String {
-        format!(
+impl fmt::Display for DispersionMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
             concat!(
                 "DispersionMetrics(",
+                "{}",
                 "range={}, ",
                 "sd_population={}, ",
                 "vc_population={}, ",
                 "juilland_d={}, ",
+                "juilland_u={}, ",
                 "carroll_d2={}, ",
+                "carroll_um={}, ",
+                "lyne_d3={}, ",
                 "roschengren_s_adj={}, ",
+                "roschengren_af={}, ",
+                "zhang_dc={}, ",
                 "dp={}, ",
                 "dp_norm={}, ",
+                "dp_nofreq={}, ",
                 "kl_divergence={}, ",
+                "kl_divergence_norm={}, ",
                 "jsd_dispersion={}, ",
                 "hellinger_dispersion={}, ",
+                "gini_coefficient={}, ",
+                "shannon_entropy={}, ",
+                "pielou_evenness={}, ",
+                "simpson_index={}, ",
                 "mean_text_frequency_ft={}, ",
                 "pervasiveness_pt={}, ",
                 "evenness_da={}, ",
@@ -63,17 +78,31 @@ impl DispersionMetrics {
                 "ft_adjusted_by_da={}",
                 ")"
             ),
+            self.word
+                .as_ref()
+                .map_or_else(String::new, |word| format!("word={word:?}, ")),
             self.range,
             Self::fmt_opt(self.sd_population),
             Self::fmt_opt(self.vc_population),
             Self::fmt_opt(self.juilland_d),
+            Self::fmt_opt(self.juilland_u),
             Self::fmt_opt(self.carroll_d2),
+            Self::fmt_opt(self.carroll_um),
+            Self::fmt_opt(self.lyne_d3),
             Self::fmt_opt(self.roschengren_s_adj),
+            Self::fmt_opt(self.roschengren_af),
+            Self::fmt_opt(self.zhang_dc),
             Self::fmt_opt(self.dp),
             Self::fmt_opt(self.dp_norm),
+            Self::fmt_opt(self.dp_nofreq),
             Self::fmt_opt(self.kl_divergence),
+            Self::fmt_opt(self.kl_divergence_norm),
             Self::fmt_opt(self.jsd_dispersion),
             Self::fmt_opt(self.hellinger_dispersion),
+            Self::fmt_opt(self.gini_coefficient),
+            Self::fmt_opt(self.shannon_entropy),
+            Self::fmt_opt(self.pielou_evenness),
+            Self::fmt_opt(self.simpson_index),
             Self::fmt_opt(self.mean_text_frequency_ft),
             Self::fmt_opt(self.pervasiveness_pt),
             Self::fmt_opt(self.evenness_da),
@@ -84,7 +113,428 @@ impl DispersionMetrics {
 }
 
 impl DispersionMetrics {
+    /// Names of the floating-point metric fields, in declaration order
+    pub const FLOAT_FIELDS: [&'static str; 26] = [
+        "sd_population",
+        "vc_population",
+        "juilland_d",
+        "juilland_u",
+        "carroll_d2",
+        "carroll_um",
+        "lyne_d3",
+        "roschengren_s_adj",
+        "roschengren_af",
+        "zhang_dc",
+        "dp",
+        "dp_norm",
+        "dp_nofreq",
+        "kl_divergence",
+        "kl_divergence_norm",
+        "jsd_dispersion",
+        "hellinger_dispersion",
+        "gini_coefficient",
+        "shannon_entropy",
+        "pielou_evenness",
+        "simpson_index",
+        "mean_text_frequency_ft",
+        "pervasiveness_pt",
+        "evenness_da",
+        "ft_adjusted_by_pt",
+        "ft_adjusted_by_da",
+    ];
+
+    /// Floating-point metric values in `FLOAT_FIELDS` order
+    #[must_use]
+    pub const fn float_values(&self) -> [Option<f64>; 26] {
+        [
+            self.sd_population,
+            self.vc_population,
+            self.juilland_d,
+            self.juilland_u,
+            self.carroll_d2,
+            self.carroll_um,
+            self.lyne_d3,
+            self.roschengren_s_adj,
+            self.roschengren_af,
+            self.zhang_dc,
+            self.dp,
+            self.dp_norm,
+            self.dp_nofreq,
+            self.kl_divergence,
+            self.kl_divergence_norm,
+            self.jsd_dispersion,
+            self.hellinger_dispersion,
+            self.gini_coefficient,
+            self.shannon_entropy,
+            self.pielou_evenness,
+            self.simpson_index,
+            self.mean_text_frequency_ft,
+            self.pervasiveness_pt,
+            self.evenness_da,
+            self.ft_adjusted_by_pt,
+            self.ft_adjusted_by_da,
+        ]
+    }
+
+    /// Value of a metric by name (`range` is returned as a float)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::UnknownMetric` if the name is not a metric field.
+    pub fn get(&self, name: &str) -> Result<Option<f64>> {
+        if name == "range" {
+            return Ok(Some(f64::from(self.range)));
+        }
+        Self::FLOAT_FIELDS
+            .iter()
+            .position(|&field| field == name)
+            .map(|index| self.float_values()[index])
+            .ok_or_else(|| DispersionError::UnknownMetric(name.to_string()))
+    }
+
+    /// Mutable references to the floating-point metric fields in `FLOAT_FIELDS` order
+    pub fn float_values_mut(&mut self) -> [&mut Option<f64>; 26] {
+        [
+            &mut self.sd_population,
+            &mut self.vc_population,
+            &mut self.juilland_d,
+            &mut self.juilland_u,
+            &mut self.carroll_d2,
+            &mut self.carroll_um,
+            &mut self.lyne_d3,
+            &mut self.roschengren_s_adj,
+            &mut self.roschengren_af,
+            &mut self.zhang_dc,
+            &mut self.dp,
+            &mut self.dp_norm,
+            &mut self.dp_nofreq,
+            &mut self.kl_divergence,
+            &mut self.kl_divergence_norm,
+            &mut self.jsd_dispersion,
+            &mut self.hellinger_dispersion,
+            &mut self.gini_coefficient,
+            &mut self.shannon_entropy,
+            &mut self.pielou_evenness,
+            &mut self.simpson_index,
+            &mut self.mean_text_frequency_ft,
+            &mut self.pervasiveness_pt,
+            &mut self.evenness_da,
+            &mut self.ft_adjusted_by_pt,
+            &mut self.ft_adjusted_by_da,
+        ]
+    }
+
+    /// Copy of these metrics with every field outside `selection` set to `None`
+    #[must_use]
+    pub fn masked(mut self, selection: MetricSet) -> Self {
+        for (index, value) in self.float_values_mut().into_iter().enumerate() {
+            if !selection.contains(MetricSet(1 << index)) {
+                *value = None;
+            }
+        }
+        self
+    }
+
     fn fmt_opt(val: Option<f64>) -> String {
         val.map_or_else(|| "None".to_string(), |v| format!("{v:.4}"))
     }
 }
+
+/// Set of metrics to compute, one flag per `DispersionMetrics::FLOAT_FIELDS` entry
+///
+/// `range` is always computed and has no flag. Combine flags with `|`:
+/// `MetricSet::DP | MetricSet::DP_NORM`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MetricSet(u32);
+
+impl MetricSet {
+    pub const SD_POPULATION: Self = Self(1 << 0);
+    pub const VC_POPULATION: Self = Self(1 << 1);
+    pub const JUILLAND_D: Self = Self(1 << 2);
+    pub const JUILLAND_U: Self = Self(1 << 3);
+    pub const CARROLL_D2: Self = Self(1 << 4);
+    pub const CARROLL_UM: Self = Self(1 << 5);
+    pub const LYNE_D3: Self = Self(1 << 6);
+    pub const ROSCHENGREN_S_ADJ: Self = Self(1 << 7);
+    pub const ROSCHENGREN_AF: Self = Self(1 << 8);
+    pub const ZHANG_DC: Self = Self(1 << 9);
+    pub const DP: Self = Self(1 << 10);
+    pub const DP_NORM: Self = Self(1 << 11);
+    pub const DP_NOFREQ: Self = Self(1 << 12);
+    pub const KL_DIVERGENCE: Self = Self(1 << 13);
+    pub const KL_DIVERGENCE_NORM: Self = Self(1 << 14);
+    pub const JSD_DISPERSION: Self = Self(1 << 15);
+    pub const HELLINGER_DISPERSION: Self = Self(1 << 16);
+    pub const GINI_COEFFICIENT: Self = Self(1 << 17);
+    pub const SHANNON_ENTROPY: Self = Self(1 << 18);
+    pub const PIELOU_EVENNESS: Self = Self(1 << 19);
+    pub const SIMPSON_INDEX: Self = Self(1 << 20);
+    pub const MEAN_TEXT_FREQUENCY_FT: Self = Self(1 << 21);
+    pub const PERVASIVENESS_PT: Self = Self(1 << 22);
+    pub const EVENNESS_DA: Self = Self(1 << 23);
+    pub const FT_ADJUSTED_BY_PT: Self = Self(1 << 24);
+    pub const FT_ADJUSTED_BY_DA: Self = Self(1 << 25);
+
+    /// No floating-point metric (only `range`)
+    pub const EMPTY: Self = Self(0);
+    /// Every metric
+    pub const ALL: Self = Self((1 << DispersionMetrics::FLOAT_FIELDS.len()) - 1);
+
+    /// Parse metric names as used in `DispersionMetrics` (`"range"` is accepted and ignored)
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::UnknownMetric` for an unrecognised name.
+    pub fn from_names<I, S>(names: I) -> Result<Self>
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut set = Self::EMPTY;
+        for name in names {
+            let name = name.as_ref();
+            if name == "range" {
+                continue;
+            }
+            let index = DispersionMetrics::FLOAT_FIELDS
+                .iter()
+                .position(|&field| field == name)
+                .ok_or_else(|| DispersionError::UnknownMetric(name.to_string()))?;
+            set.0 |= 1 << index;
+        }
+        Ok(set)
+    }
+
+    /// True if every flag of `other` is in `self`
+    #[must_use]
+    pub const fn contains(self, other: Self) -> bool {
+        self.0 & other.0 == other.0
+    }
+
+    /// True if `self` and `other` share at least one flag
+    #[must_use]
+    pub const fn intersects(self, other: Self) -> bool {
+        self.0 & other.0 != 0
+    }
+
+    #[must_use]
+    pub const fn union(self, other: Self) -> Self {
+        Self(self.0 | other.0)
+    }
+
+    /// Names of the selected metrics in `FLOAT_FIELDS` order
+    pub fn names(self) -> impl Iterator<Item = &'static str> {
+        DispersionMetrics::FLOAT_FIELDS
+            .iter()
+            .enumerate()
+            .filter(move |&(i, _)| self.0 & (1 << i) != 0)
+            .map(|(_, &name)| name)
+    }
+}
+
+impl Default for MetricSet {
+    fn default() -> Self {
+        Self::ALL
+    }
+}
+
+impl BitOr for MetricSet {
+    type Output = Self;
+
+    fn bitor(self, rhs: Self) -> Self {
+        self.union(rhs)
+    }
+}
+
+/// Metric values at the theoretical extremes for a word's total frequency
+///
+/// For `f` tokens and relative part sizes `s`, `most_even` is the allocation
+/// closest to `f·s` in integer tokens and `most_clumped` puts every token in
+/// the smallest non-empty part. For the size-aware metrics in
+/// `NORMALIZABLE` these are the extremes any distribution of the word can
+/// reach (up to integer rounding), so observed values can be min–max
+/// normalized. The spread of raw counts (`sd_population`, `vc_population`)
+/// and the FT family are not extreme at these allocations when part sizes
+/// differ, and are not normalized.
+#[cfg_attr(feature = "python", pyo3::pyclass(get_all))]
+#[derive(Debug, Clone)]
+pub struct MetricBounds {
+    pub most_even: Vec<f64>,
+    pub most_clumped: Vec<f64>,
+    pub even_metrics: DispersionMetrics,
+    pub clumped_metrics: DispersionMetrics,
+}
+
+impl MetricBounds {
+    /// Metrics bracketed by the most even and most clumped allocations
+    /// (`range` is normalized too)
+    pub const NORMALIZABLE: MetricSet = MetricSet(
+        MetricSet::ALL.0
+            & !(MetricSet::SD_POPULATION.0
+                | MetricSet::VC_POPULATION.0
+                | MetricSet::MEAN_TEXT_FREQUENCY_FT.0
+                | MetricSet::FT_ADJUSTED_BY_PT.0
+                | MetricSet::FT_ADJUSTED_BY_DA.0),
+    );
+
+    /// Min–max normalize `value` of metric `name` between the two extremes
+    ///
+    /// The result is oriented the same way for every metric: 0 at the most
+    /// even distribution, 1 at the most clumped one. Values beyond a bound
+    /// (possible through integer rounding of the even allocation) are
+    /// clamped to [0, 1]. `None` for a metric outside `NORMALIZABLE`, or if
+    /// either bound is undefined or the bounds coincide.
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::UnknownMetric` if the name is not a metric field.
+    pub fn normalize(&self, name: &str, value: f64) -> Result<Option<f64>> {
+        if !Self::NORMALIZABLE.contains(MetricSet::from_names([name])?) {
+            return Ok(None);
+        }
+        let (Some(even), Some(clumped)) = (
+            self.even_metrics.get(name)?,
+            self.clumped_metrics.get(name)?,
+        ) else {
+            return Ok(None);
+        };
+        let span = clumped - even;
+        if span.abs() < 1e-12 {
+            return Ok(None);
+        }
+        Ok(Some(((value - even) / span).clamp(0.0, 1.0)))
+    }
+}
+
+/// Column-oriented batch results: one vector per metric, one entry per word
+///
+/// Missing values (`None` in `DispersionMetrics`) are stored as `NaN`.
+#[derive(Debug, Clone, Default)]
+pub struct MetricColumns {
+    pub range: Vec<i32>,
+    pub columns: Vec<(&'static str, Vec<f64>)>,
+}
+
+impl MetricColumns {
+    /// Transpose per-word metrics into columns
+    #[must_use]
+    pub fn from_metrics(metrics: &[DispersionMetrics]) -> Self {
+        Self::from_selected(metrics, MetricSet::ALL)
+    }
+
+    /// Transpose per-word metrics into columns, keeping only `selection`
+    #[must_use]
+    pub fn from_selected(metrics: &[DispersionMetrics], selection: MetricSet) -> Self {
+        let indices: Vec<usize> = (0..DispersionMetrics::FLOAT_FIELDS.len())
+            .filter(|&i| selection.contains(MetricSet(1 << i)))
+            .collect();
+        let mut columns: Vec<(&'static str, Vec<f64>)> = indices
+            .iter()
+            .map(|&i| {
+                (
+                    DispersionMetrics::FLOAT_FIELDS[i],
+                    Vec::with_capacity(metrics.len()),
+                )
+            })
+            .collect();
+        let mut range = Vec::with_capacity(metrics.len());
+
+        for m in metrics {
+            range.push(m.range);
+            let values = m.float_values();
+            for ((_, column), &i) in columns.iter_mut().zip(&indices) {
+                column.push(values[i].unwrap_or(f64::NAN));
+            }
+        }
+
+        Self { range, columns }
+    }
+
+    /// Number of words (rows)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.range.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.range.is_empty()
+    }
+
+    /// Column for a floating-point metric by name
+    #[must_use]
+    pub fn get(&self, name: &str) -> Option<&[f64]> {
+        self.columns
+            .iter()
+            .find(|(column_name, _)| *column_name == name)
+            .map(|(_, values)| values.as_slice())
+    }
+}
+
+/// Batch results whose rows can also be looked up by word
+///
+/// Row `i` keeps its position and carries `words[i]` in
+/// `DispersionMetrics::word`. If a word occurs more than once, lookups by
+/// word return its first row.
+#[cfg_attr(feature = "python", pyo3::pyclass(sequence))]
+#[derive(Debug, Clone, Default)]
+pub struct LabelledMetrics {
+    metrics: Vec<DispersionMetrics>,
+    positions: HashMap<String, usize>,
+}
+
+impl LabelledMetrics {
+    /// Attach `words[i]` to `metrics[i]`
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::LabelCountMismatch` if the lengths differ.
+    pub fn new(words: Vec<String>, mut metrics: Vec<DispersionMetrics>) -> Result<Self> {
+        if words.len() != metrics.len() {
+            return Err(DispersionError::LabelCountMismatch {
+                expected: metrics.len(),
+                found: words.len(),
+            });
+        }
+        let mut positions = HashMap::with_capacity(words.len());
+        for (row, (word, m)) in words.into_iter().zip(&mut metrics).enumerate() {
+            positions.entry(word.clone()).or_insert(row);
+            m.word = Some(word);
+        }
+        Ok(Self { metrics, positions })
+    }
+
+    /// Metrics of a word by label
+    #[must_use]
+    pub fn get(&self, word: &str) -> Option<&DispersionMetrics> {
+        self.position(word).map(|row| &self.metrics[row])
+    }
+
+    /// Row index of a word
+    #[must_use]
+    pub fn position(&self, word: &str) -> Option<usize> {
+        self.positions.get(word).copied()
+    }
+
+    /// Metrics in row order
+    #[must_use]
+    pub fn as_slice(&self) -> &[DispersionMetrics] {
+        &self.metrics
+    }
+
+    #[must_use]
+    pub fn into_vec(self) -> Vec<DispersionMetrics> {
+        self.metrics
+    }
+
+    /// Number of words (rows)
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.metrics.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.metrics.is_empty()
+    }
+}
@@ -0,0 +1,137 @@
+//! Thread pool selection for parallel batch computations
+//!
+//! Batch entry points run their rayon loops on the process-wide default pool
+//! (rayon's global pool unless changed with `set_default_parallelism`). Wrap a
+//! call in `Parallelism::install` to run it on a specific pool instead.
+
+use crate::error::{DispersionError, Result};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use std::sync::{Arc, Mutex, PoisonError, RwLock};
+
+/// Process-wide pool for batch work (`None` = rayon's global pool)
+static DEFAULT_POOL: RwLock<Option<Arc<ThreadPool>>> = RwLock::new(None);
+
+/// Dedicated pools by thread count, most recently used last
+///
+/// Only the `MAX_SIZED_POOLS` most recently used sizes are kept; an evicted
+/// pool shuts its threads down once no running call or default setting
+/// still holds it, and is rebuilt if its size is asked for again.
+static SIZED_POOLS: Mutex<Vec<(usize, Arc<ThreadPool>)>> = Mutex::new(Vec::new());
+
+const MAX_SIZED_POOLS: usize = 4;
+
+/// Where batch computations run
+#[derive(Debug, Clone, Default)]
+pub enum Parallelism {
+    /// The process-wide default set with `set_default_parallelism`
+    #[default]
+    Default,
+    /// A single worker thread processing rows one after another, in order
+    Sequential,
+    /// A dedicated pool with this many threads (0 lets rayon pick one per core)
+    Threads(usize),
+    /// A caller-provided pool
+    Pool(Arc<ThreadPool>),
+}
+
+impl Parallelism {
+    /// Parallelism for an optional thread count (`None` = default, `1` = sequential)
+    #[must_use]
+    pub const fn from_n_threads(n_threads: Option<usize>) -> Self {
+        match n_threads {
+            None => Self::Default,
+            Some(1) => Self::Sequential,
+            Some(n) => Self::Threads(n),
+        }
+    }
+
+    /// Run `op`, and every rayon parallel iterator inside it, on this pool
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::ThreadPool` if a dedicated pool cannot be built.
+    pub fn install<R: Send>(&self, op: impl FnOnce() -> R + Send) -> Result<R> {
+        Ok(match self.pool()? {
+            Some(pool) => pool.install(op),
+            None => op(),
+        })
+    }
+
+    /// Number of worker threads batch work will use
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::ThreadPool` if a dedicated pool cannot be built.
+    pub fn num_threads(&self) -> Result<usize> {
+        Ok(self
+            .pool()?
+            .map_or_else(rayon::current_num_threads, |pool| {
+                pool.current_num_threads()
+            }))
+    }
+
+    /// Resolve to a concrete pool; `None` means rayon's global pool
+    fn pool(&self) -> Result<Option<Arc<ThreadPool>>> {
+        match self {
+            Self::Default => Ok(DEFAULT_POOL
+                .read()
+                .unwrap_or_else(PoisonError::into_inner)
+                .clone()),
+            Self::Sequential => sized_pool(1).map(Some),
+            Self::Threads(n) => sized_pool(*n).map(Some),
+            Self::Pool(pool) => Ok(Some(Arc::clone(pool))),
+        }
+    }
+}
+
+/// Set the pool used by batch computations that do not choose their own
+///
+/// `Parallelism::Default` restores rayon's global pool.
+///
+/// # Errors
+///
+/// Returns `DispersionError::ThreadPool` if a dedicated pool cannot be built.
+pub fn set_default_parallelism(parallelism: &Parallelism) -> Result<()> {
+    let pool = match parallelism {
+        Parallelism::Default => None,
+        other => other.pool()?,
+    };
+    *DEFAULT_POOL.write().unwrap_or_else(PoisonError::into_inner) = pool;
+    Ok(())
+}
+
+/// Run batch work on the process-wide default pool
+///
+/// Work started from inside a rayon pool (e.g. under `Parallelism::install`)
+/// stays on that pool.
+pub(crate) fn run_batch<R: Send>(op: impl FnOnce() -> R + Send) -> R {
+    let pool = DEFAULT_POOL
+        .read()
+        .unwrap_or_else(PoisonError::into_inner)
+        .clone();
+    match pool {
+        Some(pool) if rayon::current_thread_index().is_none() => pool.install(op),
+        _ => op(),
+    }
+}
+
+fn sized_pool(n_threads: usize) -> Result<Arc<ThreadPool>> {
+    let mut pools = SIZED_POOLS.lock().unwrap_or_else(PoisonError::into_inner);
+    if let Some(index) = pools.iter().position(|(n, _)| *n == n_threads) {
+        let entry = pools.remove(index);
+        let pool = Arc::clone(&entry.1);
+        pools.push(entry);
+        return Ok(pool);
+    }
+    let pool = ThreadPoolBuilder::new()
+        .num_threads(n_threads)
+        .thread_name(|i| format!("corpus-dispersion-{i}"))
+        .build()
+        .map_err(|e| DispersionError::ThreadPool(e.to_string()))?;
+    let pool = Arc::new(pool);
+    if pools.len() == MAX_SIZED_POOLS {
+        pools.remove(0);
+    }
+    pools.push((n_threads, Arc::clone(&pool)));
+    Ok(pool)
+}
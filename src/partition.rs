@@ -0,0 +1,267 @@
+//! Corpus partition shared by every word of an analysis
+
+use crate::error::{Result, validate_partition};
+
+/// Corpus part sizes and the per-part quantities every metric relies on
+///
+/// Build once per corpus and share it (through `Arc`) between analyzers and
+/// batch calls instead of recomputing `s` and friends for every word.
+#[derive(Debug, Clone)]
+pub struct CorpusPartition {
+    /// Corpus partition sizes in words
+    part_sizes: Vec<f64>,
+    /// Total corpus size in words
+    total_corpus_words: f64,
+    /// Relative partition sizes (s vector)
+    s: Vec<f64>,
+    /// Natural log of `s` (`-inf` for empty parts)
+    ln_s: Vec<f64>,
+    /// Square root of `s`
+    sqrt_s: Vec<f64>,
+    /// Smallest relative partition size
+    min_s: f64,
+    /// Sum of `s` (1.0 when the total equals the sum of part sizes)
+    sum_s: f64,
+    /// JSD `q·ln(q/m)` term of each part when the word is absent there
+    jsd_zero_terms: Vec<f64>,
+    /// Sum of `jsd_zero_terms`
+    jsd_zero_total: f64,
+    /// Non-empty parts from largest to smallest (ties in part order)
+    size_order: Vec<usize>,
+    /// Share of the summed part sizes of each part in `size_order`
+    sorted_shares: Vec<f64>,
+    /// Smallest non-empty part (part 0 if every part is empty)
+    smallest_part: usize,
+}
+
+impl CorpusPartition {
+    /// Create a partition from part sizes and the total corpus size
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError` if part sizes are empty, negative or
+    /// non-finite, or the total is not positive.
+    pub fn new(part_sizes: Vec<f64>, total_corpus_words: f64) -> Result<Self> {
+        validate_partition(&part_sizes, total_corpus_words)?;
+
+        let s: Vec<f64> = part_sizes
+            .iter()
+            .map(|&size| size / total_corpus_words)
+            .collect();
+        let ln_s = s.iter().map(|&s_i| s_i.ln()).collect();
+        let sqrt_s = s.iter().map(|&s_i| s_i.sqrt()).collect();
+        let min_s = s.iter().copied().fold(f64::INFINITY, f64::min);
+        let sum_s = s.iter().sum();
+        let jsd_zero_terms: Vec<f64> = s.iter().map(|&q| jsd_q_term(q, 0.5 * q)).collect();
+        let jsd_zero_total = jsd_zero_terms.iter().sum();
+
+        let size_sum: f64 = part_sizes.iter().sum();
+        let mut size_order: Vec<usize> = (0..part_sizes.len())
+            .filter(|&i| part_sizes[i] > 0.0)
+            .collect();
+        size_order.sort_by(|&a, &b| part_sizes[b].total_cmp(&part_sizes[a]).then(a.cmp(&b)));
+        let sorted_shares = size_order
+            .iter()
+            .map(|&i| part_sizes[i] / size_sum)
+            .collect();
+        let smallest_part = size_order
+            .iter()
+            .copied()
+            .min_by(|&a, &b| part_sizes[a].total_cmp(&part_sizes[b]))
+            .unwrap_or(0);
+
+        Ok(Self {
+            part_sizes,
+            total_corpus_words,
+            s,
+            ln_s,
+            sqrt_s,
+            min_s,
+            sum_s,
+            jsd_zero_terms,
+            jsd_zero_total,
+            size_order,
+            sorted_shares,
+            smallest_part,
+        })
+    }
+
+    /// Create a partition whose total is the sum of the part sizes
+    ///
+    /// # Errors
+    ///
+    /// Same as `new`.
+    pub fn from_part_sizes(part_sizes: Vec<f64>) -> Result<Self> {
+        let total = part_sizes.iter().sum();
+        Self::new(part_sizes, total)
+    }
+
+    /// Number of corpus parts
+    #[must_use]
+    pub fn n_parts(&self) -> usize {
+        self.part_sizes.len()
+    }
+
+    #[must_use]
+    pub fn part_sizes(&self) -> &[f64] {
+        &self.part_sizes
+    }
+
+    #[must_use]
+    pub const fn total_corpus_words(&self) -> f64 {
+        self.total_corpus_words
+    }
+
+    /// Relative partition sizes (s vector)
+    #[must_use]
+    pub fn s(&self) -> &[f64] {
+        &self.s
+    }
+
+    #[must_use]
+    pub fn ln_s(&self) -> &[f64] {
+        &self.ln_s
+    }
+
+    #[must_use]
+    pub fn sqrt_s(&self) -> &[f64] {
+        &self.sqrt_s
+    }
+
+    #[must_use]
+    pub const fn min_s(&self) -> f64 {
+        self.min_s
+    }
+
+    #[must_use]
+    pub const fn sum_s(&self) -> f64 {
+        self.sum_s
+    }
+
+    /// Most even allocation of `f` tokens over the parts
+    ///
+    /// Each non-empty part gets `floor(f·p_i)` tokens, where `p_i` is its share
+    /// of the summed part sizes, and the remaining tokens go one at a time to
+    /// the parts with the largest remainders (largest remainder method), which
+    /// minimises `Σ|v_i − f·p_i|`. A fractional leftover (for non-integer `f`)
+    /// goes to the next part in line. Parts of size zero get no tokens, and the
+    /// allocation sums to `f` even when the total corpus size differs from the
+    /// sum of the part sizes.
+    #[must_use]
+    pub fn most_even_distribution(&self, f: f64) -> Vec<f64> {
+        let mut v = vec![0.0; self.n_parts()];
+        for (part, tokens) in self.even_allocation(f) {
+            v[part] = tokens;
+        }
+        v
+    }
+
+    /// Most clumped allocation of `f` tokens: all of them in the smallest
+    /// non-empty part
+    #[must_use]
+    pub fn most_clumped_distribution(&self, f: f64) -> Vec<f64> {
+        let mut v = vec![0.0; self.n_parts()];
+        v[self.smallest_part] = f;
+        v
+    }
+
+    /// Smallest and largest DP achievable by a word with total frequency `f`
+    ///
+    /// Parts left empty by an allocation add `s_i` to its DP, so only the
+    /// parts that receive tokens are visited: the cost grows with
+    /// `min(n_parts, f)`, not with the number of parts.
+    #[must_use]
+    pub fn dp_range(&self, f: f64) -> (f64, f64) {
+        if f == 0.0 {
+            return (0.0, 0.0);
+        }
+        let (mut spread, mut covered) = (0.0, 0.0);
+        for (part, tokens) in self.even_allocation(f) {
+            spread += (tokens / f - self.s[part]).abs();
+            covered += self.s[part];
+        }
+        let min_dp = 0.5 * (spread + (self.sum_s - covered).max(0.0));
+        let s_clumped = self.s[self.smallest_part];
+        let max_dp = 0.5 * ((1.0 - s_clumped).abs() + self.sum_s - s_clumped);
+        (min_dp, max_dp)
+    }
+
+    /// Parts receiving tokens in `most_even_distribution`, with their tokens
+    ///
+    /// Parts whose expected count `f·p_i` reaches one token are a prefix of
+    /// the cached size order; every later part has a remainder of `f·p_i`,
+    /// already in decreasing order. Only the prefix is sorted by remainder.
+    fn even_allocation(&self, f: f64) -> Vec<(usize, f64)> {
+        let shares = &self.sorted_shares;
+        let n_head = shares.partition_point(|&p| f * p >= 1.0);
+        let mut allocation: Vec<(usize, f64)> = Vec::with_capacity(n_head);
+        let mut head: Vec<(f64, usize)> = Vec::with_capacity(n_head);
+        for (position, &p) in shares[..n_head].iter().enumerate() {
+            let expected = f * p;
+            let floor = expected.floor();
+            allocation.push((self.size_order[position], floor));
+            head.push((expected - floor, position));
+        }
+        head.sort_by(|a, b| {
+            b.0.total_cmp(&a.0)
+                .then(self.size_order[a.1].cmp(&self.size_order[b.1]))
+        });
+        let mut leftover = f - allocation.iter().map(|&(_, tokens)| tokens).sum::<f64>();
+
+        // Merge the head remainders with the tail, largest remainder first
+        let (mut h, mut t) = (0, n_head);
+        while leftover > 0.0 {
+            let token = leftover.min(1.0);
+            let take_head = match (head.get(h), shares.get(t)) {
+                (Some(&(remainder, position)), Some(&p)) => remainder
+                    .total_cmp(&(f * p))
+                    .then(self.size_order[t].cmp(&self.size_order[position]))
+                    .is_ge(),
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            if take_head {
+                allocation[head[h].1].1 += token;
+                h += 1;
+            } else {
+                allocation.push((self.size_order[t], token));
+                t += 1;
+            }
+            leftover -= token;
+        }
+        allocation
+    }
+
+    pub(crate) fn jsd_zero_terms(&self) -> &[f64] {
+        &self.jsd_zero_terms
+    }
+
+    pub(crate) const fn jsd_zero_total(&self) -> f64 {
+        self.jsd_zero_total
+    }
+}
+
+/// DP rescaled to `[0, 1]` between its minimum and maximum for frequency `f`
+///
+/// `None` when the two coincide (e.g. a single part), as the rescaling is undefined.
+pub(crate) fn dp_nofreq(partition: &CorpusPartition, dp: f64, f: f64) -> Option<f64> {
+    if f == 0.0 {
+        return Some(0.0);
+    }
+    let (min_dp, max_dp) = partition.dp_range(f);
+    let span = max_dp - min_dp;
+    if span < 1e-12 {
+        return None;
+    }
+    Some(((dp - min_dp) / span).clamp(0.0, 1.0))
+}
+
+/// `q·ln(q/m)` term of the JSD, skipped for (near-)zero probabilities
+pub(crate) fn jsd_q_term(q: f64, m: f64) -> f64 {
+    if q > 1e-12 && m > 1e-12 {
+        q * (q / m).ln()
+    } else {
+        0.0
+    }
+}
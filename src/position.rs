@@ -0,0 +1,287 @@
+//! Position-based dispersion from token offsets in a running corpus
+//!
+//! Instead of per-part frequencies, these metrics use the distances between
+//! consecutive occurrences of a word (Savický & Hlaváčová 2002; Washtell
+//! 2009), which also reveals whether a word occurs in bursts. The corpus is
+//! treated as circular: the first distance wraps around from the last
+//! occurrence to the first.
+
+use crate::error::{DispersionError, Result};
+use crate::parallel::run_batch;
+use std::fmt;
+
+/// Position-based dispersion metrics of a single word
+#[cfg_attr(feature = "python", pyo3::pyclass(get_all))]
+#[derive(Debug, Clone)]
+pub struct PositionMetrics {
+    /// Number of occurrences
+    pub frequency: usize,
+    /// Average reduced frequency (ARF)
+    pub arf: Option<f64>,
+    /// Average waiting time (AWT)
+    pub awt: Option<f64>,
+    /// Average logarithmic distance (ALD)
+    pub ald: Option<f64>,
+    /// Washtell's self-dispersion (nearest-neighbour distance ratio)
+    pub self_dispersion: Option<f64>,
+    /// Mean distance between consecutive occurrences
+    pub mean_distance: Option<f64>,
+    /// Population variance of the distances
+    pub distance_variance: Option<f64>,
+    /// Burstiness coefficient `(σ − μ) / (σ + μ)` of the distances
+    pub burstiness: Option<f64>,
+}
+
+impl fmt::Display for PositionMetrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt_opt =
+            |val: Option<f64>| val.map_or_else(|| "None".to_string(), |v| format!("{v:.4}"));
+        write!(
+            f,
+            concat!(
+                "PositionMetrics(frequency={}, arf={}, awt={}, ald={}, ",
+                "self_dispersion={}, mean_distance={}, distance_variance={}, burstiness={})"
+            ),
+            self.frequency,
+            fmt_opt(self.arf),
+            fmt_opt(self.awt),
+            fmt_opt(self.ald),
+            fmt_opt(self.self_dispersion),
+            fmt_opt(self.mean_distance),
+            fmt_opt(self.distance_variance),
+            fmt_opt(self.burstiness)
+        )
+    }
+}
+
+/// Analyzer for dispersion metrics computed from token positions
+#[derive(Debug, Clone)]
+pub struct PositionAnalyzer {
+    /// Sorted 0-based token offsets of the word
+    positions: Vec<usize>,
+    /// Corpus length in tokens
+    corpus_length: usize,
+    /// Distances between consecutive occurrences (first one wraps around)
+    distances: Vec<f64>,
+}
+
+impl PositionAnalyzer {
+    /// Create an analyzer from the word's token offsets
+    ///
+    /// # Arguments
+    ///
+    /// * `positions` - Strictly increasing 0-based offsets of the word
+    /// * `corpus_length` - Number of tokens in the corpus
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError` if the corpus length is zero or a position is
+    /// out of range or not strictly increasing.
+    pub fn new(positions: Vec<usize>, corpus_length: usize) -> Result<Self> {
+        validate_positions(&positions, corpus_length)?;
+        Ok(Self::from_validated(positions, corpus_length))
+    }
+
+    #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+    fn from_validated(positions: Vec<usize>, corpus_length: usize) -> Self {
+        let distances = match (positions.first(), positions.last()) {
+            (Some(&first), Some(&last)) => std::iter::once(first + corpus_length - last)
+                .chain(positions.windows(2).map(|w| w[1] - w[0]))
+                .map(|d| d as f64)
+                .collect(),
+            _ => Vec::new(),
+        };
+        Self {
+            positions,
+            corpus_length,
+            distances,
+        }
+    }
+
+    /// Number of occurrences
+    #[must_use]
+    pub fn get_frequency(&self) -> usize {
+        self.positions.len()
+    }
+
+    #[must_use]
+    pub const fn get_corpus_length(&self) -> usize {
+        self.corpus_length
+    }
+
+    #[must_use]
+    pub fn get_positions(&self) -> &[usize] {
+        &self.positions
+    }
+
+    /// Distances between consecutive occurrences, starting with the wrap-around one
+    #[must_use]
+    pub fn get_distances(&self) -> &[f64] {
+        &self.distances
+    }
+
+    /// Average reduced frequency: `ARF = (1/v) Σ min(d_i, v)` with `v = N/f`
+    ///
+    /// Ranges from 1 (all occurrences adjacent) to `f` (evenly spaced).
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+    pub fn get_arf(&self) -> Option<f64> {
+        if self.distances.is_empty() {
+            return Some(0.0);
+        }
+        let v = self.corpus_length as f64 / self.distances.len() as f64;
+        Some(self.distances.iter().map(|&d| d.min(v)).sum::<f64>() / v)
+    }
+
+    /// Average waiting time: `AWT = (1 + Σ d_i² / N) / 2`
+    ///
+    /// Expected number of tokens read from a random position until the word
+    /// occurs; `None` if the word does not occur.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+    pub fn get_awt(&self) -> Option<f64> {
+        if self.distances.is_empty() {
+            return None;
+        }
+        let sum_sq = self.distances.iter().map(|&d| d * d).sum::<f64>();
+        Some(0.5 * (1.0 + sum_sq / self.corpus_length as f64))
+    }
+
+    /// Average logarithmic distance: `ALD = Σ (d_i / N) · log10(d_i)`
+    ///
+    /// `None` if the word does not occur.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+    pub fn get_ald(&self) -> Option<f64> {
+        if self.distances.is_empty() {
+            return None;
+        }
+        let n = self.corpus_length as f64;
+        Some(self.distances.iter().map(|&d| d / n * d.log10()).sum())
+    }
+
+    /// Washtell's (2009) self-dispersion
+    ///
+    /// Mean distance from each occurrence to its nearest neighbour divided by
+    /// the `N / 2f` expected for randomly placed occurrences: below 1 the word
+    /// clusters, around 1 it is random, 2 for perfectly even spacing. `None`
+    /// with fewer than two occurrences.
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+    pub fn get_self_dispersion(&self) -> Option<f64> {
+        let f = self.distances.len();
+        if f < 2 {
+            return None;
+        }
+        // Occurrence i has distance d_i to the previous and d_{i+1} to the next one
+        let sum_nearest: f64 = (0..f)
+            .map(|i| self.distances[i].min(self.distances[(i + 1) % f]))
+            .sum();
+        let mean_nearest = sum_nearest / f as f64;
+        Some(mean_nearest / (self.corpus_length as f64 / (2.0 * f as f64)))
+    }
+
+    /// Mean distance between consecutive occurrences (`N / f` on the circular corpus)
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+    pub fn get_mean_distance(&self) -> Option<f64> {
+        if self.distances.is_empty() {
+            return None;
+        }
+        Some(self.distances.iter().sum::<f64>() / self.distances.len() as f64)
+    }
+
+    /// Population variance of the distances between consecutive occurrences
+    #[must_use]
+    #[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+    pub fn get_distance_variance(&self) -> Option<f64> {
+        let mean = self.get_mean_distance()?;
+        Some(
+            self.distances
+                .iter()
+                .map(|&d| (d - mean).powi(2))
+                .sum::<f64>()
+                / self.distances.len() as f64,
+        )
+    }
+
+    /// Burstiness `B = (σ − μ) / (σ + μ)` of the distances (Goh & Barabási 2008)
+    ///
+    /// −1 for perfectly periodic occurrences, about 0 for random (Poisson)
+    /// ones, approaching 1 for bursts. `None` with fewer than two occurrences.
+    #[must_use]
+    pub fn get_burstiness(&self) -> Option<f64> {
+        if self.distances.len() < 2 {
+            return None;
+        }
+        let mu = self.get_mean_distance()?;
+        let sigma = self.get_distance_variance()?.sqrt();
+        Some((sigma - mu) / (sigma + mu))
+    }
+
+    pub fn calculate_all_metrics(&self) -> PositionMetrics {
+        PositionMetrics {
+            frequency: self.get_frequency(),
+            arf: self.get_arf(),
+            awt: self.get_awt(),
+            ald: self.get_ald(),
+            self_dispersion: self.get_self_dispersion(),
+            mean_distance: self.get_mean_distance(),
+            distance_variance: self.get_distance_variance(),
+            burstiness: self.get_burstiness(),
+        }
+    }
+
+    /// Calculate position metrics for multiple words in parallel
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid word's error wrapped in `DispersionError::Row`.
+    pub fn calculate_batch_metrics(
+        positions: Vec<Vec<usize>>,
+        corpus_length: usize,
+    ) -> Result<Vec<PositionMetrics>> {
+        use rayon::prelude::*;
+
+        for (i, word) in positions.iter().enumerate() {
+            validate_positions(word, corpus_length).map_err(|e| e.in_row(i))?;
+        }
+
+        Ok(run_batch(|| {
+            positions
+                .into_par_iter()
+                .map(|word| Self::from_validated(word, corpus_length).calculate_all_metrics())
+                .collect()
+        }))
+    }
+}
+
+/// Check that offsets are strictly increasing and inside the corpus
+///
+/// # Errors
+///
+/// Returns `NonPositiveTotal` for an empty corpus and `InvalidPosition` otherwise.
+pub fn validate_positions(positions: &[usize], corpus_length: usize) -> Result<()> {
+    if corpus_length == 0 {
+        return Err(DispersionError::NonPositiveTotal(0.0));
+    }
+    let mut previous: Option<usize> = None;
+    for (index, &value) in positions.iter().enumerate() {
+        if value >= corpus_length {
+            return Err(DispersionError::InvalidPosition {
+                index,
+                value,
+                reason: "is outside the corpus",
+            });
+        }
+        if previous.is_some_and(|p| value <= p) {
+            return Err(DispersionError::InvalidPosition {
+                index,
+                value,
+                reason: "is not greater than the previous position",
+            });
+        }
+        previous = Some(value);
+    }
+    Ok(())
+}
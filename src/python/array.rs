@@ -0,0 +1,251 @@
+//! Conversion of Python frequency matrices (nested lists or NumPy arrays)
+
+use crate::error::{DispersionError, Result};
+use crate::matrix::{FrequencyMatrix, FrequencyValue, MatrixLayout};
+use crate::sparse::{CsrMatrix, SparseIndex};
+use numpy::{
+    Element, PyArray1, PyArray2, PyArrayMethods, PyReadonlyArray1, PyReadonlyArray2,
+    PyUntypedArrayMethods,
+};
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyList, PyTuple};
+
+/// Frequency matrix argument accepted by batch entry points
+///
+/// Lists of lists are copied as before; 2-D NumPy arrays are borrowed through
+/// the buffer protocol and read in place; SciPy sparse matrices are read
+/// through their CSR index arrays.
+pub enum MatrixInput<'py> {
+    Nested(Vec<Vec<f64>>),
+    Array(ArrayInput<'py>),
+    Sparse(SparseInput<'py>),
+}
+
+/// 2-D NumPy array of one of the supported dtypes
+pub enum ArrayInput<'py> {
+    F64(PyReadonlyArray2<'py, f64>),
+    F32(PyReadonlyArray2<'py, f32>),
+    I64(PyReadonlyArray2<'py, i64>),
+}
+
+/// SciPy CSR matrix (CSC input is converted to CSR once)
+pub struct SparseInput<'py> {
+    indices: SparseIndices<'py>,
+    data: PyReadonlyArray1<'py, f64>,
+    shape: (usize, usize),
+}
+
+/// CSR `indptr` and `indices` arrays; int32 (SciPy's default) is borrowed,
+/// any other index dtype is converted to int64
+pub enum SparseIndices<'py> {
+    I32 {
+        indptr: PyReadonlyArray1<'py, i32>,
+        indices: PyReadonlyArray1<'py, i32>,
+    },
+    I64 {
+        indptr: PyReadonlyArray1<'py, i64>,
+        indices: PyReadonlyArray1<'py, i64>,
+    },
+}
+
+impl<'py> SparseInput<'py> {
+    fn extract(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        let format: String = ob.getattr("format")?.extract()?;
+        let mut csr = match format.as_str() {
+            "csr" => ob.clone(),
+            "csc" => ob.call_method0("tocsr")?,
+            other => {
+                return Err(PyTypeError::new_err(format!(
+                    "sparse frequency_matrix must be in CSR or CSC format, got '{other}'"
+                )));
+            }
+        };
+        // Duplicate or unsorted column indices are merged on a copy so the
+        // caller's matrix is left untouched.
+        if !csr.getattr("has_canonical_format")?.extract::<bool>()? {
+            csr = csr.call_method0("copy")?;
+            csr.call_method0("sum_duplicates")?;
+        }
+        let py = ob.py();
+        let as_dtype = |name: &str, dtype: &str| -> PyResult<Bound<'py, PyAny>> {
+            let kwargs = PyDict::new(py);
+            kwargs.set_item("copy", false)?;
+            csr.getattr(name)?
+                .call_method("astype", (dtype,), Some(&kwargs))
+        };
+        let (indptr, indices) = (csr.getattr("indptr")?, csr.getattr("indices")?);
+        let indices = match (
+            indptr.downcast::<PyArray1<i32>>(),
+            indices.downcast::<PyArray1<i32>>(),
+        ) {
+            (Ok(indptr), Ok(indices)) => SparseIndices::I32 {
+                indptr: indptr.readonly(),
+                indices: indices.readonly(),
+            },
+            _ => SparseIndices::I64 {
+                indptr: as_dtype("indptr", "int64")?.extract()?,
+                indices: as_dtype("indices", "int64")?.extract()?,
+            },
+        };
+        Ok(Self {
+            indices,
+            data: as_dtype("data", "float64")?.extract()?,
+            shape: csr.getattr("shape")?.extract()?,
+        })
+    }
+
+    /// Index arrays, borrowed or converted
+    pub const fn indices(&self) -> &SparseIndices<'py> {
+        &self.indices
+    }
+
+    /// Run `f` over a `CsrMatrix` borrowing the SciPy buffers
+    pub fn with_csr<I, R>(
+        &self,
+        indptr: &PyReadonlyArray1<'_, I>,
+        indices: &PyReadonlyArray1<'_, I>,
+        f: impl FnOnce(&CsrMatrix<'_, f64, I>) -> Result<R>,
+    ) -> Result<R>
+    where
+        I: SparseIndex + Element,
+    {
+        let contiguous = |_| DispersionError::MalformedSparse("arrays must be contiguous");
+        let indptr = indptr.as_slice().map_err(contiguous)?;
+        let indices = indices.as_slice().map_err(contiguous)?;
+        let data = self.data.as_slice().map_err(contiguous)?;
+        f(&CsrMatrix::new(
+            indptr,
+            indices,
+            data,
+            self.shape.0,
+            self.shape.1,
+        )?)
+    }
+}
+
+impl MatrixInput<'_> {
+    /// Number of rows (words)
+    pub fn n_rows(&self) -> usize {
+        match self {
+            Self::Nested(rows) => rows.len(),
+            Self::Array(ArrayInput::F64(a)) => a.shape()[0],
+            Self::Array(ArrayInput::F32(a)) => a.shape()[0],
+            Self::Array(ArrayInput::I64(a)) => a.shape()[0],
+            Self::Sparse(sparse) => sparse.shape.0,
+        }
+    }
+
+    /// True for an empty list of rows, which has no column count to check
+    pub const fn is_empty_nested(&self) -> bool {
+        matches!(self, Self::Nested(rows) if rows.is_empty())
+    }
+}
+
+impl<'py> FromPyObject<'py> for MatrixInput<'py> {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if ob.is_instance_of::<PyList>() || ob.is_instance_of::<PyTuple>() {
+            return Ok(Self::Nested(ob.extract()?));
+        }
+        if ob.hasattr("indptr")? && ob.hasattr("format")? {
+            return Ok(Self::Sparse(SparseInput::extract(ob)?));
+        }
+        // An object can only be a NumPy array if NumPy is already imported;
+        // checking first avoids importing it for unrelated inputs.
+        let modules = ob.py().import("sys")?.getattr("modules")?;
+        if modules.downcast::<PyDict>()?.contains("numpy")? {
+            if let Ok(array) = ob.downcast::<PyArray2<f64>>() {
+                return Ok(Self::Array(ArrayInput::F64(array.readonly())));
+            }
+            if let Ok(array) = ob.downcast::<PyArray2<f32>>() {
+                return Ok(Self::Array(ArrayInput::F32(array.readonly())));
+            }
+            if let Ok(array) = ob.downcast::<PyArray2<i64>>() {
+                return Ok(Self::Array(ArrayInput::I64(array.readonly())));
+            }
+        }
+        Err(PyTypeError::new_err(
+            "frequency_matrix must be a list of lists, a 2-D float64, float32 or int64 NumPy array, or a SciPy CSR/CSC matrix",
+        ))
+    }
+}
+
+/// Evaluate `$body` with `$m` bound to a `FrequencyMatrix` over any `ArrayInput`
+macro_rules! with_array {
+    ($array:expr, |$m:ident| $body:expr) => {
+        match $array {
+            $crate::python::array::ArrayInput::F64(a) => {
+                $crate::python::array::with_matrix(&a, |$m| $body)
+            }
+            $crate::python::array::ArrayInput::F32(a) => {
+                $crate::python::array::with_matrix(&a, |$m| $body)
+            }
+            $crate::python::array::ArrayInput::I64(a) => {
+                $crate::python::array::with_matrix(&a, |$m| $body)
+            }
+        }
+    };
+}
+pub(crate) use with_array;
+
+/// Evaluate `$body` with `$m` bound to a `CsrMatrix` over a `SparseInput`,
+/// whichever its index dtype
+macro_rules! with_sparse {
+    ($sparse:expr, |$m:ident| $body:expr) => {
+        match $sparse.indices() {
+            $crate::python::array::SparseIndices::I32 { indptr, indices } => {
+                $sparse.with_csr(indptr, indices, |$m| $body)
+            }
+            $crate::python::array::SparseIndices::I64 { indptr, indices } => {
+                $sparse.with_csr(indptr, indices, |$m| $body)
+            }
+        }
+    };
+}
+pub(crate) use with_sparse;
+
+/// Run `f` over a borrowed view of a NumPy array
+///
+/// C- and Fortran-contiguous arrays are used in place; other strided views
+/// are copied once into C order.
+pub fn with_matrix<T, R>(
+    array: &PyReadonlyArray2<'_, T>,
+    f: impl FnOnce(&FrequencyMatrix<'_, T>) -> Result<R>,
+) -> Result<R>
+where
+    T: FrequencyValue + Element,
+{
+    let view = array.as_array();
+    let (n_rows, n_cols) = view.dim();
+    if array.is_c_contiguous()
+        && let Some(data) = view.as_slice()
+    {
+        return f(&FrequencyMatrix::new(
+            data,
+            n_rows,
+            n_cols,
+            MatrixLayout::RowMajor,
+        )?);
+    }
+    let transposed = view.t();
+    if array.is_fortran_contiguous()
+        && let Some(data) = transposed.as_slice()
+    {
+        return f(&FrequencyMatrix::new(
+            data,
+            n_rows,
+            n_cols,
+            MatrixLayout::ColumnMajor,
+        )?);
+    }
+    let owned = view.as_standard_layout();
+    let data = owned
+        .as_slice()
+        .expect("standard layout arrays are contiguous");
+    f(&FrequencyMatrix::new(
+        data,
+        n_rows,
+        n_cols,
+        MatrixLayout::RowMajor,
+    )?)
+}
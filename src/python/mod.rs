@@ -0,0 +1,984 @@
+//! Python bindings (enabled with the `python` cargo feature)
+
+mod array;
+mod partition;
+mod position;
+mod table;
+
+use crate::analyzer::CorpusWordAnalyzer;
+use crate::bootstrap::{BootstrapConfig, MetricIntervals};
+use crate::error::DispersionError;
+use crate::matrix::FrequencyRows;
+use crate::metrics::{DispersionMetrics, LabelledMetrics, MetricBounds, MetricColumns, MetricSet};
+use crate::parallel::{Parallelism, set_default_parallelism};
+use crate::position::PositionMetrics;
+use crate::residuals::{FrequencyResiduals, ResidualConfig, frequency_residuals};
+use crate::significance::{GoodnessOfFit, NullDistribution, SimulationConfig, chi_square_sf};
+use crate::wordlist::{TieBreak, WordListConfig, WordListEntry, build_word_list};
+use numpy::IntoPyArray;
+use pyo3::Bound;
+use pyo3::exceptions::{PyIndexError, PyKeyError, PyOSError, PyRuntimeError};
+use pyo3::prelude::*;
+use pyo3::types::{PyDict, PyIterator, PyList, PyModule};
+use std::sync::Arc;
+
+use array::{MatrixInput, with_array, with_sparse};
+use partition::{PartitionArg, PyCorpusPartition};
+use position::PyPositionAnalyzer;
+use table::PyFrequencyTable;
+
+/// Python exception hierarchy mirroring `DispersionError`
+///
+/// Every class derives from `DispersionError`, which itself derives from
+/// `ValueError` so existing `except ValueError` handlers keep working.
+mod exceptions {
+    use pyo3::create_exception;
+    use pyo3::exceptions::PyValueError;
+
+    create_exception!(
+        corpus_dispersion,
+        DispersionError,
+        PyValueError,
+        "Base class for all input validation errors."
+    );
+    create_exception!(
+        corpus_dispersion,
+        LengthMismatchError,
+        DispersionError,
+        "Frequency vector length does not match the number of corpus parts."
+    );
+    create_exception!(
+        corpus_dispersion,
+        EmptyInputError,
+        DispersionError,
+        "Input vectors are empty."
+    );
+    create_exception!(
+        corpus_dispersion,
+        NonPositiveTotalError,
+        DispersionError,
+        "Total corpus size is zero or negative."
+    );
+    create_exception!(
+        corpus_dispersion,
+        NegativeValueError,
+        DispersionError,
+        "A frequency or corpus part size is negative."
+    );
+    create_exception!(
+        corpus_dispersion,
+        NonFiniteValueError,
+        DispersionError,
+        "An input value is NaN or infinite."
+    );
+    create_exception!(
+        corpus_dispersion,
+        UnknownMetricError,
+        DispersionError,
+        "Metric name is not recognised."
+    );
+    create_exception!(
+        corpus_dispersion,
+        MalformedSparseError,
+        DispersionError,
+        "Sparse matrix index arrays are inconsistent."
+    );
+    create_exception!(
+        corpus_dispersion,
+        InvalidPositionError,
+        DispersionError,
+        "A token position is out of range or not strictly increasing."
+    );
+    create_exception!(
+        corpus_dispersion,
+        InvalidParameterError,
+        DispersionError,
+        "A tuning parameter is outside its valid range."
+    );
+    create_exception!(
+        corpus_dispersion,
+        MalformedTableError,
+        DispersionError,
+        "A frequency table file could not be parsed."
+    );
+}
+
+impl From<DispersionError> for PyErr {
+    fn from(err: DispersionError) -> Self {
+        let message = err.to_string();
+        let py_err = match err.kind() {
+            DispersionError::LengthMismatch { .. } | DispersionError::LabelCountMismatch { .. } => {
+                exceptions::LengthMismatchError::new_err(message)
+            }
+            DispersionError::EmptyInput => exceptions::EmptyInputError::new_err(message),
+            DispersionError::NonPositiveTotal(_) => {
+                exceptions::NonPositiveTotalError::new_err(message)
+            }
+            DispersionError::NegativeFrequency { .. }
+            | DispersionError::NegativePartSize { .. } => {
+                exceptions::NegativeValueError::new_err(message)
+            }
+            DispersionError::NonFiniteValue { .. } => {
+                exceptions::NonFiniteValueError::new_err(message)
+            }
+            DispersionError::UnknownMetric(_) => exceptions::UnknownMetricError::new_err(message),
+            DispersionError::MalformedSparse(_) => {
+                exceptions::MalformedSparseError::new_err(message)
+            }
+            DispersionError::InvalidPosition { .. } => {
+                exceptions::InvalidPositionError::new_err(message)
+            }
+            DispersionError::InvalidParameter { .. } => {
+                exceptions::InvalidParameterError::new_err(message)
+            }
+            DispersionError::ThreadPool(_) => PyRuntimeError::new_err(message),
+            DispersionError::MalformedTable { .. } => {
+                exceptions::MalformedTableError::new_err(message)
+            }
+            DispersionError::Io(_) => PyOSError::new_err(message),
+            DispersionError::Row { .. } => exceptions::DispersionError::new_err(message),
+        };
+        // Expose the offending batch row as `err.row` and its label as
+        // `err.word` (None outside batch calls or without word labels)
+        Python::with_gil(|py| {
+            let _ = py_err.value(py).setattr("row", err.row());
+            let _ = py_err.value(py).setattr("word", err.word());
+        });
+        py_err
+    }
+}
+
+#[pymethods]
+impl DispersionMetrics {
+    fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[pymethods]
+impl MetricIntervals {
+    #[pyo3(name = "interval")]
+    fn py_interval(&self, metric_name: &str) -> PyResult<Option<(f64, f64)>> {
+        Ok(self.interval(metric_name)?)
+    }
+
+    fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[pymethods]
+impl GoodnessOfFit {
+    fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[pymethods]
+impl FrequencyResiduals {
+    fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[pymethods]
+impl WordListEntry {
+    fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[pymethods]
+impl NullDistribution {
+    fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+#[pymethods]
+impl MetricBounds {
+    #[pyo3(name = "normalize")]
+    fn py_normalize(&self, metric_name: &str, value: f64) -> PyResult<Option<f64>> {
+        Ok(self.normalize(metric_name, value)?)
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "MetricBounds(most_even={:?}, most_clumped={:?})",
+            self.most_even, self.most_clumped
+        )
+    }
+}
+
+#[pymethods]
+impl LabelledMetrics {
+    fn __len__(&self) -> usize {
+        self.len()
+    }
+
+    /// Index by row number (negative counts from the end) or by word
+    fn __getitem__(&self, key: &Bound<'_, PyAny>) -> PyResult<DispersionMetrics> {
+        if let Ok(word) = key.extract::<String>() {
+            return self
+                .get(&word)
+                .cloned()
+                .ok_or_else(|| PyKeyError::new_err(word));
+        }
+        let index: isize = key.extract()?;
+        let len = self.len().cast_signed();
+        let row = if index < 0 { index + len } else { index };
+        if !(0..len).contains(&row) {
+            return Err(PyIndexError::new_err("LabelledMetrics index out of range"));
+        }
+        Ok(self.as_slice()[row.cast_unsigned()].clone())
+    }
+
+    fn __contains__(&self, word: &Bound<'_, PyAny>) -> bool {
+        word.extract::<String>()
+            .is_ok_and(|word| self.position(&word).is_some())
+    }
+
+    fn __iter__<'py>(&self, py: Python<'py>) -> PyResult<Bound<'py, PyIterator>> {
+        PyList::new(py, self.as_slice().iter().cloned())?.try_iter()
+    }
+
+    /// Metrics of `word`, or `default` if it is not in the batch
+    #[pyo3(name = "get", signature = (word, default=None))]
+    fn py_get(&self, word: &str, default: Option<DispersionMetrics>) -> Option<DispersionMetrics> {
+        self.get(word).cloned().or(default)
+    }
+
+    /// Row index of `word`, or None
+    #[pyo3(name = "position")]
+    fn py_position(&self, word: &str) -> Option<usize> {
+        self.position(word)
+    }
+
+    /// Word labels in row order
+    #[getter]
+    fn words(&self) -> Vec<Option<String>> {
+        self.as_slice().iter().map(|m| m.word.clone()).collect()
+    }
+
+    /// Results as a plain list in row order
+    fn to_list(&self) -> Vec<DispersionMetrics> {
+        self.as_slice().to_vec()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("LabelledMetrics(n_words={})", self.len())
+    }
+}
+
+/// Batch results: a plain list, or a `LabelledMetrics` when words were given
+#[derive(IntoPyObject)]
+enum BatchResults {
+    Rows(Vec<DispersionMetrics>),
+    Labelled(LabelledMetrics),
+}
+
+/// Python wrapper around the pure-Rust `CorpusWordAnalyzer`
+#[pyclass(name = "CorpusWordAnalyzer")]
+pub struct PyCorpusWordAnalyzer {
+    inner: CorpusWordAnalyzer,
+}
+
+#[pymethods]
+impl PyCorpusWordAnalyzer {
+    /// `corpus_part_sizes_words` is a list of sizes or a shared `CorpusPartition`
+    #[new]
+    #[pyo3(signature = (v, corpus_part_sizes_words, total_corpus_words=None))]
+    fn new(
+        v: Vec<f64>,
+        corpus_part_sizes_words: PartitionArg,
+        total_corpus_words: Option<f64>,
+    ) -> PyResult<Self> {
+        let partition = corpus_part_sizes_words.resolve(total_corpus_words)?;
+        Ok(Self {
+            inner: CorpusWordAnalyzer::with_partition(v, partition)?,
+        })
+    }
+
+    fn get_range(&mut self) -> i32 {
+        self.inner.get_range()
+    }
+
+    fn get_sd_population(&mut self) -> Option<f64> {
+        self.inner.get_sd_population()
+    }
+
+    fn get_vc_population(&mut self) -> Option<f64> {
+        self.inner.get_vc_population()
+    }
+
+    fn get_juilland_d(&mut self) -> Option<f64> {
+        self.inner.get_juilland_d()
+    }
+
+    fn get_juilland_u(&mut self) -> Option<f64> {
+        self.inner.get_juilland_u()
+    }
+
+    fn get_carroll_d2(&mut self) -> Option<f64> {
+        self.inner.get_carroll_d2()
+    }
+
+    fn get_carroll_um(&mut self) -> Option<f64> {
+        self.inner.get_carroll_um()
+    }
+
+    fn get_lyne_d3(&self) -> Option<f64> {
+        self.inner.get_lyne_d3()
+    }
+
+    fn get_roschengren_s_adj(&self) -> Option<f64> {
+        self.inner.get_roschengren_s_adj()
+    }
+
+    fn get_roschengren_af(&self) -> Option<f64> {
+        self.inner.get_roschengren_af()
+    }
+
+    fn get_zhang_dc(&mut self) -> Option<f64> {
+        self.inner.get_zhang_dc()
+    }
+
+    fn get_dp(&self) -> Option<f64> {
+        self.inner.get_dp()
+    }
+
+    fn get_dp_norm(&mut self) -> Option<f64> {
+        self.inner.get_dp_norm()
+    }
+
+    fn get_dp_nofreq(&self) -> Option<f64> {
+        self.inner.get_dp_nofreq()
+    }
+
+    fn get_kl_divergence(&self) -> Option<f64> {
+        self.inner.get_kl_divergence()
+    }
+
+    fn get_kl_divergence_norm(&self) -> Option<f64> {
+        self.inner.get_kl_divergence_norm()
+    }
+
+    fn get_evenness_da(&mut self) -> Option<f64> {
+        self.inner.get_evenness_da()
+    }
+
+    fn get_jsd_dispersion(&self) -> Option<f64> {
+        self.inner.get_jsd_dispersion()
+    }
+
+    fn get_hellinger_dispersion(&self) -> Option<f64> {
+        self.inner.get_hellinger_dispersion()
+    }
+
+    fn get_gini_coefficient(&mut self) -> Option<f64> {
+        self.inner.get_gini_coefficient()
+    }
+
+    fn get_shannon_entropy(&mut self) -> Option<f64> {
+        self.inner.get_shannon_entropy()
+    }
+
+    fn get_pielou_evenness(&mut self) -> Option<f64> {
+        self.inner.get_pielou_evenness()
+    }
+
+    fn get_simpson_index(&mut self) -> Option<f64> {
+        self.inner.get_simpson_index()
+    }
+
+    fn get_hill_number(&mut self, q: f64) -> PyResult<Option<f64>> {
+        Ok(self.inner.get_hill_number(q)?)
+    }
+
+    fn get_mean_text_frequency_ft(&mut self) -> Option<f64> {
+        self.inner.get_mean_text_frequency_ft()
+    }
+
+    fn get_pervasiveness_pt(&mut self) -> Option<f64> {
+        self.inner.get_pervasiveness_pt()
+    }
+
+    fn get_corpus_part_sizes(&self) -> Vec<f64> {
+        self.inner.get_corpus_part_sizes().to_vec()
+    }
+
+    fn get_total_corpus_words(&self) -> f64 {
+        self.inner.get_total_corpus_words()
+    }
+
+    fn get_relative_partition_sizes(&self) -> Vec<f64> {
+        self.inner.get_relative_partition_sizes().to_vec()
+    }
+
+    fn get_partition(&self) -> PyCorpusPartition {
+        PyCorpusPartition {
+            inner: Arc::clone(self.inner.get_partition()),
+        }
+    }
+
+    fn get_normalized_frequencies(&self) -> Vec<f64> {
+        self.inner.get_normalized_frequencies().clone()
+    }
+
+    /// `metrics` restricts the computation to the named metrics (others are None)
+    #[pyo3(signature = (metrics=None))]
+    fn calculate_all_metrics(
+        &mut self,
+        metrics: Option<Vec<String>>,
+    ) -> PyResult<DispersionMetrics> {
+        Ok(self.inner.calculate_metrics(metric_selection(metrics)?))
+    }
+
+    fn get_theoretical_bounds(&self) -> MetricBounds {
+        self.inner.get_theoretical_bounds()
+    }
+
+    fn get_frequency_normalized_metric(&mut self, metric_name: &str) -> PyResult<Option<f64>> {
+        Ok(self.inner.get_frequency_normalized_metric(metric_name)?)
+    }
+
+    fn get_goodness_of_fit(&self) -> GoodnessOfFit {
+        self.inner.get_goodness_of_fit()
+    }
+
+    /// Percentile bootstrap intervals; resamples run with the GIL released
+    #[pyo3(signature = (metrics=None, *, n_resamples=1000, confidence_level=0.95, seed=None, n_threads=None))]
+    fn bootstrap_metrics(
+        &mut self,
+        py: Python<'_>,
+        metrics: Option<Vec<String>>,
+        n_resamples: usize,
+        confidence_level: f64,
+        seed: Option<u64>,
+        n_threads: Option<usize>,
+    ) -> PyResult<MetricIntervals> {
+        let selection = metric_selection(metrics)?;
+        let config = BootstrapConfig {
+            n_resamples,
+            confidence_level,
+            seed,
+        };
+        let parallelism = Parallelism::from_n_threads(n_threads);
+        let inner = &mut self.inner;
+        let result = py.allow_threads(|| {
+            parallelism.install(|| inner.bootstrap_metrics(selection, &config))?
+        });
+        Ok(result?)
+    }
+
+    /// Simulated multinomial null distribution; simulations run with the GIL released
+    #[pyo3(signature = (metrics=None, *, n_simulations=1000, seed=None, n_threads=None))]
+    fn simulate_null_distribution(
+        &mut self,
+        py: Python<'_>,
+        metrics: Option<Vec<String>>,
+        n_simulations: usize,
+        seed: Option<u64>,
+        n_threads: Option<usize>,
+    ) -> PyResult<NullDistribution> {
+        let selection = metric_selection(metrics)?;
+        let config = SimulationConfig {
+            n_simulations,
+            seed,
+        };
+        let parallelism = Parallelism::from_n_threads(n_threads);
+        let inner = &mut self.inner;
+        let result = py.allow_threads(|| {
+            parallelism.install(|| inner.simulate_null_distribution(selection, &config))?
+        });
+        Ok(result?)
+    }
+
+    /// With `words`, results carry their word and can be looked up by it
+    #[staticmethod]
+    #[pyo3(signature = (frequency_matrix, corpus_part_sizes, total_corpus_words=None, *, metrics=None, words=None, n_threads=None))]
+    fn calculate_batch_metrics(
+        py: Python<'_>,
+        frequency_matrix: MatrixInput<'_>,
+        corpus_part_sizes: PartitionArg,
+        total_corpus_words: Option<f64>,
+        metrics: Option<Vec<String>>,
+        words: Option<Vec<String>>,
+        n_threads: Option<usize>,
+    ) -> PyResult<BatchResults> {
+        let results = batch_metrics(
+            py,
+            frequency_matrix,
+            corpus_part_sizes,
+            total_corpus_words,
+            metric_selection(metrics)?,
+            words.as_deref(),
+            &Parallelism::from_n_threads(n_threads),
+            None,
+        )?;
+        Ok(match words {
+            Some(words) => BatchResults::Labelled(LabelledMetrics::new(words, results)?),
+            None => BatchResults::Rows(results),
+        })
+    }
+
+    /// Batch computation returning a dict of metric name -> 1-D NumPy array
+    ///
+    /// With `words`, the dict also holds a "word" list.
+    #[staticmethod]
+    #[pyo3(signature = (frequency_matrix, corpus_part_sizes, total_corpus_words=None, *, metrics=None, words=None, n_threads=None))]
+    fn calculate_batch_metrics_columnar<'py>(
+        py: Python<'py>,
+        frequency_matrix: MatrixInput<'py>,
+        corpus_part_sizes: PartitionArg,
+        total_corpus_words: Option<f64>,
+        metrics: Option<Vec<String>>,
+        words: Option<Vec<String>>,
+        n_threads: Option<usize>,
+    ) -> PyResult<Bound<'py, PyDict>> {
+        let selection = metric_selection(metrics)?;
+        let results = batch_metrics(
+            py,
+            frequency_matrix,
+            corpus_part_sizes,
+            total_corpus_words,
+            selection,
+            words.as_deref(),
+            &Parallelism::from_n_threads(n_threads),
+            None,
+        )?;
+        let dict = columns_to_dict(py, MetricColumns::from_selected(&results, selection))?;
+        if let Some(words) = words {
+            dict.set_item("word", words)?;
+        }
+        Ok(dict)
+    }
+
+    /// `total_corpus_words` may be `None` (sum of sizes, or a `CorpusPartition`)
+    #[staticmethod]
+    #[pyo3(signature = (frequency_vector, corpus_part_sizes, total_corpus_words, metric_name))]
+    fn calculate_single_metric(
+        py: Python<'_>,
+        frequency_vector: Vec<f64>,
+        corpus_part_sizes: PartitionArg,
+        total_corpus_words: Option<f64>,
+        metric_name: &str,
+    ) -> PyResult<Option<f64>> {
+        let partition = corpus_part_sizes.resolve(total_corpus_words)?;
+        let result = py.allow_threads(|| {
+            CorpusWordAnalyzer::with_partition(frequency_vector, partition)?.get_metric(metric_name)
+        });
+        Ok(result?)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (
+        frequency_matrix,
+        corpus_part_sizes,
+        total_corpus_words=None,
+        *,
+        metrics=None,
+        n_resamples=1000,
+        confidence_level=0.95,
+        seed=None,
+        n_threads=None
+    ))]
+    #[allow(clippy::too_many_arguments)] // Mirrors the Python keyword arguments
+    fn calculate_batch_bootstrap(
+        py: Python<'_>,
+        frequency_matrix: MatrixInput<'_>,
+        corpus_part_sizes: PartitionArg,
+        total_corpus_words: Option<f64>,
+        metrics: Option<Vec<String>>,
+        n_resamples: usize,
+        confidence_level: f64,
+        seed: Option<u64>,
+        n_threads: Option<usize>,
+    ) -> PyResult<Vec<MetricIntervals>> {
+        let selection = metric_selection(metrics)?;
+        let config = BootstrapConfig {
+            n_resamples,
+            confidence_level,
+            seed,
+        };
+        if frequency_matrix.is_empty_nested() {
+            config.validate()?;
+            return Ok(Vec::new());
+        }
+        let partition = corpus_part_sizes.resolve(total_corpus_words)?;
+        let result = dispatch_rows(
+            py,
+            frequency_matrix,
+            &Parallelism::from_n_threads(n_threads),
+            |rows| {
+                CorpusWordAnalyzer::calculate_batch_bootstrap(rows, &partition, selection, &config)
+            },
+            |m| {
+                CorpusWordAnalyzer::calculate_batch_bootstrap_matrix(
+                    m, &partition, selection, &config,
+                )
+            },
+        );
+        Ok(result?)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (
+        frequency_matrix,
+        corpus_part_sizes,
+        total_corpus_words=None,
+        *,
+        metrics=None,
+        n_simulations=1000,
+        seed=None,
+        n_threads=None
+    ))]
+    #[allow(clippy::too_many_arguments)] // Mirrors the Python keyword arguments
+    fn calculate_batch_null_distribution(
+        py: Python<'_>,
+        frequency_matrix: MatrixInput<'_>,
+        corpus_part_sizes: PartitionArg,
+        total_corpus_words: Option<f64>,
+        metrics: Option<Vec<String>>,
+        n_simulations: usize,
+        seed: Option<u64>,
+        n_threads: Option<usize>,
+    ) -> PyResult<Vec<NullDistribution>> {
+        let selection = metric_selection(metrics)?;
+        let config = SimulationConfig {
+            n_simulations,
+            seed,
+        };
+        if frequency_matrix.is_empty_nested() {
+            config.validate()?;
+            return Ok(Vec::new());
+        }
+        let partition = corpus_part_sizes.resolve(total_corpus_words)?;
+        let result = dispatch_rows(
+            py,
+            frequency_matrix,
+            &Parallelism::from_n_threads(n_threads),
+            |rows| {
+                CorpusWordAnalyzer::calculate_batch_null_distribution(
+                    rows, &partition, selection, &config,
+                )
+            },
+            |m| {
+                CorpusWordAnalyzer::calculate_batch_null_distribution_matrix(
+                    m, &partition, selection, &config,
+                )
+            },
+        );
+        Ok(result?)
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (frequency_matrix, corpus_part_sizes, total_corpus_words=None, *, n_threads=None))]
+    fn calculate_batch_goodness_of_fit(
+        py: Python<'_>,
+        frequency_matrix: MatrixInput<'_>,
+        corpus_part_sizes: PartitionArg,
+        total_corpus_words: Option<f64>,
+        n_threads: Option<usize>,
+    ) -> PyResult<Vec<GoodnessOfFit>> {
+        if frequency_matrix.is_empty_nested() {
+            return Ok(Vec::new());
+        }
+        let partition = corpus_part_sizes.resolve(total_corpus_words)?;
+        let result = dispatch_rows(
+            py,
+            frequency_matrix,
+            &Parallelism::from_n_threads(n_threads),
+            |rows| CorpusWordAnalyzer::calculate_batch_goodness_of_fit(rows, &partition),
+            |m| CorpusWordAnalyzer::calculate_batch_goodness_of_fit_matrix(m, &partition),
+        );
+        Ok(result?)
+    }
+
+    /// Residuals of each metric from its trend over log frequency, per word
+    #[staticmethod]
+    #[pyo3(signature = (frequency_matrix, corpus_part_sizes, total_corpus_words=None, *, metrics=None, n_bands=10, n_threads=None))]
+    fn calculate_batch_residuals(
+        py: Python<'_>,
+        frequency_matrix: MatrixInput<'_>,
+        corpus_part_sizes: PartitionArg,
+        total_corpus_words: Option<f64>,
+        metrics: Option<Vec<String>>,
+        n_bands: usize,
+        n_threads: Option<usize>,
+    ) -> PyResult<Vec<FrequencyResiduals>> {
+        let config = ResidualConfig { n_bands };
+        config.validate()?;
+        let parallelism = Parallelism::from_n_threads(n_threads);
+        let mut frequencies = Vec::new();
+        let metrics = batch_metrics(
+            py,
+            frequency_matrix,
+            corpus_part_sizes,
+            total_corpus_words,
+            metric_selection(metrics)?,
+            None,
+            &parallelism,
+            Some(&mut frequencies),
+        )?;
+        let result = py.allow_threads(|| {
+            parallelism.install(|| frequency_residuals(metrics, &frequencies, &config))?
+        });
+        Ok(result?)
+    }
+
+    /// Rank-ordered word list; `tie_break` is "frequency", "word" or "input"
+    #[staticmethod]
+    #[pyo3(signature = (
+        words,
+        frequency_matrix,
+        corpus_part_sizes,
+        total_corpus_words=None,
+        *,
+        score="ft_adjusted_by_da",
+        descending=true,
+        tie_break="frequency",
+        shared_ranks=false,
+        n_threads=None
+    ))]
+    #[allow(clippy::too_many_arguments)] // Mirrors the Python keyword arguments
+    fn calculate_batch_word_list(
+        py: Python<'_>,
+        words: Vec<String>,
+        frequency_matrix: MatrixInput<'_>,
+        corpus_part_sizes: PartitionArg,
+        total_corpus_words: Option<f64>,
+        score: &str,
+        descending: bool,
+        tie_break: &str,
+        shared_ranks: bool,
+        n_threads: Option<usize>,
+    ) -> PyResult<Vec<WordListEntry>> {
+        let tie_break = TieBreak::from_name(tie_break).ok_or_else(|| {
+            exceptions::InvalidParameterError::new_err(format!(
+                "tie_break must be 'frequency', 'word' or 'input', got '{tie_break}'."
+            ))
+        })?;
+        let config = WordListConfig {
+            score: score.to_string(),
+            descending,
+            tie_break,
+            shared_ranks,
+        };
+        let selection = config.selection()?;
+        let mut frequencies = Vec::new();
+        let metrics = batch_metrics(
+            py,
+            frequency_matrix,
+            corpus_part_sizes,
+            total_corpus_words,
+            selection,
+            Some(&words),
+            &Parallelism::from_n_threads(n_threads),
+            Some(&mut frequencies),
+        )?;
+        let result = py.allow_threads(|| build_word_list(words, &metrics, &frequencies, &config));
+        Ok(result?)
+    }
+}
+
+/// Dispatch a batch computation over nested lists, NumPy arrays or sparse matrices
+///
+/// Argument conversion happens with the GIL held; the metric computation
+/// itself runs with the GIL released so other Python threads keep running,
+/// on the pool selected by `parallelism`.
+/// Array buffers stay borrowed (read-only) for the duration of the call.
+/// With `totals`, the total frequency of every row is written there in the
+/// same GIL-released section.
+#[allow(clippy::too_many_arguments)] // Shared by every batch entry point
+fn batch_metrics(
+    py: Python<'_>,
+    frequency_matrix: MatrixInput<'_>,
+    corpus_part_sizes: PartitionArg,
+    total_corpus_words: Option<f64>,
+    selection: MetricSet,
+    words: Option<&[String]>,
+    parallelism: &Parallelism,
+    totals: Option<&mut Vec<f64>>,
+) -> PyResult<Vec<DispersionMetrics>> {
+    if let Some(words) = words
+        && words.len() != frequency_matrix.n_rows()
+    {
+        return Err(DispersionError::LabelCountMismatch {
+            expected: frequency_matrix.n_rows(),
+            found: words.len(),
+        }
+        .into());
+    }
+    // An empty list of rows yields no results without validating the sizes
+    if frequency_matrix.is_empty_nested() {
+        return Ok(Vec::new());
+    }
+    let partition = corpus_part_sizes.resolve(total_corpus_words)?;
+    let metrics = match frequency_matrix {
+        MatrixInput::Nested(rows) => py.allow_threads(|| {
+            if let Some(totals) = totals {
+                *totals = rows.iter().map(|row| row.iter().sum()).collect();
+            }
+            parallelism.install(|| {
+                CorpusWordAnalyzer::calculate_batch_metrics_with_partition(
+                    rows, &partition, selection,
+                )
+            })?
+        }),
+        MatrixInput::Array(array) => with_array!(array, |m| py.allow_threads(|| {
+            if let Some(totals) = totals {
+                *totals = row_totals(m);
+            }
+            parallelism.install(|| {
+                CorpusWordAnalyzer::calculate_batch_metrics_matrix(m, &partition, selection)
+            })?
+        })),
+        MatrixInput::Sparse(sparse) => with_sparse!(sparse, |m| py.allow_threads(|| {
+            if let Some(totals) = totals {
+                *totals = row_totals(m);
+            }
+            parallelism.install(|| {
+                CorpusWordAnalyzer::calculate_batch_metrics_sparse(m, &partition, selection)
+            })?
+        })),
+    };
+    Ok(metrics.map_err(|e| match words {
+        Some(words) => e.with_words(words),
+        None => e,
+    })?)
+}
+
+/// Total frequency of every row of a borrowed matrix
+fn row_totals(frequency_matrix: &dyn FrequencyRows) -> Vec<f64> {
+    (0..frequency_matrix.n_rows())
+        .map(|row| frequency_matrix.row_total(row))
+        .collect()
+}
+
+/// Dispatch a batch computation that needs each word's full frequency vector
+///
+/// Nested lists go to `nested`; NumPy arrays and SciPy matrices are borrowed
+/// in place and go to `matrix`. Either runs with the GIL released, on the
+/// pool selected by `parallelism`.
+fn dispatch_rows<R: Send>(
+    py: Python<'_>,
+    frequency_matrix: MatrixInput<'_>,
+    parallelism: &Parallelism,
+    nested: impl FnOnce(Vec<Vec<f64>>) -> Result<R, DispersionError> + Send,
+    matrix: impl FnOnce(&dyn FrequencyRows) -> Result<R, DispersionError> + Send,
+) -> Result<R, DispersionError> {
+    match frequency_matrix {
+        MatrixInput::Nested(rows) => py.allow_threads(|| parallelism.install(|| nested(rows))?),
+        MatrixInput::Array(array) => with_array!(array, |m| py
+            .allow_threads(|| { parallelism.install(|| matrix(m))? })),
+        MatrixInput::Sparse(sparse) => with_sparse!(sparse, |m| py
+            .allow_threads(|| { parallelism.install(|| matrix(m))? })),
+    }
+}
+
+/// Metric names requested from Python; `None` selects every metric
+fn metric_selection(metrics: Option<Vec<String>>) -> Result<MetricSet, DispersionError> {
+    metrics.map_or(Ok(MetricSet::ALL), MetricSet::from_names)
+}
+
+/// Set the number of threads used by batch calls without `n_threads`
+///
+/// `None` restores rayon's default (one thread per core), `1` runs batches
+/// sequentially.
+#[pyfunction]
+#[pyo3(signature = (n_threads=None))]
+fn set_num_threads(n_threads: Option<usize>) -> PyResult<()> {
+    Ok(set_default_parallelism(&Parallelism::from_n_threads(
+        n_threads,
+    ))?)
+}
+
+/// Number of threads batch calls without `n_threads` will use
+#[pyfunction]
+fn get_num_threads() -> PyResult<usize> {
+    Ok(Parallelism::Default.num_threads()?)
+}
+
+/// Upper-tail probability of a chi-square statistic (`None` for zero degrees of freedom)
+#[pyfunction(name = "chi_square_sf")]
+fn py_chi_square_sf(statistic: f64, degrees_of_freedom: usize) -> Option<f64> {
+    chi_square_sf(statistic, degrees_of_freedom)
+}
+
+/// Move metric columns into a dict of NumPy arrays (`range` as int32)
+fn columns_to_dict(py: Python<'_>, columns: MetricColumns) -> PyResult<Bound<'_, PyDict>> {
+    let dict = PyDict::new(py);
+    dict.set_item("range", columns.range.into_pyarray(py))?;
+    for (name, values) in columns.columns {
+        dict.set_item(name, values.into_pyarray(py))?;
+    }
+    Ok(dict)
+}
+
+/// Python module definition
+#[pymodule]
+fn corpus_dispersion(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCorpusWordAnalyzer>()?;
+    m.add_class::<DispersionMetrics>()?;
+    m.add_class::<PyCorpusPartition>()?;
+    m.add_class::<MetricBounds>()?;
+    m.add_class::<PyPositionAnalyzer>()?;
+    m.add_class::<PositionMetrics>()?;
+    m.add_class::<GoodnessOfFit>()?;
+    m.add_class::<MetricIntervals>()?;
+    m.add_class::<NullDistribution>()?;
+    m.add_class::<FrequencyResiduals>()?;
+    m.add_class::<WordListEntry>()?;
+    m.add_class::<LabelledMetrics>()?;
+    m.add_class::<PyFrequencyTable>()?;
+    m.add_function(wrap_pyfunction!(set_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(get_num_threads, m)?)?;
+    m.add_function(wrap_pyfunction!(py_chi_square_sf, m)?)?;
+    let py = m.py();
+    m.add(
+        "DispersionError",
+        py.get_type::<exceptions::DispersionError>(),
+    )?;
+    m.add(
+        "LengthMismatchError",
+        py.get_type::<exceptions::LengthMismatchError>(),
+    )?;
+    m.add(
+        "EmptyInputError",
+        py.get_type::<exceptions::EmptyInputError>(),
+    )?;
+    m.add(
+        "NonPositiveTotalError",
+        py.get_type::<exceptions::NonPositiveTotalError>(),
+    )?;
+    m.add(
+        "NegativeValueError",
+        py.get_type::<exceptions::NegativeValueError>(),
+    )?;
+    m.add(
+        "NonFiniteValueError",
+        py.get_type::<exceptions::NonFiniteValueError>(),
+    )?;
+    m.add(
+        "UnknownMetricError",
+        py.get_type::<exceptions::UnknownMetricError>(),
+    )?;
+    m.add(
+        "MalformedSparseError",
+        py.get_type::<exceptions::MalformedSparseError>(),
+    )?;
+    m.add(
+        "InvalidPositionError",
+        py.get_type::<exceptions::InvalidPositionError>(),
+    )?;
+    m.add(
+        "InvalidParameterError",
+        py.get_type::<exceptions::InvalidParameterError>(),
+    )?;
+    m.add(
+        "MalformedTableError",
+        py.get_type::<exceptions::MalformedTableError>(),
+    )?;
+    Ok(())
+}
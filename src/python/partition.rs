@@ -0,0 +1,93 @@
+//! Python wrapper for `CorpusPartition` and the part-sizes argument
+
+use crate::partition::CorpusPartition;
+use pyo3::exceptions::PyTypeError;
+use pyo3::prelude::*;
+use std::sync::Arc;
+
+/// Precomputed corpus partition shared across analyzers and batch calls
+#[pyclass(name = "CorpusPartition", frozen)]
+pub struct PyCorpusPartition {
+    pub(crate) inner: Arc<CorpusPartition>,
+}
+
+#[pymethods]
+impl PyCorpusPartition {
+    #[new]
+    #[pyo3(signature = (part_sizes, total_corpus_words=None))]
+    fn new(part_sizes: Vec<f64>, total_corpus_words: Option<f64>) -> PyResult<Self> {
+        let inner = match total_corpus_words {
+            Some(total) => CorpusPartition::new(part_sizes, total)?,
+            None => CorpusPartition::from_part_sizes(part_sizes)?,
+        };
+        Ok(Self {
+            inner: Arc::new(inner),
+        })
+    }
+
+    #[getter]
+    fn n_parts(&self) -> usize {
+        self.inner.n_parts()
+    }
+
+    #[getter]
+    fn part_sizes(&self) -> Vec<f64> {
+        self.inner.part_sizes().to_vec()
+    }
+
+    #[getter]
+    fn total_corpus_words(&self) -> f64 {
+        self.inner.total_corpus_words()
+    }
+
+    #[getter]
+    fn relative_sizes(&self) -> Vec<f64> {
+        self.inner.s().to_vec()
+    }
+
+    #[getter]
+    fn min_s(&self) -> f64 {
+        self.inner.min_s()
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.n_parts()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "CorpusPartition(n_parts={}, total_corpus_words={})",
+            self.inner.n_parts(),
+            self.inner.total_corpus_words()
+        )
+    }
+}
+
+/// `corpus_part_sizes` argument: a list of sizes or a prebuilt `CorpusPartition`
+pub enum PartitionArg {
+    Sizes(Vec<f64>),
+    Shared(Arc<CorpusPartition>),
+}
+
+impl<'py> FromPyObject<'py> for PartitionArg {
+    fn extract_bound(ob: &Bound<'py, PyAny>) -> PyResult<Self> {
+        if let Ok(partition) = ob.downcast::<PyCorpusPartition>() {
+            return Ok(Self::Shared(Arc::clone(&partition.get().inner)));
+        }
+        Ok(Self::Sizes(ob.extract()?))
+    }
+}
+
+impl PartitionArg {
+    /// Build (or borrow) the partition; the total defaults to the sum of sizes
+    pub fn resolve(self, total_corpus_words: Option<f64>) -> PyResult<Arc<CorpusPartition>> {
+        match (self, total_corpus_words) {
+            (Self::Sizes(sizes), Some(total)) => Ok(Arc::new(CorpusPartition::new(sizes, total)?)),
+            (Self::Sizes(sizes), None) => Ok(Arc::new(CorpusPartition::from_part_sizes(sizes)?)),
+            (Self::Shared(partition), None) => Ok(partition),
+            (Self::Shared(_), Some(_)) => Err(PyTypeError::new_err(
+                "total_corpus_words cannot be given together with a CorpusPartition",
+            )),
+        }
+    }
+}
@@ -0,0 +1,88 @@
+//! Python wrapper for the position-based `PositionAnalyzer`
+
+use crate::parallel::Parallelism;
+use crate::position::{PositionAnalyzer, PositionMetrics};
+use pyo3::prelude::*;
+
+#[pymethods]
+impl PositionMetrics {
+    fn __repr__(&self) -> String {
+        self.to_string()
+    }
+}
+
+/// Python wrapper around the pure-Rust `PositionAnalyzer`
+#[pyclass(name = "PositionAnalyzer")]
+pub struct PyPositionAnalyzer {
+    inner: PositionAnalyzer,
+}
+
+#[pymethods]
+impl PyPositionAnalyzer {
+    #[new]
+    fn new(positions: Vec<usize>, corpus_length: usize) -> PyResult<Self> {
+        Ok(Self {
+            inner: PositionAnalyzer::new(positions, corpus_length)?,
+        })
+    }
+
+    fn get_frequency(&self) -> usize {
+        self.inner.get_frequency()
+    }
+
+    fn get_corpus_length(&self) -> usize {
+        self.inner.get_corpus_length()
+    }
+
+    fn get_distances(&self) -> Vec<f64> {
+        self.inner.get_distances().to_vec()
+    }
+
+    fn get_arf(&self) -> Option<f64> {
+        self.inner.get_arf()
+    }
+
+    fn get_awt(&self) -> Option<f64> {
+        self.inner.get_awt()
+    }
+
+    fn get_ald(&self) -> Option<f64> {
+        self.inner.get_ald()
+    }
+
+    fn get_self_dispersion(&self) -> Option<f64> {
+        self.inner.get_self_dispersion()
+    }
+
+    fn get_mean_distance(&self) -> Option<f64> {
+        self.inner.get_mean_distance()
+    }
+
+    fn get_distance_variance(&self) -> Option<f64> {
+        self.inner.get_distance_variance()
+    }
+
+    fn get_burstiness(&self) -> Option<f64> {
+        self.inner.get_burstiness()
+    }
+
+    fn calculate_all_metrics(&self) -> PositionMetrics {
+        self.inner.calculate_all_metrics()
+    }
+
+    #[staticmethod]
+    #[pyo3(signature = (positions, corpus_length, *, n_threads=None))]
+    fn calculate_batch_metrics(
+        py: Python<'_>,
+        positions: Vec<Vec<usize>>,
+        corpus_length: usize,
+        n_threads: Option<usize>,
+    ) -> PyResult<Vec<PositionMetrics>> {
+        let parallelism = Parallelism::from_n_threads(n_threads);
+        let result = py.allow_threads(|| {
+            parallelism
+                .install(|| PositionAnalyzer::calculate_batch_metrics(positions, corpus_length))?
+        });
+        Ok(result?)
+    }
+}
@@ -0,0 +1,198 @@
+//! Python wrapper for `FrequencyTable`
+
+use super::exceptions;
+use super::metric_selection;
+use super::partition::PyCorpusPartition;
+use crate::corpus::{CorpusConfig, PartitionBy, Tokenizer, build_frequency_table};
+use crate::metrics::LabelledMetrics;
+use crate::parallel::Parallelism;
+use crate::table::{FrequencyTable, TableConfig};
+use numpy::ndarray::ArrayView2;
+use numpy::{IntoPyArray, PyArray2, PyArrayMethods};
+use pyo3::exceptions::PyOverflowError;
+use pyo3::prelude::*;
+use std::path::PathBuf;
+use std::sync::Arc;
+
+/// Word × part frequency table read from a CSV/TSV file or counted from text files
+#[pyclass(name = "FrequencyTable", frozen)]
+pub struct PyFrequencyTable {
+    inner: FrequencyTable,
+}
+
+#[pymethods]
+impl PyFrequencyTable {
+    #[new]
+    #[pyo3(signature = (path, *, delimiter="\t".to_string(), has_header=true, quote=Some("\"".to_string()), size_row=None))]
+    fn new(
+        py: Python<'_>,
+        path: PathBuf,
+        delimiter: String,
+        has_header: bool,
+        quote: Option<String>,
+        size_row: Option<String>,
+    ) -> PyResult<Self> {
+        let config = TableConfig {
+            delimiter: ascii_byte("delimiter", &delimiter)?,
+            has_header,
+            quote: quote.map(|quote| ascii_byte("quote", &quote)).transpose()?,
+            size_row,
+        };
+        let inner = py.allow_threads(|| FrequencyTable::from_path(&path, &config))?;
+        Ok(Self { inner })
+    }
+
+    /// Count the text files of a directory; each file (or subdirectory) is a part
+    #[staticmethod]
+    #[allow(clippy::too_many_arguments)] // Mirrors the Python keyword arguments
+    #[pyo3(signature = (
+        path,
+        *,
+        partition_by="file",
+        extensions=Some(vec!["txt".to_string()]),
+        lowercase=true,
+        keep_numbers=true,
+        joiners="'\u{2019}-".to_string(),
+        split_cjk=true,
+        min_length=1,
+        n_threads=None,
+    ))]
+    fn from_directory(
+        py: Python<'_>,
+        path: PathBuf,
+        partition_by: &str,
+        extensions: Option<Vec<String>>,
+        lowercase: bool,
+        keep_numbers: bool,
+        joiners: String,
+        split_cjk: bool,
+        min_length: usize,
+        n_threads: Option<usize>,
+    ) -> PyResult<Self> {
+        let partition_by = PartitionBy::from_name(partition_by).ok_or_else(|| {
+            exceptions::InvalidParameterError::new_err(format!(
+                "partition_by must be 'file' or 'directory', got '{partition_by}'."
+            ))
+        })?;
+        let config = CorpusConfig {
+            partition_by,
+            extensions: extensions.unwrap_or_default(),
+            tokenizer: Tokenizer {
+                lowercase,
+                keep_numbers,
+                joiners,
+                split_cjk,
+                min_length,
+            },
+        };
+        let parallelism = Parallelism::from_n_threads(n_threads);
+        let inner =
+            py.allow_threads(|| parallelism.install(|| build_frequency_table(&path, &config))?)?;
+        Ok(Self { inner })
+    }
+
+    #[getter]
+    fn words(&self) -> Vec<String> {
+        self.inner.words().to_vec()
+    }
+
+    #[getter]
+    fn part_names(&self) -> Vec<String> {
+        self.inner.part_names().to_vec()
+    }
+
+    #[getter]
+    fn n_words(&self) -> usize {
+        self.inner.n_words()
+    }
+
+    #[getter]
+    fn n_parts(&self) -> usize {
+        self.inner.n_parts()
+    }
+
+    /// Corpus partition built from the part sizes
+    #[getter]
+    fn partition(&self) -> PyCorpusPartition {
+        PyCorpusPartition {
+            inner: Arc::clone(self.inner.partition()),
+        }
+    }
+
+    /// Frequencies as a read-only NumPy view (dense tables) or a SciPy CSR
+    /// matrix (sparse tables counted from a directory)
+    #[getter]
+    fn matrix(this: Bound<'_, Self>) -> PyResult<Bound<'_, PyAny>> {
+        let py = this.py();
+        let table = &this.get().inner;
+        if let Some(csr) = table.sparse_matrix() {
+            let to_i64 = |values: &[usize]| -> PyResult<Vec<i64>> {
+                values
+                    .iter()
+                    .map(|&value| i64::try_from(value))
+                    .collect::<Result<_, _>>()
+                    .map_err(|e| PyOverflowError::new_err(e.to_string()))
+            };
+            let (indptr, indices, values) = csr.buffers();
+            let arrays = (
+                values.to_vec().into_pyarray(py),
+                to_i64(indices)?.into_pyarray(py),
+                to_i64(indptr)?.into_pyarray(py),
+            );
+            return py
+                .import("scipy.sparse")?
+                .getattr("csr_matrix")?
+                .call1((arrays, (table.n_words(), table.n_parts())));
+        }
+        let data = table.data().expect("a table is either dense or sparse");
+        let view = ArrayView2::from_shape((table.n_words(), table.n_parts()), data)
+            .expect("table buffer holds one row per word");
+        // SAFETY: the table is frozen, so its buffer is never modified or
+        // reallocated while the array keeps `this` alive as its base object.
+        let array = unsafe { PyArray2::borrow_from_array(&view, this.clone().into_any()) };
+        array.readwrite().make_nonwriteable();
+        Ok(array.into_any())
+    }
+
+    /// Frequencies of the word in row `index` (`None` if out of range)
+    fn row(&self, index: usize) -> Option<Vec<f64>> {
+        self.inner.row(index)
+    }
+
+    /// Metrics of every word, looked up by word or position
+    #[pyo3(signature = (metrics=None, n_threads=None))]
+    fn calculate_metrics(
+        &self,
+        py: Python<'_>,
+        metrics: Option<Vec<String>>,
+        n_threads: Option<usize>,
+    ) -> PyResult<LabelledMetrics> {
+        let selection = metric_selection(metrics)?;
+        let parallelism = Parallelism::from_n_threads(n_threads);
+        let result =
+            py.allow_threads(|| parallelism.install(|| self.inner.calculate_metrics(selection))?);
+        Ok(result?)
+    }
+
+    fn __len__(&self) -> usize {
+        self.inner.n_words()
+    }
+
+    fn __repr__(&self) -> String {
+        format!(
+            "FrequencyTable(n_words={}, n_parts={})",
+            self.inner.n_words(),
+            self.inner.n_parts()
+        )
+    }
+}
+
+// Single ASCII character given as a Python string
+fn ascii_byte(name: &str, value: &str) -> PyResult<u8> {
+    match value.as_bytes() {
+        &[byte] => Ok(byte),
+        _ => Err(exceptions::InvalidParameterError::new_err(format!(
+            "{name} must be a single ASCII character, got {value:?}."
+        ))),
+    }
+}
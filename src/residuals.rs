@@ -0,0 +1,272 @@
+//! Frequency-adjusted dispersion residuals across a word list
+//!
+//! Most dispersion metrics drift with frequency: a rare word looks clumped
+//! simply because it has few tokens to spread. Words are grouped into bands
+//! of roughly equal size by total frequency; the median of each metric in
+//! each band, placed at the band's median log frequency, gives a
+//! piecewise-linear trend of the metric against log frequency. Residuals from
+//! that trend and percentiles within the band measure dispersion beyond what
+//! frequency predicts.
+
+use crate::error::{DispersionError, Result, validate_frequencies};
+use crate::metrics::DispersionMetrics;
+use crate::parallel::run_batch;
+use std::fmt;
+
+/// Settings of the frequency trend
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResidualConfig {
+    /// Number of frequency bands (default 10); words sharing a frequency
+    /// always fall in the same band, so fewer bands may be used
+    pub n_bands: usize,
+}
+
+impl Default for ResidualConfig {
+    fn default() -> Self {
+        Self { n_bands: 10 }
+    }
+}
+
+impl ResidualConfig {
+    /// Check the settings
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::InvalidParameter` if `n_bands` is zero.
+    #[allow(clippy::cast_precision_loss)] // Only used in the error message
+    pub fn validate(&self) -> Result<()> {
+        if self.n_bands == 0 {
+            return Err(DispersionError::InvalidParameter {
+                name: "n_bands",
+                value: self.n_bands as f64,
+                reason: "at least 1",
+            });
+        }
+        Ok(())
+    }
+}
+
+/// A word's metrics relative to words of similar frequency
+///
+/// `range` is not modelled and stays 0 in `predicted`, `residual` and
+/// `percentile`. For words that never occur, `observed` still holds their
+/// metrics, while every field of `predicted`, `residual` and `percentile`
+/// and the `band` are `None`.
+#[cfg_attr(feature = "python", pyo3::pyclass(get_all))]
+#[derive(Debug, Clone)]
+pub struct FrequencyResiduals {
+    /// Metrics of the word
+    pub observed: DispersionMetrics,
+    /// Trend value at the word's log frequency
+    pub predicted: DispersionMetrics,
+    /// `observed − predicted`
+    pub residual: DispersionMetrics,
+    /// Mid-rank percentile (0–100) of the observed value within the word's band
+    pub percentile: DispersionMetrics,
+    /// Total frequency of the word
+    pub frequency: f64,
+    /// Frequency band, 0 for the rarest words (`None` if the word never occurs)
+    pub band: Option<usize>,
+}
+
+impl fmt::Display for FrequencyResiduals {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "FrequencyResiduals(frequency={}, band={})",
+            self.frequency,
+            self.band
+                .map_or_else(|| "None".to_string(), |band| band.to_string())
+        )
+    }
+}
+
+/// Fit every metric present in `metrics` against log frequency
+///
+/// `frequencies[i]` is the total frequency of the word whose metrics are
+/// `metrics[i]`. Metric fields that are `None` for a word are left out of
+/// its band's trend.
+///
+/// # Errors
+///
+/// Returns `DispersionError::InvalidParameter` for an invalid `config`,
+/// `LengthMismatch` if the slices differ in length, or `NegativeFrequency` /
+/// `NonFiniteValue` for an invalid frequency.
+pub fn frequency_residuals(
+    metrics: Vec<DispersionMetrics>,
+    frequencies: &[f64],
+    config: &ResidualConfig,
+) -> Result<Vec<FrequencyResiduals>> {
+    use rayon::prelude::*;
+
+    config.validate()?;
+    validate_frequencies(frequencies, metrics.len())?;
+
+    let bands = assign_bands(frequencies, config.n_bands);
+    let mut members: Vec<Vec<usize>> = Vec::new();
+    for (word, &band) in bands.iter().enumerate() {
+        if let Some(band) = band {
+            if members.len() <= band {
+                members.resize_with(band + 1, Vec::new);
+            }
+            members[band].push(word);
+        }
+    }
+    let log_frequencies: Vec<f64> = frequencies.iter().map(|f| f.ln()).collect();
+    let centers: Vec<f64> = members
+        .iter()
+        .map(|words| {
+            let mut values: Vec<f64> = words.iter().map(|&w| log_frequencies[w]).collect();
+            values.sort_by(f64::total_cmp);
+            median(&values)
+        })
+        .collect();
+
+    let fits: Vec<Vec<Fit>> = run_batch(|| {
+        (0..DispersionMetrics::FLOAT_FIELDS.len())
+            .into_par_iter()
+            .map(|index| {
+                let values: Vec<Option<f64>> = metrics
+                    .iter()
+                    .map(|m| m.float_values()[index].filter(|v| !v.is_nan()))
+                    .collect();
+                fit_metric(&values, &bands, &members, &centers, &log_frequencies)
+            })
+            .collect()
+    });
+
+    Ok(metrics
+        .into_iter()
+        .enumerate()
+        .map(|(word, observed)| {
+            let mut predicted = DispersionMetrics::default();
+            let mut residual = DispersionMetrics::default();
+            let mut percentile = DispersionMetrics::default();
+            for (index, ((p, r), q)) in predicted
+                .float_values_mut()
+                .into_iter()
+                .zip(residual.float_values_mut())
+                .zip(percentile.float_values_mut())
+                .enumerate()
+            {
+                let fit = fits[index][word];
+                *p = fit.predicted;
+                *r = fit.residual;
+                *q = fit.percentile;
+            }
+            FrequencyResiduals {
+                observed,
+                predicted,
+                residual,
+                percentile,
+                frequency: frequencies[word],
+                band: bands[word],
+            }
+        })
+        .collect())
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct Fit {
+    predicted: Option<f64>,
+    residual: Option<f64>,
+    percentile: Option<f64>,
+}
+
+// Equal-count bands over the occurring words, never splitting a frequency
+// across bands; band numbers are consecutive from 0
+fn assign_bands(frequencies: &[f64], n_bands: usize) -> Vec<Option<usize>> {
+    let mut order: Vec<usize> = (0..frequencies.len())
+        .filter(|&w| frequencies[w] > 0.0)
+        .collect();
+    order.sort_by(|&a, &b| frequencies[a].total_cmp(&frequencies[b]));
+
+    let mut bands = vec![None; frequencies.len()];
+    let mut band = 0;
+    let mut target = 0;
+    let mut previous = None;
+    for (rank, &word) in order.iter().enumerate() {
+        if previous != Some(frequencies[word]) {
+            let rank_band = rank * n_bands / order.len();
+            if rank_band > target {
+                band += 1;
+            }
+            target = target.max(rank_band);
+            previous = Some(frequencies[word]);
+        }
+        bands[word] = Some(band);
+    }
+    bands
+}
+
+// Trend, residual and within-band percentile of one metric for every word
+#[allow(clippy::cast_precision_loss)] // Band sizes are far below 2^52
+fn fit_metric(
+    values: &[Option<f64>],
+    bands: &[Option<usize>],
+    members: &[Vec<usize>],
+    centers: &[f64],
+    log_frequencies: &[f64],
+) -> Vec<Fit> {
+    let sorted_bands: Vec<Vec<f64>> = members
+        .iter()
+        .map(|words| {
+            let mut band_values: Vec<f64> = words.iter().filter_map(|&w| values[w]).collect();
+            band_values.sort_by(f64::total_cmp);
+            band_values
+        })
+        .collect();
+    // (log frequency, median) knots of the bands where the metric exists
+    let knots: Vec<(f64, f64)> = sorted_bands
+        .iter()
+        .zip(centers)
+        .filter(|(band_values, _)| !band_values.is_empty())
+        .map(|(band_values, &center)| (center, median(band_values)))
+        .collect();
+
+    values
+        .iter()
+        .zip(bands)
+        .zip(log_frequencies)
+        .map(|((&value, &band), &x)| {
+            let (Some(value), Some(band)) = (value, band) else {
+                return Fit::default();
+            };
+            let band_values = &sorted_bands[band];
+            let below = band_values.partition_point(|&v| v < value);
+            let at_most = band_values.partition_point(|&v| v <= value);
+            let predicted = interpolate(&knots, x);
+            Fit {
+                predicted: Some(predicted),
+                residual: Some(value - predicted),
+                percentile: Some(
+                    100.0 * (below as f64 + 0.5 * (at_most - below) as f64)
+                        / band_values.len() as f64,
+                ),
+            }
+        })
+        .collect()
+}
+
+// Piecewise-linear interpolation through the knots, constant beyond the ends
+fn interpolate(knots: &[(f64, f64)], x: f64) -> f64 {
+    let upper = knots.partition_point(|&(knot_x, _)| knot_x < x);
+    if upper == 0 {
+        return knots[0].1;
+    }
+    if upper == knots.len() {
+        return knots[knots.len() - 1].1;
+    }
+    let (x0, y0) = knots[upper - 1];
+    let (x1, y1) = knots[upper];
+    ((x - x0) / (x1 - x0)).mul_add(y1 - y0, y0)
+}
+
+fn median(sorted: &[f64]) -> f64 {
+    let mid = sorted.len() / 2;
+    if sorted.len().is_multiple_of(2) {
+        f64::midpoint(sorted[mid - 1], sorted[mid])
+    } else {
+        sorted[mid]
+    }
+}
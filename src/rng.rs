@@ -0,0 +1,48 @@
+//! Small seedable random number generator for the resampling APIs
+//!
+//! Every resample or simulation gets its own generator derived from
+//! `(seed, row, index)`, so results are reproducible from one seed and do
+//! not depend on how rayon schedules the work.
+
+use std::collections::hash_map::RandomState;
+use std::hash::BuildHasher;
+
+/// A fresh seed from the process-wide random source
+pub(crate) fn fresh_seed() -> u64 {
+    RandomState::new().hash_one(0x5eed_u64)
+}
+
+/// Seed of the independent stream for `(seed, row, index)`
+pub(crate) fn stream_seed(seed: u64, row: usize, index: usize) -> u64 {
+    let mut mixer = SplitMix64::new(seed ^ (row as u64).wrapping_mul(0x9E37_79B9_7F4A_7C15));
+    mixer.next_u64() ^ (index as u64).wrapping_mul(0xD1B5_4A32_D192_ED03)
+}
+
+/// `SplitMix64` generator (Steele, Lea & Flood 2014): small, fast and seedable
+pub(crate) struct SplitMix64(u64);
+
+impl SplitMix64 {
+    pub(crate) const fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub(crate) const fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// Uniform index in `0..n` by Lemire's multiply-shift
+    #[allow(clippy::cast_possible_truncation)]
+    pub(crate) fn next_index(&mut self, n: usize) -> usize {
+        ((u128::from(self.next_u64()) * n as u128) >> 64) as usize
+    }
+
+    /// Uniform float in `[0, 1)` with 53 random bits
+    #[allow(clippy::cast_precision_loss)]
+    pub(crate) fn next_f64(&mut self) -> f64 {
+        (self.next_u64() >> 11) as f64 * (1.0 / (1u64 << 53) as f64)
+    }
+}
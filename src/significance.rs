@@ -0,0 +1,436 @@
+//! Significance tests of a word's distribution against the part sizes
+//!
+//! The observed frequencies are compared with the size-proportional
+//! expectation `f · s_i`. P-values come from the chi-square distribution via
+//! the regularized incomplete gamma function, so no statistics library is
+//! needed. `NullDistribution` instead simulates that expectation: the word's
+//! tokens are scattered over the parts by multinomial sampling and the
+//! observed metrics are located in the simulated distribution.
+
+use crate::analyzer::CorpusWordAnalyzer;
+use crate::error::{DispersionError, Result};
+use crate::metrics::{DispersionMetrics, MetricSet};
+use crate::parallel::run_batch;
+use crate::partition::CorpusPartition;
+use crate::rng::{SplitMix64, fresh_seed, stream_seed};
+use std::fmt;
+use std::sync::Arc;
+
+/// Chi-square and G-test (log-likelihood) goodness of fit of a single word
+#[cfg_attr(feature = "python", pyo3::pyclass(get_all))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct GoodnessOfFit {
+    /// Pearson's chi-square statistic Σ (v_i − f·s_i)² / (f·s_i)
+    pub chi_square: f64,
+    /// Log-likelihood statistic G = 2 Σ v_i ln(v_i / (f·s_i))
+    pub g_statistic: f64,
+    /// Number of parts with a positive size, minus one
+    pub degrees_of_freedom: usize,
+    /// Upper-tail probability of `chi_square` (`None` without degrees of freedom)
+    pub chi_square_p_value: Option<f64>,
+    /// Upper-tail probability of `g_statistic` (`None` without degrees of freedom)
+    pub g_p_value: Option<f64>,
+}
+
+impl GoodnessOfFit {
+    /// Attach chi-square p-values to the two statistics
+    #[must_use]
+    pub fn new(chi_square: f64, g_statistic: f64, degrees_of_freedom: usize) -> Self {
+        Self {
+            chi_square,
+            g_statistic,
+            degrees_of_freedom,
+            chi_square_p_value: chi_square_sf(chi_square, degrees_of_freedom),
+            g_p_value: chi_square_sf(g_statistic, degrees_of_freedom),
+        }
+    }
+}
+
+impl fmt::Display for GoodnessOfFit {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt_opt =
+            |val: Option<f64>| val.map_or_else(|| "None".to_string(), |v| format!("{v:.4e}"));
+        write!(
+            f,
+            concat!(
+                "GoodnessOfFit(chi_square={:.4}, g_statistic={:.4}, degrees_of_freedom={}, ",
+                "chi_square_p_value={}, g_p_value={})"
+            ),
+            self.chi_square,
+            self.g_statistic,
+            self.degrees_of_freedom,
+            fmt_opt(self.chi_square_p_value),
+            fmt_opt(self.g_p_value)
+        )
+    }
+}
+
+/// Upper-tail probability P(X ≥ `statistic`) of a chi-square distribution
+///
+/// Returns `None` for zero degrees of freedom or a NaN statistic.
+#[must_use]
+#[allow(clippy::cast_precision_loss)] // Degrees of freedom are far below 2^52
+pub fn chi_square_sf(statistic: f64, degrees_of_freedom: usize) -> Option<f64> {
+    if degrees_of_freedom == 0 || statistic.is_nan() {
+        return None;
+    }
+    if statistic <= 0.0 {
+        return Some(1.0);
+    }
+    Some(regularized_gamma_q(
+        degrees_of_freedom as f64 / 2.0,
+        statistic / 2.0,
+    ))
+}
+
+/// Settings of a simulated null distribution
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationConfig {
+    /// Number of simulated words (default 1000)
+    pub n_simulations: usize,
+    /// Seed of the random generator; `None` draws a fresh seed per call
+    pub seed: Option<u64>,
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        Self {
+            n_simulations: 1000,
+            seed: None,
+        }
+    }
+}
+
+impl SimulationConfig {
+    /// Check the settings
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::InvalidParameter` if `n_simulations` is zero.
+    pub fn validate(&self) -> Result<()> {
+        if self.n_simulations == 0 {
+            return Err(DispersionError::InvalidParameter {
+                name: "n_simulations",
+                value: 0.0,
+                reason: "at least 1",
+            });
+        }
+        Ok(())
+    }
+
+    // Configured seed, or a fresh random one
+    pub(crate) fn resolve_seed(&self) -> u64 {
+        self.seed.unwrap_or_else(fresh_seed)
+    }
+}
+
+/// Observed metrics of a word located in their simulated null distribution
+///
+/// Under the null, the word's `f` tokens (rounded to a whole number) fall
+/// into part `i` with probability proportional to `s_i`. Each field is a
+/// `DispersionMetrics` holding one statistic per requested metric; `range`
+/// is only meaningful in `observed`. Empirical p-values use the `(k + 1) /
+/// (n + 1)` estimate, so they are never zero. Which tail signals clumping
+/// depends on the metric: `p_value_greater` for DP-like measures (higher =
+/// more clumped), `p_value_less` for D, D2, DA and the other evenness measures.
+#[cfg_attr(feature = "python", pyo3::pyclass(get_all))]
+#[derive(Debug, Clone)]
+pub struct NullDistribution {
+    /// Metrics of the observed frequencies
+    pub observed: DispersionMetrics,
+    /// Mean of the simulated metrics
+    pub expected: DispersionMetrics,
+    /// Sample standard deviation of the simulated metrics
+    pub std_dev: DispersionMetrics,
+    /// `(observed − expected) / std_dev` (`None` when the simulations do not vary)
+    pub z_score: DispersionMetrics,
+    /// Share of simulations at least as large as the observed value
+    pub p_value_greater: DispersionMetrics,
+    /// Share of simulations at most as large as the observed value
+    pub p_value_less: DispersionMetrics,
+    /// Number of simulated words
+    pub n_simulations: usize,
+    /// Seed the simulations were drawn from (pass it back to reproduce them)
+    pub seed: u64,
+}
+
+impl fmt::Display for NullDistribution {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "NullDistribution(n_simulations={}, seed={})",
+            self.n_simulations, self.seed
+        )
+    }
+}
+
+/// Simulate the null distribution of one word whose `observed` metrics are known
+///
+/// `row` selects the random streams (see `crate::rng`).
+#[allow(clippy::cast_precision_loss)] // Simulation counts are far below 2^52
+pub(crate) fn simulate_null(
+    f: f64,
+    partition: &Arc<CorpusPartition>,
+    selection: MetricSet,
+    config: &SimulationConfig,
+    seed: u64,
+    row: usize,
+    observed: DispersionMetrics,
+) -> NullDistribution {
+    use rayon::prelude::*;
+
+    // Without a part of positive size there is nowhere to put tokens
+    #[allow(clippy::cast_possible_truncation, clippy::cast_sign_loss)]
+    let n_tokens = if partition.s().iter().any(|&s_i| s_i > 0.0) {
+        f.round() as u64
+    } else {
+        0
+    };
+
+    let samples: Vec<DispersionMetrics> = run_batch(|| {
+        (0..config.n_simulations)
+            .into_par_iter()
+            .map(|simulation| {
+                let mut rng = SplitMix64::new(stream_seed(seed, row, simulation));
+                let v = scatter_tokens(n_tokens, partition.s(), &mut rng);
+                CorpusWordAnalyzer::with_partition(v, Arc::clone(partition))
+                    .expect("simulated counts are non-negative, one per part")
+                    .calculate_metrics(selection)
+            })
+            .collect()
+    });
+
+    let mut expected = DispersionMetrics::default();
+    let mut std_dev = DispersionMetrics::default();
+    let mut z_score = DispersionMetrics::default();
+    let mut p_value_greater = DispersionMetrics::default();
+    let mut p_value_less = DispersionMetrics::default();
+    let observed_values = observed.float_values();
+
+    for (index, ((((mean, sd), z), greater), less)) in expected
+        .float_values_mut()
+        .into_iter()
+        .zip(std_dev.float_values_mut())
+        .zip(z_score.float_values_mut())
+        .zip(p_value_greater.float_values_mut())
+        .zip(p_value_less.float_values_mut())
+        .enumerate()
+    {
+        let values: Vec<f64> = samples
+            .iter()
+            .filter_map(|m| m.float_values()[index])
+            .filter(|v| !v.is_nan())
+            .collect();
+        let Some(obs) = observed_values[index] else {
+            continue;
+        };
+        if values.is_empty() {
+            continue;
+        }
+        let n = values.len() as f64;
+        let m = values.iter().sum::<f64>() / n;
+        let s = if values.len() > 1 {
+            (values.iter().map(|&x| (x - m).powi(2)).sum::<f64>() / (n - 1.0)).sqrt()
+        } else {
+            0.0
+        };
+        // Tolerate rounding noise when simulated and observed values coincide
+        let tolerance = 1e-12 * obs.abs().max(1.0);
+        let at_least = values.iter().filter(|&&x| x >= obs - tolerance).count();
+        let at_most = values.iter().filter(|&&x| x <= obs + tolerance).count();
+
+        *mean = Some(m);
+        *sd = Some(s);
+        *z = (s > 1e-12).then(|| (obs - m) / s);
+        *greater = Some((at_least as f64 + 1.0) / (n + 1.0));
+        *less = Some((at_most as f64 + 1.0) / (n + 1.0));
+    }
+
+    NullDistribution {
+        observed,
+        expected,
+        std_dev,
+        z_score,
+        p_value_greater,
+        p_value_less,
+        n_simulations: config.n_simulations,
+        seed,
+    }
+}
+
+// Multinomial draw of `n_tokens` tokens over the parts, as one binomial draw
+// per part conditional on the tokens not yet placed (O(parts) per draw)
+#[allow(clippy::cast_precision_loss)] // Token counts are far below 2^52
+fn scatter_tokens(n_tokens: u64, s: &[f64], rng: &mut SplitMix64) -> Vec<f64> {
+    let mut v = vec![0.0; s.len()];
+    let last = s.iter().rposition(|&s_i| s_i > 0.0);
+    let mut remaining = n_tokens;
+    let mut mass: f64 = s.iter().sum();
+    for (i, &s_i) in s.iter().enumerate() {
+        if remaining == 0 {
+            break;
+        }
+        if s_i <= 0.0 {
+            continue;
+        }
+        let drawn = if Some(i) == last {
+            remaining
+        } else {
+            binomial(remaining, (s_i / mass).min(1.0), rng)
+        };
+        v[i] = drawn as f64;
+        remaining -= drawn;
+        mass -= s_i;
+    }
+    v
+}
+
+// Binomial(n, p) draw: inversion for a small mean, otherwise transformed
+// rejection with squeeze (BTRS, Hörmann 1993) in expected constant time
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn binomial(n: u64, p: f64, rng: &mut SplitMix64) -> u64 {
+    if n == 0 || p <= 0.0 {
+        return 0;
+    }
+    if p >= 1.0 {
+        return n;
+    }
+    if p > 0.5 {
+        return n - binomial(n, 1.0 - p, rng);
+    }
+    let n_f = n as f64;
+    let q = 1.0 - p;
+
+    if n_f * p < 10.0 {
+        // Walk the probability mass function up from 0
+        let ratio = p / q;
+        let mut mass = q.powf(n_f);
+        let mut u = rng.next_f64();
+        let mut k = 0;
+        while u > mass && k < n {
+            u -= mass;
+            k += 1;
+            mass *= ratio * (n_f - k as f64 + 1.0) / k as f64;
+        }
+        return k;
+    }
+
+    let spq = (n_f * p * q).sqrt();
+    let b = 2.53f64.mul_add(spq, 1.15);
+    let a = 0.01f64.mul_add(p, 0.0248f64.mul_add(b, -0.0873));
+    let c = n_f.mul_add(p, 0.5);
+    let alpha = (2.83 + 5.1 / b) * spq;
+    let v_r = 0.92 - 4.2 / b;
+    let ln_odds = (p / q).ln();
+    let mode = ((n_f + 1.0) * p).floor();
+    let h = ln_gamma(mode + 1.0) + ln_gamma(n_f - mode + 1.0);
+    loop {
+        let u = rng.next_f64() - 0.5;
+        let v = rng.next_f64();
+        let us = 0.5 - u.abs();
+        let k = (2.0 * a / us + b).mul_add(u, c).floor();
+        if !(0.0..=n_f).contains(&k) {
+            continue;
+        }
+        if us >= 0.07 && v <= v_r {
+            return k as u64;
+        }
+        let log_v = (v * alpha / (a / (us * us) + b)).ln();
+        if log_v <= (k - mode).mul_add(ln_odds, h - ln_gamma(k + 1.0) - ln_gamma(n_f - k + 1.0)) {
+            return k as u64;
+        }
+    }
+}
+
+const GAMMA_EPS: f64 = 1e-15;
+const GAMMA_MAX_ITER: usize = 10_000;
+const GAMMA_FPMIN: f64 = f64::MIN_POSITIVE / f64::EPSILON;
+
+// Lanczos approximation (g = 7, n = 9) of ln Γ(x) for x > 0
+fn ln_gamma(x: f64) -> f64 {
+    const COEFFS: [f64; 9] = [
+        0.999_999_999_999_809_9,
+        676.520_368_121_885_1,
+        -1_259.139_216_722_402_8,
+        771.323_428_777_653_1,
+        -176.615_029_162_140_6,
+        12.507_343_278_686_905,
+        -0.138_571_095_265_720_12,
+        9.984_369_578_019_572e-6,
+        1.505_632_735_149_311_6e-7,
+    ];
+    if x < 0.5 {
+        // Reflection formula keeps the approximation in its accurate range
+        return (std::f64::consts::PI / (std::f64::consts::PI * x).sin()).ln() - ln_gamma(1.0 - x);
+    }
+    let x = x - 1.0;
+    let mut sum = COEFFS[0];
+    for (i, &c) in COEFFS.iter().enumerate().skip(1) {
+        #[allow(clippy::cast_precision_loss)]
+        let i = i as f64;
+        sum += c / (x + i);
+    }
+    let t = x + 7.5;
+    0.5f64.mul_add(
+        (2.0 * std::f64::consts::PI).ln(),
+        (x + 0.5).mul_add(t.ln(), -t),
+    ) + sum.ln()
+}
+
+// Regularized upper incomplete gamma Q(a, x) = Γ(a, x) / Γ(a)
+fn regularized_gamma_q(a: f64, x: f64) -> f64 {
+    if x < a + 1.0 {
+        (1.0 - gamma_p_series(a, x)).clamp(0.0, 1.0)
+    } else {
+        gamma_q_continued_fraction(a, x).clamp(0.0, 1.0)
+    }
+}
+
+// Series expansion of P(a, x), convergent for x < a + 1
+fn gamma_p_series(a: f64, x: f64) -> f64 {
+    let mut ap = a;
+    let mut term = 1.0 / a;
+    let mut sum = term;
+    for _ in 0..GAMMA_MAX_ITER {
+        ap += 1.0;
+        term *= x / ap;
+        sum += term;
+        if term.abs() < sum.abs() * GAMMA_EPS {
+            break;
+        }
+    }
+    sum * (a.mul_add(x.ln(), -x) - ln_gamma(a)).exp()
+}
+
+// Lentz's continued fraction for Q(a, x), convergent for x ≥ a + 1
+#[allow(clippy::cast_precision_loss)]
+fn gamma_q_continued_fraction(a: f64, x: f64) -> f64 {
+    let mut b = x + 1.0 - a;
+    let mut c = 1.0 / GAMMA_FPMIN;
+    let mut d = 1.0 / b;
+    let mut h = d;
+    for i in 1..=GAMMA_MAX_ITER {
+        let i = i as f64;
+        let an = -i * (i - a);
+        b += 2.0;
+        d = an.mul_add(d, b);
+        if d.abs() < GAMMA_FPMIN {
+            d = GAMMA_FPMIN;
+        }
+        c = b + an / c;
+        if c.abs() < GAMMA_FPMIN {
+            c = GAMMA_FPMIN;
+        }
+        d = 1.0 / d;
+        let delta = d * c;
+        h *= delta;
+        if (delta - 1.0).abs() < GAMMA_EPS {
+            break;
+        }
+    }
+    (a.mul_add(x.ln(), -x) - ln_gamma(a)).exp() * h
+}
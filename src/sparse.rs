@@ -0,0 +1,439 @@
+//! Sparse (CSR) frequency matrices and zero-aware metric computation
+//!
+//! Word × document matrices are mostly zeros. Every metric here iterates only
+//! the stored cells of a row; the contribution of the remaining zero cells is
+//! folded in through per-partition sums precomputed in `CorpusPartition`.
+
+use crate::error::{DispersionError, Result};
+use crate::matrix::{FrequencyRows, FrequencyValue};
+use crate::metrics::{DispersionMetrics, MetricSet};
+use crate::partition::{CorpusPartition, dp_nofreq, jsd_q_term};
+use std::f64::consts::LN_2;
+
+/// Index types accepted in CSR `indptr` / `indices` arrays
+pub trait SparseIndex: Copy + Send + Sync {
+    /// Convert to `usize`; negative values map to `usize::MAX` and fail validation
+    fn to_usize(self) -> usize;
+}
+
+impl SparseIndex for usize {
+    #[inline]
+    fn to_usize(self) -> usize {
+        self
+    }
+}
+
+impl SparseIndex for u32 {
+    #[inline]
+    fn to_usize(self) -> usize {
+        usize::try_from(self).unwrap_or(usize::MAX)
+    }
+}
+
+impl SparseIndex for i32 {
+    #[inline]
+    fn to_usize(self) -> usize {
+        usize::try_from(self).unwrap_or(usize::MAX)
+    }
+}
+
+impl SparseIndex for i64 {
+    #[inline]
+    fn to_usize(self) -> usize {
+        usize::try_from(self).unwrap_or(usize::MAX)
+    }
+}
+
+/// Compressed sparse row matrix (word × part) borrowed from existing buffers
+///
+/// Follows the SciPy layout: the stored cells of row `r` are
+/// `indices[indptr[r]..indptr[r + 1]]` with values in `data` at the same
+/// positions. Column indices must be strictly increasing within each row.
+#[derive(Debug, Clone, Copy)]
+pub struct CsrMatrix<'a, T, I = usize> {
+    indptr: &'a [I],
+    indices: &'a [I],
+    data: &'a [T],
+    n_rows: usize,
+    n_cols: usize,
+}
+
+impl<'a, T: FrequencyValue, I: SparseIndex> CsrMatrix<'a, T, I> {
+    /// Wrap CSR buffers as an `n_rows` × `n_cols` matrix
+    ///
+    /// # Errors
+    ///
+    /// Returns `MalformedSparse` if the index arrays are inconsistent (wrapped
+    /// in `DispersionError::Row` when a single row is at fault).
+    pub fn new(
+        indptr: &'a [I],
+        indices: &'a [I],
+        data: &'a [T],
+        n_rows: usize,
+        n_cols: usize,
+    ) -> Result<Self> {
+        if indptr.len() != n_rows + 1 {
+            return Err(DispersionError::MalformedSparse(
+                "indptr length must be the number of rows plus one",
+            ));
+        }
+        if indices.len() != data.len() {
+            return Err(DispersionError::MalformedSparse(
+                "indices and data must have the same length",
+            ));
+        }
+        if indptr[0].to_usize() != 0 || indptr[n_rows].to_usize() != data.len() {
+            return Err(DispersionError::MalformedSparse(
+                "indptr must start at 0 and end at the number of stored values",
+            ));
+        }
+        for row in 0..n_rows {
+            let (start, end) = (indptr[row].to_usize(), indptr[row + 1].to_usize());
+            if start > end || end > data.len() {
+                return Err(
+                    DispersionError::MalformedSparse("indptr must be non-decreasing").in_row(row),
+                );
+            }
+            let mut previous: Option<usize> = None;
+            for &col in &indices[start..end] {
+                let col = col.to_usize();
+                if col >= n_cols {
+                    return Err(
+                        DispersionError::MalformedSparse("column index out of bounds").in_row(row),
+                    );
+                }
+                if previous.is_some_and(|p| col <= p) {
+                    return Err(DispersionError::MalformedSparse(
+                        "column indices must be sorted and unique within a row",
+                    )
+                    .in_row(row));
+                }
+                previous = Some(col);
+            }
+        }
+        Ok(Self {
+            indptr,
+            indices,
+            data,
+            n_rows,
+            n_cols,
+        })
+    }
+
+    #[must_use]
+    pub const fn n_rows(&self) -> usize {
+        self.n_rows
+    }
+
+    #[must_use]
+    pub const fn n_cols(&self) -> usize {
+        self.n_cols
+    }
+
+    /// Number of stored cells
+    #[must_use]
+    pub const fn nnz(&self) -> usize {
+        self.data.len()
+    }
+
+    /// The borrowed `indptr`, `indices` and `data` buffers
+    #[must_use]
+    pub const fn buffers(&self) -> (&'a [I], &'a [I], &'a [T]) {
+        (self.indptr, self.indices, self.data)
+    }
+
+    /// Iterate over the stored `(column, value)` cells of one row
+    pub fn row(&self, row: usize) -> impl Iterator<Item = (usize, f64)> + '_ {
+        let (start, end) = (self.indptr[row].to_usize(), self.indptr[row + 1].to_usize());
+        self.indices[start..end]
+            .iter()
+            .zip(&self.data[start..end])
+            .map(|(&col, &value)| (col.to_usize(), value.to_f64()))
+    }
+
+    /// Check every stored value for negative or non-finite entries
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid cell (in row order) wrapped in `DispersionError::Row`.
+    pub fn validate(&self) -> Result<()> {
+        for row in 0..self.n_rows {
+            for (index, value) in self.row(row) {
+                if !value.is_finite() {
+                    return Err(DispersionError::NonFiniteValue {
+                        field: "Frequency",
+                        index: Some(index),
+                        value,
+                    }
+                    .in_row(row));
+                }
+                if value < 0.0 {
+                    return Err(DispersionError::NegativeFrequency { index, value }.in_row(row));
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+impl<T: FrequencyValue, I: SparseIndex> FrequencyRows for CsrMatrix<'_, T, I> {
+    fn n_rows(&self) -> usize {
+        Self::n_rows(self)
+    }
+
+    fn n_cols(&self) -> usize {
+        Self::n_cols(self)
+    }
+
+    fn dense_row(&self, row: usize) -> Vec<f64> {
+        let mut values = vec![0.0; self.n_cols];
+        for (col, value) in self.row(row) {
+            values[col] = value;
+        }
+        values
+    }
+
+    fn row_total(&self, row: usize) -> f64 {
+        self.row(row).map(|(_, value)| value).sum()
+    }
+
+    fn validate(&self) -> Result<()> {
+        Self::validate(self)
+    }
+}
+
+/// Compute the metrics in `selection` for one row of a sparse matrix given its stored cells
+#[allow(
+    clippy::cast_precision_loss,
+    clippy::cast_possible_truncation,
+    clippy::cast_possible_wrap,
+    clippy::similar_names,
+    clippy::too_many_lines
+)] // Same notation and casts as the dense analyzer
+pub(crate) fn sparse_row_metrics(
+    partition: &CorpusPartition,
+    cells: &[(usize, f64)],
+    selection: MetricSet,
+) -> DispersionMetrics {
+    let sizes = partition.part_sizes();
+    let s = partition.s();
+    let n = s.len();
+    let n_f = n as f64;
+    let n_zero = (n - cells.len()) as f64;
+    let f: f64 = cells.iter().map(|&(_, v)| v).sum();
+    let range = cells.iter().filter(|&&(_, v)| v > 0.0).count() as i32;
+
+    let p: Vec<f64> = cells
+        .iter()
+        .map(|&(c, v)| if sizes[c] > 0.0 { v / sizes[c] } else { 0.0 })
+        .collect();
+    let sum_p: f64 = p.iter().sum();
+    let mean_p = sum_p / n_f;
+    let mean_v = f / n_f;
+
+    let sd_population = if f == 0.0 {
+        0.0
+    } else {
+        let ss = cells
+            .iter()
+            .map(|&(_, v)| (v - mean_v).powi(2))
+            .sum::<f64>()
+            + n_zero * mean_v * mean_v;
+        (ss / n_f).sqrt()
+    };
+    let vc_population = if mean_v.abs() < 1e-12 {
+        0.0
+    } else {
+        sd_population / mean_v
+    };
+
+    let juilland_d = if n <= 1 {
+        if f > 0.0 { 1.0 } else { 0.0 }
+    } else if f == 0.0 || mean_p.abs() < 1e-12 {
+        0.0
+    } else {
+        let ss = p.iter().map(|&x| (x - mean_p).powi(2)).sum::<f64>() + n_zero * mean_p * mean_p;
+        let vc_p = (ss / n_f).sqrt() / mean_p;
+        1.0 - vc_p / ((n - 1) as f64).sqrt()
+    };
+
+    let entropic = MetricSet::CARROLL_D2
+        | MetricSet::CARROLL_UM
+        | MetricSet::SHANNON_ENTROPY
+        | MetricSet::PIELOU_EVENNESS;
+    let entropy = if sum_p.abs() < 1e-12 || !selection.intersects(entropic) {
+        0.0
+    } else {
+        p.iter()
+            .map(|&p_i| {
+                let norm_prop = p_i / sum_p;
+                if norm_prop > 1e-12 {
+                    -norm_prop * norm_prop.ln()
+                } else {
+                    0.0
+                }
+            })
+            .sum::<f64>()
+    };
+    let carroll_d2 = if n <= 1 {
+        if f > 0.0 { 1.0 } else { 0.0 }
+    } else if sum_p.abs() < 1e-12 {
+        0.0
+    } else {
+        entropy / ((n_f.ln() / LN_2) * LN_2)
+    };
+
+    let simpson_index = if sum_p.abs() < 1e-12 {
+        0.0
+    } else {
+        p.iter().map(|&p_i| (p_i / sum_p).powi(2)).sum::<f64>()
+    };
+
+    // Unstored cells have p_i = 0 and add nothing to Σ√p_i
+    let zhang_dc = if sum_p.abs() < 1e-12 {
+        0.0
+    } else {
+        let sum_sqrt = p.iter().map(|&p_i| p_i.sqrt()).sum::<f64>();
+        (sum_sqrt * sum_sqrt) / (n_f * sum_p)
+    };
+
+    let divergences = MetricSet::LYNE_D3
+        | MetricSet::ROSCHENGREN_S_ADJ
+        | MetricSet::ROSCHENGREN_AF
+        | MetricSet::DP
+        | MetricSet::DP_NORM
+        | MetricSet::DP_NOFREQ
+        | MetricSet::KL_DIVERGENCE
+        | MetricSet::KL_DIVERGENCE_NORM
+        | MetricSet::JSD_DISPERSION
+        | MetricSet::HELLINGER_DISPERSION;
+    let (lyne_d3, roschengren_af, dp, kl_divergence, jsd_dispersion, hellinger_dispersion) =
+        if f == 0.0 || !selection.intersects(divergences) {
+            (0.0, 0.0, 0.0, 0.0, 0.0, 0.0)
+        } else {
+            let mut chi_square_stored = 0.0;
+            let mut s_stored = 0.0;
+            let mut sum_sqrt = 0.0;
+            let mut dp_stored = 0.0;
+            let mut kl = 0.0;
+            let mut kl_pm = 0.0;
+            let mut kl_qm = partition.jsd_zero_total();
+            let mut bc = 0.0;
+            for &(c, v) in cells {
+                let q = s[c];
+                let prop = v / f;
+                if q > 0.0 {
+                    let expected = f * q;
+                    chi_square_stored += (v - expected).powi(2) / expected;
+                    s_stored += q;
+                }
+                sum_sqrt += (q * v).sqrt();
+                dp_stored += (prop - q).abs() - q;
+                if prop > 0.0 && q > 0.0 {
+                    kl += prop * (prop / q).ln() / LN_2;
+                }
+                let m = 0.5 * (prop + q);
+                if prop > 1e-12 && m > 1e-12 {
+                    kl_pm += prop * (prop / m).ln();
+                }
+                kl_qm += jsd_q_term(q, m) - partition.jsd_zero_terms()[c];
+                bc += (prop * q).sqrt();
+            }
+            let jsd = 0.5 * (kl_pm + kl_qm);
+            let hellinger_distance = (1.0 - bc.clamp(0.0, 1.0)).sqrt();
+            // Every unstored cell contributes its expected count f·s_i
+            let chi_square = f.mul_add(partition.sum_s() - s_stored, chi_square_stored);
+            (
+                1.0 - chi_square / (4.0 * f),
+                sum_sqrt * sum_sqrt,
+                0.5 * (partition.sum_s() + dp_stored),
+                kl,
+                1.0 - (jsd / LN_2).min(1.0),
+                1.0 - hellinger_distance,
+            )
+        };
+
+    let denom = 1.0 - partition.min_s();
+    let dp_norm = if denom.abs() < 1e-12 { 0.0 } else { dp / denom };
+
+    let pairwise =
+        MetricSet::GINI_COEFFICIENT | MetricSet::EVENNESS_DA | MetricSet::FT_ADJUSTED_BY_DA;
+    let sum_abs_diff = if f == 0.0 || n <= 1 || !selection.intersects(pairwise) {
+        0.0
+    } else {
+        let mut sorted = p.clone();
+        sorted.sort_by(f64::total_cmp);
+        // Stored values among themselves, plus each one against every zero cell
+        sum_abs_pairwise_diff_sorted(&sorted) + n_zero * sum_p
+    };
+    let gini_coefficient = if n <= 1 || sum_p.abs() < 1e-12 {
+        0.0
+    } else {
+        sum_abs_diff / (n_f * sum_p)
+    };
+
+    let evenness_da = if f == 0.0
+        || !selection.intersects(MetricSet::EVENNESS_DA | MetricSet::FT_ADJUSTED_BY_DA)
+    {
+        0.0
+    } else if n == 1 {
+        1.0
+    } else if mean_p.abs() < 1e-12 {
+        let all_same = p.iter().all(|&x| (x - mean_p).abs() < 1e-12);
+        if all_same { 1.0 } else { 0.0 }
+    } else {
+        let num_pairs = (n * (n - 1)) / 2;
+        let avg_abs_diff = sum_abs_diff / num_pairs as f64;
+        (1.0 - (avg_abs_diff / (2.0 * mean_p))).clamp(0.0, 1.0)
+    };
+
+    let pervasiveness_pt = f64::from(range) / n_f;
+
+    DispersionMetrics {
+        word: None,
+        range,
+        sd_population: Some(sd_population),
+        vc_population: Some(vc_population),
+        juilland_d: Some(juilland_d),
+        juilland_u: Some(f * juilland_d),
+        carroll_d2: Some(carroll_d2),
+        carroll_um: Some(f.mul_add(carroll_d2, (1.0 - carroll_d2) * f / n_f)),
+        lyne_d3: Some(lyne_d3),
+        roschengren_s_adj: Some(if f == 0.0 { 0.0 } else { roschengren_af / f }),
+        roschengren_af: Some(roschengren_af),
+        zhang_dc: Some(zhang_dc),
+        dp: Some(dp),
+        dp_norm: Some(dp_norm),
+        dp_nofreq: if selection.contains(MetricSet::DP_NOFREQ) {
+            dp_nofreq(partition, dp, f)
+        } else {
+            None
+        },
+        kl_divergence: Some(kl_divergence),
+        kl_divergence_norm: Some(1.0 - (-kl_divergence).exp2()),
+        jsd_dispersion: Some(jsd_dispersion),
+        hellinger_dispersion: Some(hellinger_dispersion),
+        gini_coefficient: Some(gini_coefficient),
+        shannon_entropy: Some(entropy),
+        pielou_evenness: Some(carroll_d2),
+        simpson_index: Some(simpson_index),
+        mean_text_frequency_ft: Some(mean_p),
+        pervasiveness_pt: Some(pervasiveness_pt),
+        evenness_da: Some(evenness_da),
+        ft_adjusted_by_pt: Some(mean_p * pervasiveness_pt),
+        ft_adjusted_by_da: Some(mean_p * evenness_da),
+    }
+    .masked(selection)
+}
+
+/// Σ_{i<j} |x_i − x_j| for ascending `sorted` values via the prefix-sum identity
+#[allow(clippy::cast_precision_loss)] // Acceptable for typical corpus sizes
+pub(crate) fn sum_abs_pairwise_diff_sorted(sorted: &[f64]) -> f64 {
+    let mut prefix = 0.0;
+    let mut total = 0.0;
+    for (j, &x) in sorted.iter().enumerate() {
+        total += x * j as f64 - prefix;
+        prefix += x;
+    }
+    total
+}
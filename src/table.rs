@@ -0,0 +1,498 @@
+//! Word × part frequency tables read from CSV/TSV files
+//!
+//! Each record holds a word in the first column followed by one frequency per
+//! corpus part. An optional header record names the parts, and an optional
+//! size row (identified by its label in the word column) gives the part sizes;
+//! without one, each part's size is the sum of its column. The file is read
+//! record by record into a flat row-major buffer that the batch engine reads
+//! in place. Tables counted from a corpus directory are mostly zeros and are
+//! kept in CSR form instead (see `FrequencyTable::from_csr`).
+
+use crate::analyzer::CorpusWordAnalyzer;
+use crate::error::{DispersionError, Result, validate_frequencies};
+use crate::matrix::{FrequencyMatrix, FrequencyRows, MatrixLayout};
+use crate::metrics::{LabelledMetrics, MetricSet};
+use crate::partition::CorpusPartition;
+use crate::sparse::CsrMatrix;
+use std::fs::File;
+use std::io::{BufRead, BufReader};
+use std::ops::Range;
+use std::path::Path;
+use std::sync::Arc;
+
+/// Layout of a frequency table file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TableConfig {
+    /// Field separator (default tab; use `b','` for CSV)
+    pub delimiter: u8,
+    /// Treat the first record as a header naming the parts (default `true`);
+    /// its first cell is ignored
+    pub has_header: bool,
+    /// Quote character (default `"`); quoted fields may contain the delimiter
+    /// and line breaks, and a doubled quote stands for a literal one. `None`
+    /// reads every character literally.
+    pub quote: Option<u8>,
+    /// Word-column label of the row holding the part sizes (e.g. `"__size__"`);
+    /// `None` infers each part size as its column sum
+    pub size_row: Option<String>,
+}
+
+impl Default for TableConfig {
+    fn default() -> Self {
+        Self {
+            delimiter: b'\t',
+            has_header: true,
+            quote: Some(b'"'),
+            size_row: None,
+        }
+    }
+}
+
+/// Frequencies of a vocabulary across the parts of a corpus
+#[derive(Debug, Clone)]
+pub struct FrequencyTable {
+    words: Vec<String>,
+    part_names: Vec<String>,
+    data: TableData,
+    partition: Arc<CorpusPartition>,
+}
+
+#[derive(Debug, Clone)]
+enum TableData {
+    /// Row-major `words.len()` × `n_parts` frequencies
+    Dense(Vec<f64>),
+    /// Stored cells of each row, in the layout of `CsrMatrix`
+    Sparse {
+        indptr: Vec<usize>,
+        indices: Vec<usize>,
+        values: Vec<f64>,
+    },
+}
+
+impl FrequencyTable {
+    /// Build a table from its parts
+    ///
+    /// `data` holds one row of `part_sizes.len()` frequencies per word, in
+    /// row-major order. Empty `part_names` are replaced by `part_1`, `part_2`, ….
+    ///
+    /// # Errors
+    ///
+    /// Returns `LengthMismatch` if `data` or `part_names` does not match the
+    /// number of words and parts, or any partition validation error for
+    /// `part_sizes` (which must sum to a positive total).
+    pub fn new(
+        words: Vec<String>,
+        part_names: Vec<String>,
+        data: Vec<f64>,
+        part_sizes: Vec<f64>,
+    ) -> Result<Self> {
+        let n_parts = part_sizes.len();
+        let part_names = if part_names.is_empty() {
+            (1..=n_parts).map(|part| format!("part_{part}")).collect()
+        } else {
+            part_names
+        };
+        if part_names.len() != n_parts {
+            return Err(DispersionError::LengthMismatch {
+                expected: n_parts,
+                found: part_names.len(),
+            });
+        }
+        FrequencyMatrix::new(&data, words.len(), n_parts, MatrixLayout::RowMajor)?;
+        Ok(Self {
+            words,
+            part_names,
+            data: TableData::Dense(data),
+            partition: Arc::new(CorpusPartition::from_part_sizes(part_sizes)?),
+        })
+    }
+
+    /// Build a table from CSR buffers, storing only the non-zero cells
+    ///
+    /// Row `i` (word `words[i]`) holds the values `values[indptr[i]..indptr[i + 1]]`
+    /// in the parts `indices[indptr[i]..indptr[i + 1]]`, as in `CsrMatrix`.
+    /// Empty `part_names` are replaced by `part_1`, `part_2`, ….
+    ///
+    /// # Errors
+    ///
+    /// Returns `LengthMismatch` if `part_names` does not match the number of
+    /// parts, `MalformedSparse` if the buffers are inconsistent with the
+    /// number of words and parts, or any partition validation error for
+    /// `part_sizes`.
+    pub fn from_csr(
+        words: Vec<String>,
+        part_names: Vec<String>,
+        indptr: Vec<usize>,
+        indices: Vec<usize>,
+        values: Vec<f64>,
+        part_sizes: Vec<f64>,
+    ) -> Result<Self> {
+        let n_parts = part_sizes.len();
+        let part_names = if part_names.is_empty() {
+            (1..=n_parts).map(|part| format!("part_{part}")).collect()
+        } else {
+            part_names
+        };
+        if part_names.len() != n_parts {
+            return Err(DispersionError::LengthMismatch {
+                expected: n_parts,
+                found: part_names.len(),
+            });
+        }
+        CsrMatrix::new(&indptr, &indices, &values, words.len(), n_parts)?;
+        Ok(Self {
+            words,
+            part_names,
+            data: TableData::Sparse {
+                indptr,
+                indices,
+                values,
+            },
+            partition: Arc::new(CorpusPartition::from_part_sizes(part_sizes)?),
+        })
+    }
+
+    /// Read a table from a file
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::Io` if the file cannot be opened or read,
+    /// and otherwise the same errors as `read`.
+    pub fn from_path(path: impl AsRef<Path>, config: &TableConfig) -> Result<Self> {
+        let file = File::open(path.as_ref())
+            .map_err(|e| DispersionError::Io(format!("{}: {e}", path.as_ref().display())))?;
+        Self::read(BufReader::new(file), config)
+    }
+
+    /// Read a table record by record
+    ///
+    /// Blank lines are skipped, a leading UTF-8 byte order mark is ignored and
+    /// both `\n` and `\r\n` line endings are accepted. Empty frequency cells
+    /// count as 0.
+    ///
+    /// # Errors
+    ///
+    /// Returns `MalformedTable` (with the 1-based line of the offending
+    /// record) for a record with the wrong number of fields, a cell that is
+    /// not a number, an unterminated quoted field, text after a closing quote,
+    /// invalid UTF-8 or a repeated size row; `EmptyInput` if there is no part
+    /// column; `Io` if reading fails; `NegativeFrequency` / `NonFiniteValue`
+    /// for an invalid frequency (wrapped in `DispersionError::Row` with the
+    /// word, before any part size is inferred from it); or a partition
+    /// validation error for the part sizes.
+    pub fn read<R: BufRead>(reader: R, config: &TableConfig) -> Result<Self> {
+        let mut records = Records::new(reader, config);
+        let mut part_names = Vec::new();
+        let mut n_parts = None;
+        if config.has_header
+            && let Some((_, header)) = records.next_record()?
+        {
+            part_names = header.into_iter().skip(1).collect();
+            n_parts = Some(part_names.len());
+        }
+
+        let mut words = Vec::new();
+        let mut data = Vec::new();
+        let mut part_sizes = None;
+        while let Some((line, mut record)) = records.next_record()? {
+            let expected = *n_parts.get_or_insert(record.len() - 1);
+            if record.len() != expected + 1 {
+                return Err(DispersionError::MalformedTable {
+                    line,
+                    reason: format!("expected {} fields, found {}", expected + 1, record.len()),
+                });
+            }
+            let word = std::mem::take(&mut record[0]);
+            let values = record[1..]
+                .iter()
+                .enumerate()
+                .map(|(column, cell)| parse_cell(cell, line, column + 2));
+            if config.size_row.as_deref() == Some(word.as_str()) {
+                if part_sizes.is_some() {
+                    return Err(DispersionError::MalformedTable {
+                        line,
+                        reason: format!("size row {word:?} appears more than once"),
+                    });
+                }
+                part_sizes = Some(values.collect::<Result<Vec<f64>>>()?);
+            } else {
+                for value in values {
+                    data.push(value?);
+                }
+                words.push(word);
+            }
+        }
+
+        let n_parts = n_parts.unwrap_or(0);
+        if n_parts == 0 {
+            return Err(DispersionError::EmptyInput);
+        }
+        for (index, row) in data.chunks_exact(n_parts).enumerate() {
+            validate_frequencies(row, n_parts).map_err(|e| e.in_row(index).with_words(&words))?;
+        }
+        let part_sizes = part_sizes.unwrap_or_else(|| {
+            let mut sums = vec![0.0; n_parts];
+            for row in data.chunks_exact(n_parts) {
+                for (sum, value) in sums.iter_mut().zip(row) {
+                    *sum += value;
+                }
+            }
+            sums
+        });
+        Self::new(words, part_names, data, part_sizes)
+    }
+
+    /// Word labels, one per row
+    #[must_use]
+    pub fn words(&self) -> &[String] {
+        &self.words
+    }
+
+    /// Part names from the header (`part_1`, `part_2`, … without one)
+    #[must_use]
+    pub fn part_names(&self) -> &[String] {
+        &self.part_names
+    }
+
+    /// Number of words (rows)
+    #[must_use]
+    pub fn n_words(&self) -> usize {
+        self.words.len()
+    }
+
+    /// Number of corpus parts (frequency columns)
+    #[must_use]
+    pub fn n_parts(&self) -> usize {
+        self.partition.n_parts()
+    }
+
+    /// Frequencies of the word in row `index` across the parts
+    #[must_use]
+    pub fn row(&self, index: usize) -> Option<Vec<f64>> {
+        (index < self.n_words()).then(|| self.rows().dense_row(index))
+    }
+
+    /// Corpus partition built from the part sizes
+    #[must_use]
+    pub fn partition(&self) -> &Arc<CorpusPartition> {
+        &self.partition
+    }
+
+    /// True if only the non-zero cells are stored (see `from_csr`)
+    #[must_use]
+    pub const fn is_sparse(&self) -> bool {
+        matches!(self.data, TableData::Sparse { .. })
+    }
+
+    /// Row-major `n_words` × `n_parts` frequencies (`None` for a sparse table)
+    #[must_use]
+    pub fn data(&self) -> Option<&[f64]> {
+        match &self.data {
+            TableData::Dense(data) => Some(data),
+            TableData::Sparse { .. } => None,
+        }
+    }
+
+    /// Borrow the frequencies as a row-major matrix (`None` for a sparse table)
+    #[must_use]
+    pub fn matrix(&self) -> Option<FrequencyMatrix<'_, f64>> {
+        match &self.data {
+            TableData::Dense(data) => Some(self.dense_matrix(data)),
+            TableData::Sparse { .. } => None,
+        }
+    }
+
+    /// Borrow the frequencies as a CSR matrix (`None` for a dense table)
+    #[must_use]
+    pub fn sparse_matrix(&self) -> Option<CsrMatrix<'_, f64>> {
+        match &self.data {
+            TableData::Dense(_) => None,
+            TableData::Sparse {
+                indptr,
+                indices,
+                values,
+            } => Some(self.csr_matrix(indptr, indices, values)),
+        }
+    }
+
+    /// Borrow the frequencies row by row, whichever way they are stored
+    #[must_use]
+    pub fn rows(&self) -> Box<dyn FrequencyRows + '_> {
+        match &self.data {
+            TableData::Dense(data) => Box::new(self.dense_matrix(data)),
+            TableData::Sparse {
+                indptr,
+                indices,
+                values,
+            } => Box::new(self.csr_matrix(indptr, indices, values)),
+        }
+    }
+
+    /// Calculate the selected metrics for every word in parallel
+    ///
+    /// # Errors
+    ///
+    /// Returns the first invalid row as `DispersionError::Row` carrying the
+    /// word label.
+    pub fn calculate_metrics(&self, selection: MetricSet) -> Result<LabelledMetrics> {
+        let metrics = match &self.data {
+            TableData::Dense(data) => CorpusWordAnalyzer::calculate_batch_metrics_matrix(
+                &self.dense_matrix(data),
+                &self.partition,
+                selection,
+            ),
+            TableData::Sparse {
+                indptr,
+                indices,
+                values,
+            } => CorpusWordAnalyzer::calculate_batch_metrics_sparse(
+                &self.csr_matrix(indptr, indices, values),
+                &self.partition,
+                selection,
+            ),
+        }
+        .map_err(|e| e.with_words(&self.words))?;
+        LabelledMetrics::new(self.words.clone(), metrics)
+    }
+}
+
+// Matrix views over the stored buffers, whose shapes were checked on construction
+impl FrequencyTable {
+    fn dense_matrix<'a>(&self, data: &'a [f64]) -> FrequencyMatrix<'a, f64> {
+        FrequencyMatrix::new(data, self.n_words(), self.n_parts(), MatrixLayout::RowMajor)
+            .expect("table buffer holds one row per word")
+    }
+
+    fn csr_matrix<'a>(
+        &self,
+        indptr: &'a [usize],
+        indices: &'a [usize],
+        values: &'a [f64],
+    ) -> CsrMatrix<'a, f64> {
+        CsrMatrix::new(indptr, indices, values, self.n_words(), self.n_parts())
+            .expect("table buffers were checked when the table was built")
+    }
+}
+
+fn parse_cell(cell: &str, line: usize, column: usize) -> Result<f64> {
+    let cell = cell.trim();
+    if cell.is_empty() {
+        return Ok(0.0);
+    }
+    cell.parse().map_err(|_| DispersionError::MalformedTable {
+        line,
+        reason: format!("cannot read {cell:?} in column {column} as a number"),
+    })
+}
+
+/// Delimited records read one physical line at a time
+struct Records<R> {
+    reader: R,
+    delimiter: u8,
+    quote: Option<u8>,
+    line: usize,
+    buffer: Vec<u8>,
+}
+
+impl<R: BufRead> Records<R> {
+    fn new(reader: R, config: &TableConfig) -> Self {
+        Self {
+            reader,
+            delimiter: config.delimiter,
+            quote: config.quote,
+            line: 0,
+            buffer: Vec::new(),
+        }
+    }
+
+    // Byte range of the next physical line in `buffer` without its line
+    // ending, or `None` at end of input
+    fn next_line(&mut self) -> Result<Option<Range<usize>>> {
+        self.buffer.clear();
+        let read = self
+            .reader
+            .read_until(b'\n', &mut self.buffer)
+            .map_err(|e| DispersionError::Io(e.to_string()))?;
+        if read == 0 {
+            return Ok(None);
+        }
+        self.line += 1;
+        let mut range = 0..self.buffer.len();
+        if self.line == 1 && self.buffer.starts_with(b"\xEF\xBB\xBF") {
+            range.start = 3;
+        }
+        for ending in [b'\n', b'\r'] {
+            if range.end > range.start && self.buffer[range.end - 1] == ending {
+                range.end -= 1;
+            }
+        }
+        Ok(Some(range))
+    }
+
+    /// Next non-blank record and the line it starts on
+    fn next_record(&mut self) -> Result<Option<(usize, Vec<String>)>> {
+        let (delimiter, quote) = (self.delimiter, self.quote);
+        let mut fields = Vec::new();
+        let mut field = Vec::new();
+        let mut in_quotes = false;
+        let mut after_quote = false;
+        let mut start = 0;
+        loop {
+            let Some(range) = self.next_line()? else {
+                if in_quotes {
+                    return Err(DispersionError::MalformedTable {
+                        line: start,
+                        reason: "unterminated quoted field".to_string(),
+                    });
+                }
+                return Ok(None);
+            };
+            let line = &self.buffer[range];
+            if in_quotes {
+                // The line break belongs to the quoted field
+                field.push(b'\n');
+            } else if line.is_empty() {
+                continue;
+            } else {
+                start = self.line;
+            }
+
+            let mut bytes = line.iter().copied().peekable();
+            while let Some(byte) = bytes.next() {
+                if in_quotes {
+                    // A doubled quote is a literal quote character
+                    if Some(byte) != quote || bytes.next_if_eq(&byte).is_some() {
+                        field.push(byte);
+                    } else {
+                        in_quotes = false;
+                        after_quote = true;
+                    }
+                } else if byte == delimiter {
+                    fields.push(std::mem::take(&mut field));
+                    after_quote = false;
+                } else if after_quote {
+                    return Err(DispersionError::MalformedTable {
+                        line: self.line,
+                        reason: "unexpected text after a closing quote".to_string(),
+                    });
+                } else if Some(byte) == quote && field.is_empty() {
+                    in_quotes = true;
+                } else {
+                    field.push(byte);
+                }
+            }
+            if !in_quotes {
+                fields.push(field);
+                let record = fields
+                    .into_iter()
+                    .map(String::from_utf8)
+                    .collect::<std::result::Result<_, _>>()
+                    .map_err(|_| DispersionError::MalformedTable {
+                        line: start,
+                        reason: "record is not valid UTF-8".to_string(),
+                    })?;
+                return Ok(Some((start, record)));
+            }
+        }
+    }
+}
@@ -0,0 +1,203 @@
+//! Ranked word lists by lexical prevalence
+//!
+//! Egbert & Burch (2023) rank a vocabulary by frequency adjusted for
+//! dispersion: the mean text frequency FT multiplied by the evenness DA or by
+//! the pervasiveness PT. Any other metric can be used as the score instead.
+//! Words with equal scores are ordered by an explicit `TieBreak` rule, so the
+//! ranking is fully determined by the input.
+
+use crate::error::{DispersionError, Result, validate_frequencies};
+use crate::metrics::{DispersionMetrics, MetricSet};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// Order of words whose scores are equal
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TieBreak {
+    /// Higher total frequency first, then alphabetical order of the words
+    #[default]
+    Frequency,
+    /// Alphabetical order of the words
+    Word,
+    /// Order of the input rows
+    Input,
+}
+
+impl TieBreak {
+    /// Parse a rule by name: `"frequency"`, `"word"` or `"input"`
+    #[must_use]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "frequency" => Some(Self::Frequency),
+            "word" => Some(Self::Word),
+            "input" => Some(Self::Input),
+            _ => None,
+        }
+    }
+}
+
+/// Settings of a ranked word list
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordListConfig {
+    /// Metric to rank by (default `ft_adjusted_by_da`, FT × DA; use
+    /// `ft_adjusted_by_pt` for FT × PT)
+    pub score: String,
+    /// Rank larger scores first (default); set to `false` for metrics such as
+    /// DP where smaller values mean more evenly dispersed
+    pub descending: bool,
+    /// Order of words with equal scores
+    pub tie_break: TieBreak,
+    /// Give words with equal scores the same rank ("1224" competition ranking)
+    /// instead of consecutive ranks
+    pub shared_ranks: bool,
+}
+
+impl Default for WordListConfig {
+    fn default() -> Self {
+        Self {
+            score: "ft_adjusted_by_da".to_string(),
+            descending: true,
+            tie_break: TieBreak::Frequency,
+            shared_ranks: false,
+        }
+    }
+}
+
+impl WordListConfig {
+    /// Metrics needed for the score and the reported components
+    ///
+    /// # Errors
+    ///
+    /// Returns `DispersionError::UnknownMetric` if `score` is not a metric name.
+    pub fn selection(&self) -> Result<MetricSet> {
+        Ok(MetricSet::from_names([self.score.as_str()])?
+            | MetricSet::MEAN_TEXT_FREQUENCY_FT
+            | MetricSet::PERVASIVENESS_PT
+            | MetricSet::EVENNESS_DA)
+    }
+}
+
+/// One row of a ranked word list
+#[cfg_attr(feature = "python", pyo3::pyclass(get_all))]
+#[derive(Debug, Clone, PartialEq)]
+pub struct WordListEntry {
+    /// 1-based rank (`None` if the score was not computed or is NaN)
+    pub rank: Option<usize>,
+    pub word: String,
+    /// Value of the ranking metric
+    pub score: Option<f64>,
+    /// Total frequency of the word
+    pub frequency: f64,
+    /// Mean text frequency FT
+    pub mean_text_frequency_ft: Option<f64>,
+    /// Pervasiveness PT
+    pub pervasiveness_pt: Option<f64>,
+    /// Evenness DA
+    pub evenness_da: Option<f64>,
+}
+
+impl fmt::Display for WordListEntry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let fmt_opt =
+            |value: Option<f64>| value.map_or_else(|| "None".to_string(), |v| format!("{v:.4}"));
+        write!(
+            f,
+            "WordListEntry(rank={}, word={:?}, score={}, frequency={})",
+            self.rank
+                .map_or_else(|| "None".to_string(), |rank| rank.to_string()),
+            self.word,
+            fmt_opt(self.score),
+            self.frequency
+        )
+    }
+}
+
+/// Rank words by a metric that has already been computed
+///
+/// `metrics[i]` and `frequencies[i]` belong to `words[i]`. The entries are
+/// returned in rank order; words without a score follow the ranked ones in
+/// tie-break order.
+///
+/// # Errors
+///
+/// Returns `LabelCountMismatch` if `words` and `metrics` differ in length,
+/// `LengthMismatch` if `frequencies` does, `NegativeFrequency` /
+/// `NonFiniteValue` for an invalid frequency, or `UnknownMetric` if the score
+/// is not a metric name.
+pub fn build_word_list(
+    words: Vec<String>,
+    metrics: &[DispersionMetrics],
+    frequencies: &[f64],
+    config: &WordListConfig,
+) -> Result<Vec<WordListEntry>> {
+    if words.len() != metrics.len() {
+        return Err(DispersionError::LabelCountMismatch {
+            expected: metrics.len(),
+            found: words.len(),
+        });
+    }
+    validate_frequencies(frequencies, metrics.len())?;
+    // Validate the score name before looking it up per word
+    config.selection()?;
+
+    let mut entries: Vec<WordListEntry> = words
+        .into_iter()
+        .zip(metrics)
+        .zip(frequencies)
+        .map(|((word, m), &frequency)| {
+            Ok(WordListEntry {
+                rank: None,
+                word,
+                score: m.get(&config.score)?.filter(|v| !v.is_nan()),
+                frequency,
+                mean_text_frequency_ft: m.mean_text_frequency_ft,
+                pervasiveness_pt: m.pervasiveness_pt,
+                evenness_da: m.evenness_da,
+            })
+        })
+        .collect::<Result<_>>()?;
+
+    // A stable sort keeps the input order for `TieBreak::Input`
+    entries.sort_by(|a, b| {
+        compare_scores(a.score, b.score, config.descending)
+            .then_with(|| break_tie(a, b, config.tie_break))
+    });
+
+    let mut previous: Option<(f64, usize)> = None;
+    for (position, entry) in entries.iter_mut().enumerate() {
+        let Some(score) = entry.score else { break };
+        let rank = match previous {
+            Some((previous_score, previous_rank))
+                if config.shared_ranks && previous_score.total_cmp(&score).is_eq() =>
+            {
+                previous_rank
+            }
+            _ => position + 1,
+        };
+        entry.rank = Some(rank);
+        previous = Some((score, rank));
+    }
+    Ok(entries)
+}
+
+// Scored words before unscored ones, then by score in the requested direction
+fn compare_scores(a: Option<f64>, b: Option<f64>, descending: bool) -> Ordering {
+    match (a, b) {
+        (Some(a), Some(b)) if descending => b.total_cmp(&a),
+        (Some(a), Some(b)) => a.total_cmp(&b),
+        (Some(_), None) => Ordering::Less,
+        (None, Some(_)) => Ordering::Greater,
+        (None, None) => Ordering::Equal,
+    }
+}
+
+fn break_tie(a: &WordListEntry, b: &WordListEntry, rule: TieBreak) -> Ordering {
+    match rule {
+        TieBreak::Frequency => b
+            .frequency
+            .total_cmp(&a.frequency)
+            .then_with(|| a.word.cmp(&b.word)),
+        TieBreak::Word => a.word.cmp(&b.word),
+        TieBreak::Input => Ordering::Equal,
+    }
+}
@@ -4,6 +4,7 @@
 //! and test the public API from an end-user perspective.
 
 use corpus_dispersion::*;
+use std::sync::Arc;
 
 mod common;
 use common::*;
@@ -44,6 +45,54 @@ fn test_new_analyzer_invalid_total() {
     assert!(analyzer.is_err());
 }
 
+#[test]
+fn test_new_analyzer_error_kinds() {
+    let err = CorpusWordAnalyzer::new(vec![1.0, 2.0], vec![10.0], 10.0).unwrap_err();
+    assert_eq!(
+        err,
+        DispersionError::LengthMismatch {
+            expected: 1,
+            found: 2
+        }
+    );
+
+    let err = CorpusWordAnalyzer::new(vec![], vec![], 30.0).unwrap_err();
+    assert_eq!(err, DispersionError::EmptyInput);
+
+    let err = CorpusWordAnalyzer::new(vec![1.0], vec![10.0], 0.0).unwrap_err();
+    assert_eq!(err, DispersionError::NonPositiveTotal(0.0));
+}
+
+#[test]
+fn test_new_analyzer_invalid_values() {
+    let err = CorpusWordAnalyzer::new(vec![1.0, -2.0], vec![10.0, 10.0], 20.0).unwrap_err();
+    assert_eq!(
+        err,
+        DispersionError::NegativeFrequency {
+            index: 1,
+            value: -2.0
+        }
+    );
+
+    let err = CorpusWordAnalyzer::new(vec![1.0, 2.0], vec![10.0, -10.0], 20.0).unwrap_err();
+    assert!(matches!(
+        err,
+        DispersionError::NegativePartSize { index: 1, .. }
+    ));
+
+    let err = CorpusWordAnalyzer::new(vec![f64::NAN, 2.0], vec![10.0, 10.0], 20.0).unwrap_err();
+    assert!(matches!(
+        err,
+        DispersionError::NonFiniteValue { index: Some(0), .. }
+    ));
+
+    let err = CorpusWordAnalyzer::new(vec![1.0], vec![10.0], f64::INFINITY).unwrap_err();
+    assert!(matches!(
+        err,
+        DispersionError::NonFiniteValue { index: None, .. }
+    ));
+}
+
 #[test]
 fn test_get_range() {
     let v = vec![1.0, 0.0, 2.0];
@@ -392,6 +441,275 @@ fn test_calculate_batch_metrics_invalid_length() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_calculate_batch_metrics_reports_row() {
+    let frequency_matrix = vec![vec![2.0, 3.0, 5.0], vec![1.0, -2.0, 3.0]];
+    let corpus_part_sizes = vec![10.0, 10.0, 10.0];
+
+    let err =
+        CorpusWordAnalyzer::calculate_batch_metrics(frequency_matrix, corpus_part_sizes, 30.0)
+            .unwrap_err();
+
+    assert_eq!(err.row(), Some(1));
+    assert!(matches!(
+        err.kind(),
+        DispersionError::NegativeFrequency { index: 1, .. }
+    ));
+    assert!(err.to_string().starts_with("Row 1:"));
+}
+
+#[test]
+fn test_calculate_batch_metrics_labelled() {
+    let partition = Arc::new(CorpusPartition::new(vec![10.0, 10.0, 10.0], 30.0).unwrap());
+    let words: Vec<String> = ["apple", "pear", "apple"]
+        .iter()
+        .map(ToString::to_string)
+        .collect();
+    let matrix = vec![vec![2.0, 3.0, 5.0], vec![4.0, 0.0, 0.0], vec![1.0; 3]];
+
+    let results = CorpusWordAnalyzer::calculate_batch_metrics_labelled(
+        words.clone(),
+        matrix.clone(),
+        &partition,
+        MetricSet::DP,
+    )
+    .unwrap();
+    let plain = CorpusWordAnalyzer::calculate_batch_metrics_with_partition(
+        matrix,
+        &partition,
+        MetricSet::DP,
+    )
+    .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results.get("pear").unwrap().dp, plain[1].dp);
+    assert_eq!(results.get("pear").unwrap().word.as_deref(), Some("pear"));
+    // A repeated word resolves to its first row
+    assert_eq!(results.position("apple"), Some(0));
+    assert_eq!(results.as_slice()[2].word.as_deref(), Some("apple"));
+    assert!(results.get("plum").is_none());
+    assert!(
+        results.as_slice()[1]
+            .to_string()
+            .starts_with("DispersionMetrics(word=\"pear\", range=1")
+    );
+
+    // Row errors name the word
+    let err = CorpusWordAnalyzer::calculate_batch_metrics_labelled(
+        words.clone(),
+        vec![vec![1.0; 3], vec![1.0, f64::NAN, 0.0], vec![1.0; 3]],
+        &partition,
+        MetricSet::ALL,
+    )
+    .unwrap_err();
+    assert_eq!(err.row(), Some(1));
+    assert_eq!(err.word(), Some("pear"));
+    assert!(err.to_string().starts_with("Row 1 (\"pear\"):"));
+
+    let err = CorpusWordAnalyzer::calculate_batch_metrics_labelled(
+        words,
+        vec![vec![1.0; 3]],
+        &partition,
+        MetricSet::ALL,
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        DispersionError::LabelCountMismatch {
+            expected: 1,
+            found: 3
+        }
+    );
+    assert!(err.word().is_none());
+}
+
+#[test]
+fn test_corpus_partition_precomputed_values() {
+    let partition = CorpusPartition::new(vec![10.0, 30.0, 60.0], 100.0).unwrap();
+    assert_eq!(partition.n_parts(), 3);
+    assert_eq!(partition.s(), &[0.1, 0.3, 0.6]);
+    assert_approx_eq(partition.min_s(), 0.1, 1e-12, "min_s");
+    assert_approx_eq(partition.sum_s(), 1.0, 1e-12, "sum_s");
+    assert_approx_eq(partition.ln_s()[2], 0.6_f64.ln(), 1e-12, "ln_s");
+    assert_approx_eq(partition.sqrt_s()[1], 0.3_f64.sqrt(), 1e-12, "sqrt_s");
+
+    let from_sizes = CorpusPartition::from_part_sizes(vec![10.0, 30.0, 60.0]).unwrap();
+    assert_approx_eq(from_sizes.total_corpus_words(), 100.0, 1e-12, "total");
+
+    assert_eq!(
+        CorpusPartition::new(vec![], 1.0).unwrap_err(),
+        DispersionError::EmptyInput
+    );
+}
+
+#[test]
+fn test_analyzer_with_shared_partition() {
+    let partition = Arc::new(CorpusPartition::new(vec![10.0, 10.0, 10.0], 30.0).unwrap());
+    let mut shared =
+        CorpusWordAnalyzer::with_partition(vec![2.0, 3.0, 5.0], Arc::clone(&partition)).unwrap();
+    let mut owned = create_basic_analyzer();
+
+    assert!(Arc::ptr_eq(shared.get_partition(), &partition));
+    assert_eq!(shared.get_juilland_d(), owned.get_juilland_d());
+    assert_eq!(shared.get_dp(), owned.get_dp());
+
+    let err = CorpusWordAnalyzer::with_partition(vec![1.0], partition).unwrap_err();
+    assert!(matches!(err, DispersionError::LengthMismatch { .. }));
+}
+
+#[test]
+fn test_calculate_batch_metrics_with_partition() {
+    let partition = Arc::new(CorpusPartition::new(vec![10.0, 10.0, 10.0], 30.0).unwrap());
+    let rows = vec![vec![2.0, 3.0, 5.0], vec![1.0, 2.0, 3.0]];
+
+    let shared = CorpusWordAnalyzer::calculate_batch_metrics_with_partition(
+        rows.clone(),
+        &partition,
+        MetricSet::ALL,
+    )
+    .unwrap();
+    let owned =
+        CorpusWordAnalyzer::calculate_batch_metrics(rows, vec![10.0, 10.0, 10.0], 30.0).unwrap();
+
+    assert_eq!(shared.len(), 2);
+    for (a, b) in shared.iter().zip(&owned) {
+        assert_eq!(a.dp, b.dp);
+        assert_eq!(a.evenness_da, b.evenness_da);
+    }
+}
+
+#[test]
+fn test_calculate_batch_metrics_matrix_layouts() {
+    let rows = vec![vec![2.0, 3.0, 5.0], vec![4.0, 0.0, 1.0]];
+    let sizes = vec![10.0, 10.0, 10.0];
+    let expected = CorpusWordAnalyzer::calculate_batch_metrics(rows, sizes.clone(), 30.0).unwrap();
+
+    let row_major = [2.0, 3.0, 5.0, 4.0, 0.0, 1.0];
+    let col_major = [2_i64, 4, 3, 0, 5, 1];
+    let row_major_f32 = [2.0_f32, 3.0, 5.0, 4.0, 0.0, 1.0];
+    let partition = Arc::new(CorpusPartition::new(sizes, 30.0).unwrap());
+
+    let m = FrequencyMatrix::new(&row_major, 2, 3, MatrixLayout::RowMajor).unwrap();
+    let by_rows =
+        CorpusWordAnalyzer::calculate_batch_metrics_matrix(&m, &partition, MetricSet::ALL).unwrap();
+    let m = FrequencyMatrix::new(&col_major, 2, 3, MatrixLayout::ColumnMajor).unwrap();
+    let by_cols =
+        CorpusWordAnalyzer::calculate_batch_metrics_matrix(&m, &partition, MetricSet::ALL).unwrap();
+    let m = FrequencyMatrix::new(&row_major_f32, 2, 3, MatrixLayout::RowMajor).unwrap();
+    let by_f32 =
+        CorpusWordAnalyzer::calculate_batch_metrics_matrix(&m, &partition, MetricSet::ALL).unwrap();
+
+    for results in [&by_rows, &by_cols, &by_f32] {
+        assert_eq!(results.len(), expected.len());
+        for (actual, exp) in results.iter().zip(&expected) {
+            assert_eq!(actual.range, exp.range);
+            assert_eq!(actual.dp, exp.dp);
+            assert_eq!(actual.juilland_d, exp.juilland_d);
+        }
+    }
+}
+
+#[test]
+fn test_calculate_batch_metrics_matrix_invalid() {
+    let partition = Arc::new(CorpusPartition::new(vec![10.0, 10.0, 10.0], 30.0).unwrap());
+
+    let err = FrequencyMatrix::new(&[1.0, 2.0, 3.0], 2, 3, MatrixLayout::RowMajor).unwrap_err();
+    assert!(matches!(err, DispersionError::LengthMismatch { .. }));
+
+    let data = [1.0, 2.0, 3.0, 4.0];
+    let m = FrequencyMatrix::new(&data, 2, 2, MatrixLayout::RowMajor).unwrap();
+    let err = CorpusWordAnalyzer::calculate_batch_metrics_matrix(&m, &partition, MetricSet::ALL)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        DispersionError::LengthMismatch {
+            expected: 3,
+            found: 2
+        }
+    ));
+
+    let data = [1.0, 2.0, 3.0, 1.0, f64::NAN, 1.0];
+    let m = FrequencyMatrix::new(&data, 2, 3, MatrixLayout::RowMajor).unwrap();
+    let err = CorpusWordAnalyzer::calculate_batch_metrics_matrix(&m, &partition, MetricSet::ALL)
+        .unwrap_err();
+    assert_eq!(err.row(), Some(1));
+}
+
+#[test]
+fn test_calculate_batch_metrics_sparse_matches_dense() {
+    let rows = vec![
+        vec![2.0, 0.0, 5.0, 0.0, 1.0],
+        vec![0.0, 0.0, 0.0, 0.0, 0.0],
+        vec![0.0, 7.0, 0.0, 0.0, 0.0],
+        vec![1.0, 1.0, 1.0, 1.0, 1.0],
+        vec![0.0, 3.0, 0.0, 9.0, 0.0],
+    ];
+    let sizes = vec![10.0, 20.0, 5.0, 40.0, 25.0];
+    let dense =
+        CorpusWordAnalyzer::calculate_batch_metrics(rows.clone(), sizes.clone(), 100.0).unwrap();
+
+    let mut indptr = vec![0_usize];
+    let mut indices = Vec::new();
+    let mut data = Vec::new();
+    for row in &rows {
+        for (col, &value) in row.iter().enumerate() {
+            if value != 0.0 {
+                indices.push(col);
+                data.push(value);
+            }
+        }
+        indptr.push(indices.len());
+    }
+    let csr = CsrMatrix::new(&indptr, &indices, &data, rows.len(), sizes.len()).unwrap();
+    let partition = Arc::new(CorpusPartition::new(sizes, 100.0).unwrap());
+    let sparse =
+        CorpusWordAnalyzer::calculate_batch_metrics_sparse(&csr, &partition, MetricSet::ALL)
+            .unwrap();
+
+    assert_eq!(sparse.len(), dense.len());
+    for (i, (s, d)) in sparse.iter().zip(&dense).enumerate() {
+        assert_eq!(s.range, d.range, "range, row {i}");
+        for ((name, sv), dv) in DispersionMetrics::FLOAT_FIELDS
+            .iter()
+            .zip(s.float_values())
+            .zip(d.float_values())
+        {
+            assert_approx_eq(sv.unwrap(), dv.unwrap(), 1e-9, &format!("{name}, row {i}"));
+        }
+    }
+}
+
+#[test]
+fn test_csr_matrix_malformed() {
+    let data = [1.0, 2.0];
+    let err = CsrMatrix::new(&[0_usize, 2], &[1, 1], &data, 1, 3).unwrap_err();
+    assert_eq!(err.row(), Some(0));
+    assert!(matches!(err.kind(), DispersionError::MalformedSparse(_)));
+
+    let err = CsrMatrix::new(&[0_i32, 1, 2], &[0, 5], &data, 2, 3).unwrap_err();
+    assert_eq!(err.row(), Some(1));
+
+    let err = CsrMatrix::new(&[0_i64, 2], &[0, 1], &data, 2, 3).unwrap_err();
+    assert!(matches!(err, DispersionError::MalformedSparse(_)));
+}
+
+#[test]
+fn test_metric_columns_from_batch() {
+    let frequency_matrix = vec![vec![2.0, 3.0, 5.0], vec![0.0, 0.0, 0.0]];
+    let metrics =
+        CorpusWordAnalyzer::calculate_batch_metrics(frequency_matrix, vec![10.0; 3], 30.0).unwrap();
+
+    let columns = MetricColumns::from_metrics(&metrics);
+    assert_eq!(columns.len(), 2);
+    assert_eq!(columns.range, vec![3, 0]);
+    assert_eq!(columns.columns.len(), DispersionMetrics::FLOAT_FIELDS.len());
+
+    let dp = columns.get("dp").unwrap();
+    assert_eq!(dp[0], metrics[0].dp.unwrap());
+    assert_eq!(dp[1], 0.0);
+    assert!(columns.get("range").is_none());
+    assert!(columns.get("unknown").is_none());
+}
+
 #[test]
 fn test_calculate_single_metric_juilland_d() {
     let frequency_vector = vec![2.0, 3.0, 5.0];
@@ -424,7 +742,10 @@ fn test_calculate_single_metric_unknown() {
         "unknown_metric",
     );
 
-    assert!(result.is_err());
+    assert_eq!(
+        result.unwrap_err(),
+        DispersionError::UnknownMetric("unknown_metric".to_string())
+    );
 }
 
 #[test]
@@ -435,12 +756,23 @@ fn test_all_supported_single_metrics() {
 
     let metrics = [
         "juilland_d",
+        "juilland_u",
         "carroll_d2",
+        "carroll_um",
+        "lyne_d3",
+        "roschengren_af",
+        "zhang_dc",
         "dp",
         "dp_norm",
+        "dp_nofreq",
         "kl_divergence",
+        "kl_divergence_norm",
         "jsd_dispersion",
         "hellinger_dispersion",
+        "gini_coefficient",
+        "shannon_entropy",
+        "pielou_evenness",
+        "simpson_index",
         "evenness_da",
         "mean_text_frequency_ft",
         "pervasiveness_pt",
@@ -461,17 +793,29 @@ fn test_all_supported_single_metrics() {
 #[test]
 fn test_dispersion_metrics_repr() {
     let metrics = DispersionMetrics {
+        word: None,
         range: 3,
         sd_population: Some(1.5),
         vc_population: Some(0.5),
         juilland_d: Some(0.8),
+        juilland_u: Some(8.0),
         carroll_d2: Some(0.9),
+        carroll_um: Some(9.0),
+        lyne_d3: Some(0.95),
         roschengren_s_adj: Some(0.7),
+        roschengren_af: Some(7.0),
+        zhang_dc: Some(0.9),
         dp: Some(0.2),
         dp_norm: Some(0.3),
+        dp_nofreq: Some(0.4),
         kl_divergence: Some(0.1),
+        kl_divergence_norm: Some(0.07),
         jsd_dispersion: Some(0.95),
         hellinger_dispersion: Some(0.85),
+        gini_coefficient: Some(0.2),
+        shannon_entropy: Some(1.03),
+        pielou_evenness: Some(0.94),
+        simpson_index: Some(0.38),
         mean_text_frequency_ft: Some(0.33),
         pervasiveness_pt: Some(1.0),
         evenness_da: Some(0.75),
@@ -484,3 +828,1244 @@ fn test_dispersion_metrics_repr() {
     assert_eq!(metrics.juilland_d, Some(0.8));
     assert_eq!(metrics.carroll_d2, Some(0.9));
 }
+
+#[test]
+fn test_calculate_metrics_subset() {
+    let mut analyzer = create_basic_analyzer();
+    let full = create_basic_analyzer().calculate_all_metrics();
+
+    let selection = MetricSet::from_names(["range", "dp", "ft_adjusted_by_pt"]).unwrap();
+    assert_eq!(selection, MetricSet::DP | MetricSet::FT_ADJUSTED_BY_PT);
+    let subset = analyzer.calculate_metrics(selection);
+
+    assert_eq!(subset.range, full.range);
+    assert_eq!(subset.dp, full.dp);
+    assert_eq!(subset.ft_adjusted_by_pt, full.ft_adjusted_by_pt);
+    assert!(subset.evenness_da.is_none());
+    assert!(subset.mean_text_frequency_ft.is_none());
+    assert!(subset.pervasiveness_pt.is_none());
+    assert!(subset.dp_norm.is_none());
+
+    assert_eq!(
+        MetricSet::from_names(["dp", "bogus"]).unwrap_err(),
+        DispersionError::UnknownMetric("bogus".to_string())
+    );
+    assert_eq!(
+        MetricSet::from_names(DispersionMetrics::FLOAT_FIELDS).unwrap(),
+        MetricSet::ALL
+    );
+}
+
+#[test]
+fn test_batch_metrics_subset_dense_and_sparse() {
+    let partition = Arc::new(CorpusPartition::new(vec![10.0, 20.0, 30.0], 60.0).unwrap());
+    let data = [2.0, 0.0, 5.0, 0.0, 4.0, 0.0];
+    let m = FrequencyMatrix::new(&data, 2, 3, MatrixLayout::RowMajor).unwrap();
+    let csr = CsrMatrix::new(&[0_usize, 2, 3], &[0, 2, 1], &[2.0, 5.0, 4.0], 2, 3).unwrap();
+    let selection = MetricSet::DP | MetricSet::EVENNESS_DA;
+
+    let full =
+        CorpusWordAnalyzer::calculate_batch_metrics_matrix(&m, &partition, MetricSet::ALL).unwrap();
+    let dense =
+        CorpusWordAnalyzer::calculate_batch_metrics_matrix(&m, &partition, selection).unwrap();
+    let sparse =
+        CorpusWordAnalyzer::calculate_batch_metrics_sparse(&csr, &partition, selection).unwrap();
+
+    for ((d, s), f) in dense.iter().zip(&sparse).zip(&full) {
+        assert_eq!(d.range, f.range);
+        assert_eq!(d.dp, f.dp);
+        assert_eq!(d.evenness_da, f.evenness_da);
+        assert_approx_eq(s.dp.unwrap(), f.dp.unwrap(), 1e-12, "sparse dp");
+        assert_approx_eq(
+            s.evenness_da.unwrap(),
+            f.evenness_da.unwrap(),
+            1e-12,
+            "sparse da",
+        );
+        for metrics in [d, s] {
+            let selected = metrics
+                .float_values()
+                .iter()
+                .filter(|v| v.is_some())
+                .count();
+            assert_eq!(selected, 2);
+        }
+    }
+
+    let columns = MetricColumns::from_selected(&dense, selection);
+    let names: Vec<&str> = columns.columns.iter().map(|(name, _)| *name).collect();
+    assert_eq!(names, vec!["dp", "evenness_da"]);
+    assert_eq!(columns.get("dp").unwrap().len(), 2);
+}
+
+#[test]
+fn test_parallelism_pools() {
+    let rows: Vec<Vec<f64>> = (0..50)
+        .map(|i| vec![f64::from(i % 7), f64::from(i % 3), 1.0, 0.0])
+        .collect();
+    let partition = Arc::new(CorpusPartition::new(vec![10.0, 20.0, 30.0, 40.0], 100.0).unwrap());
+    let batch = || {
+        CorpusWordAnalyzer::calculate_batch_metrics_with_partition(
+            rows.clone(),
+            &partition,
+            MetricSet::ALL,
+        )
+        .unwrap()
+    };
+    let reference = batch();
+
+    assert_eq!(Parallelism::Sequential.num_threads().unwrap(), 1);
+    assert_eq!(Parallelism::Threads(3).num_threads().unwrap(), 3);
+    assert_eq!(
+        Parallelism::Threads(3)
+            .install(rayon::current_num_threads)
+            .unwrap(),
+        3
+    );
+
+    for parallelism in [
+        Parallelism::Sequential,
+        Parallelism::Threads(2),
+        Parallelism::from_n_threads(None),
+    ] {
+        let results = parallelism.install(batch).unwrap();
+        assert_eq!(results.len(), reference.len());
+        for (a, b) in results.iter().zip(&reference) {
+            assert_eq!(a.to_string(), b.to_string());
+        }
+    }
+
+    // Sizes evicted from the pool cache are rebuilt on demand
+    for n_threads in (2..=9).chain(2..=3) {
+        assert_eq!(
+            Parallelism::Threads(n_threads)
+                .install(rayon::current_num_threads)
+                .unwrap(),
+            n_threads
+        );
+    }
+}
+
+#[test]
+fn test_set_default_parallelism() {
+    set_default_parallelism(&Parallelism::Threads(2)).unwrap();
+    assert_eq!(Parallelism::Default.num_threads().unwrap(), 2);
+    let metrics =
+        CorpusWordAnalyzer::calculate_batch_metrics(vec![vec![2.0, 3.0, 5.0]], vec![10.0; 3], 30.0)
+            .unwrap();
+    assert_eq!(metrics.len(), 1);
+
+    set_default_parallelism(&Parallelism::Default).unwrap();
+    assert_eq!(
+        Parallelism::Default.num_threads().unwrap(),
+        rayon::current_num_threads()
+    );
+}
+
+#[test]
+fn test_theoretical_bounds() {
+    let mut analyzer = CorpusWordAnalyzer::new(vec![3.0, 1.0], vec![50.0, 50.0], 100.0).unwrap();
+    let bounds = analyzer.get_theoretical_bounds();
+
+    assert_eq!(bounds.most_even, vec![2.0, 2.0]);
+    assert_eq!(bounds.most_clumped, vec![4.0, 0.0]);
+    assert_eq!(bounds.even_metrics.range, 2);
+    assert_eq!(bounds.clumped_metrics.range, 1);
+    assert_approx_eq(bounds.even_metrics.dp.unwrap(), 0.0, 1e-12, "even dp");
+    assert_approx_eq(bounds.clumped_metrics.dp.unwrap(), 0.5, 1e-12, "clumped dp");
+
+    // Same orientation for every metric: 0 at the most even, 1 at the most clumped
+    let even_d = bounds.even_metrics.juilland_d.unwrap();
+    let clumped_d = bounds.clumped_metrics.juilland_d.unwrap();
+    assert!(even_d > clumped_d);
+    assert_eq!(bounds.normalize("juilland_d", even_d).unwrap(), Some(0.0));
+    assert_eq!(
+        bounds.normalize("juilland_d", clumped_d).unwrap(),
+        Some(1.0)
+    );
+
+    let dp = analyzer
+        .get_frequency_normalized_metric("dp")
+        .unwrap()
+        .unwrap();
+    assert_approx_eq(
+        dp,
+        analyzer.get_dp_nofreq().unwrap(),
+        1e-12,
+        "normalized dp",
+    );
+    assert_eq!(
+        analyzer.get_frequency_normalized_metric("range").unwrap(),
+        Some(0.0)
+    );
+    assert_eq!(
+        bounds.normalize("bogus", 0.5).unwrap_err(),
+        DispersionError::UnknownMetric("bogus".to_string())
+    );
+}
+
+#[test]
+fn test_position_analyzer_validation() {
+    let err = PositionAnalyzer::new(vec![1, 5, 5], 10).unwrap_err();
+    assert!(matches!(
+        err,
+        DispersionError::InvalidPosition {
+            index: 2,
+            value: 5,
+            ..
+        }
+    ));
+    let err = PositionAnalyzer::new(vec![3, 10], 10).unwrap_err();
+    assert!(matches!(
+        err,
+        DispersionError::InvalidPosition { index: 1, .. }
+    ));
+    assert!(matches!(
+        PositionAnalyzer::new(vec![], 0).unwrap_err(),
+        DispersionError::NonPositiveTotal(_)
+    ));
+
+    let absent = PositionAnalyzer::new(vec![], 10)
+        .unwrap()
+        .calculate_all_metrics();
+    assert_eq!(absent.frequency, 0);
+    assert_eq!(absent.arf, Some(0.0));
+    assert!(absent.awt.is_none());
+    assert!(absent.ald.is_none());
+}
+
+#[test]
+fn test_position_batch_metrics() {
+    let words = vec![vec![0, 1, 2, 3, 4], vec![0, 20, 40, 60, 80], vec![50]];
+    let batch = PositionAnalyzer::calculate_batch_metrics(words.clone(), 100).unwrap();
+    assert_eq!(batch.len(), 3);
+    for (metrics, positions) in batch.iter().zip(words) {
+        let single = PositionAnalyzer::new(positions, 100)
+            .unwrap()
+            .calculate_all_metrics();
+        assert_eq!(metrics.to_string(), single.to_string());
+    }
+    assert_eq!(batch[2].arf, Some(1.0));
+
+    let err =
+        PositionAnalyzer::calculate_batch_metrics(vec![vec![0, 1], vec![2, 1]], 100).unwrap_err();
+    assert_eq!(err.row(), Some(1));
+}
+
+#[test]
+fn test_batch_goodness_of_fit() {
+    let rows = vec![
+        vec![2.0, 3.0, 5.0],
+        vec![0.0, 0.0, 0.0],
+        vec![9.0, 0.0, 1.0],
+    ];
+    let partition = Arc::new(CorpusPartition::new(vec![10.0; 3], 30.0).unwrap());
+    let batch =
+        CorpusWordAnalyzer::calculate_batch_goodness_of_fit(rows.clone(), &partition).unwrap();
+
+    assert_eq!(batch.len(), rows.len());
+    for (row, fit) in rows.into_iter().zip(&batch) {
+        let single = CorpusWordAnalyzer::with_partition(row, Arc::clone(&partition))
+            .unwrap()
+            .get_goodness_of_fit();
+        assert_eq!(*fit, single);
+    }
+    assert_eq!(batch[1].chi_square, 0.0);
+    assert_eq!(batch[1].chi_square_p_value, Some(1.0));
+    assert!(
+        batch[2]
+            .to_string()
+            .starts_with("GoodnessOfFit(chi_square=")
+    );
+
+    // Borrowed dense and CSR matrices give the same tests
+    let col_major = [2.0, 0.0, 9.0, 3.0, 0.0, 0.0, 5.0, 0.0, 1.0];
+    let dense = FrequencyMatrix::new(&col_major, 3, 3, MatrixLayout::ColumnMajor).unwrap();
+    assert_eq!(
+        CorpusWordAnalyzer::calculate_batch_goodness_of_fit_matrix(&dense, &partition).unwrap(),
+        batch
+    );
+    let (indptr, indices, data) = (
+        [0_i32, 3, 3, 5],
+        [0_i32, 1, 2, 0, 2],
+        [2.0, 3.0, 5.0, 9.0, 1.0],
+    );
+    let csr = CsrMatrix::new(&indptr, &indices, &data, 3, 3).unwrap();
+    assert_eq!(
+        CorpusWordAnalyzer::calculate_batch_goodness_of_fit_matrix(&csr, &partition).unwrap(),
+        batch
+    );
+    let narrow = FrequencyMatrix::new(&[1.0, 2.0], 1, 2, MatrixLayout::RowMajor).unwrap();
+    assert!(matches!(
+        CorpusWordAnalyzer::calculate_batch_goodness_of_fit_matrix(&narrow, &partition),
+        Err(DispersionError::LengthMismatch { .. })
+    ));
+
+    let err = CorpusWordAnalyzer::calculate_batch_goodness_of_fit(
+        vec![vec![1.0, 2.0, 3.0], vec![1.0, -2.0, 3.0]],
+        &partition,
+    )
+    .unwrap_err();
+    assert_eq!(err.row(), Some(1));
+}
+
+#[test]
+fn test_bootstrap_metrics_reproducible_with_seed() {
+    let config = BootstrapConfig {
+        n_resamples: 200,
+        confidence_level: 0.9,
+        seed: Some(42),
+    };
+    let selection = MetricSet::DP | MetricSet::JUILLAND_D;
+    let v = vec![2.0, 0.0, 5.0, 1.0, 9.0, 3.0];
+    let sizes = vec![10.0, 20.0, 5.0, 40.0, 25.0, 15.0];
+
+    let mut analyzer = CorpusWordAnalyzer::new(v.clone(), sizes.clone(), 115.0).unwrap();
+    let first = analyzer.bootstrap_metrics(selection, &config).unwrap();
+    let second = analyzer.bootstrap_metrics(selection, &config).unwrap();
+
+    assert_eq!(first.seed, 42);
+    assert_eq!(first.n_resamples, 200);
+    assert_eq!(first.n_valid_resamples, 200);
+    assert_eq!(first.lower.dp, second.lower.dp);
+    assert_eq!(first.upper.juilland_d, second.upper.juilland_d);
+    assert_eq!(first.estimate.dp, analyzer.get_dp());
+
+    for name in ["dp", "juilland_d"] {
+        let (lower, upper) = first.interval(name).unwrap().unwrap();
+        assert!(lower <= upper, "{name}: {lower} > {upper}");
+    }
+    assert!(first.lower.carroll_d2.is_none());
+    assert!(first.interval("carroll_d2").unwrap().is_none());
+    assert!(matches!(
+        first.interval("bogus"),
+        Err(DispersionError::UnknownMetric(_))
+    ));
+
+    // Row 0 of a batch uses the same random streams as a single word
+    let partition = Arc::clone(analyzer.get_partition());
+    let batch = CorpusWordAnalyzer::calculate_batch_bootstrap(
+        vec![v, vec![1.0; 6]],
+        &partition,
+        selection,
+        &config,
+    )
+    .unwrap();
+    assert_eq!(batch.len(), 2);
+    assert_eq!(batch[0].lower.dp, first.lower.dp);
+    assert_eq!(batch[0].upper.dp, first.upper.dp);
+
+    // A CSR matrix of the same rows resamples identically
+    let indptr = [0_usize, 5, 11];
+    let indices = [0, 2, 3, 4, 5, 0, 1, 2, 3, 4, 5];
+    let data = [2.0, 5.0, 1.0, 9.0, 3.0, 1.0, 1.0, 1.0, 1.0, 1.0, 1.0];
+    let csr = CsrMatrix::new(&indptr, &indices, &data, 2, 6).unwrap();
+    let sparse =
+        CorpusWordAnalyzer::calculate_batch_bootstrap_matrix(&csr, &partition, selection, &config)
+            .unwrap();
+    for (s, d) in sparse.iter().zip(&batch) {
+        assert_eq!(s.lower.to_string(), d.lower.to_string());
+        assert_eq!(s.upper.to_string(), d.upper.to_string());
+        assert_eq!(s.n_valid_resamples, d.n_valid_resamples);
+    }
+}
+
+#[test]
+fn test_bootstrap_degenerate_distribution() {
+    // Every resample of identical parts is the original distribution
+    let mut analyzer = create_uniform_analyzer();
+    let config = BootstrapConfig {
+        n_resamples: 50,
+        seed: Some(7),
+        ..BootstrapConfig::default()
+    };
+    let intervals = analyzer.bootstrap_metrics(MetricSet::ALL, &config).unwrap();
+
+    assert_eq!(intervals.lower.range, intervals.estimate.range);
+    assert_eq!(intervals.upper.range, intervals.estimate.range);
+    for ((name, estimate), (lower, upper)) in DispersionMetrics::FLOAT_FIELDS
+        .iter()
+        .zip(intervals.estimate.float_values())
+        .zip(
+            intervals
+                .lower
+                .float_values()
+                .into_iter()
+                .zip(intervals.upper.float_values()),
+        )
+    {
+        let Some(estimate) = estimate else { continue };
+        assert_approx_eq(lower.unwrap(), estimate, 1e-12, name);
+        assert_approx_eq(upper.unwrap(), estimate, 1e-12, name);
+    }
+}
+
+#[test]
+fn test_bootstrap_skips_resamples_without_the_word() {
+    // All tokens in one of 8 equal parts: a resample without that part would
+    // score a perfect DP of 0, which is not a dispersion of this word
+    let mut v = vec![0.0; 8];
+    v[0] = 3.0;
+    let mut analyzer = CorpusWordAnalyzer::new(v, vec![10.0; 8], 80.0).unwrap();
+    let config = BootstrapConfig {
+        n_resamples: 500,
+        seed: Some(11),
+        ..BootstrapConfig::default()
+    };
+    let intervals = analyzer
+        .bootstrap_metrics(MetricSet::DP | MetricSet::JUILLAND_D, &config)
+        .unwrap();
+
+    // About (7/8)^8 ≈ 34% of the resamples miss the word
+    assert!(intervals.n_valid_resamples > 0);
+    assert!(intervals.n_valid_resamples < intervals.n_resamples);
+    let (lower, upper) = intervals.interval("dp").unwrap().unwrap();
+    assert!(lower > 0.0, "DP lower bound {lower}");
+    assert!(upper <= intervals.estimate.dp.unwrap() + 1e-12);
+    // D is 0 for the observed word itself, and never leaves [0, 1]
+    let (lower, upper) = intervals.interval("juilland_d").unwrap().unwrap();
+    assert!(
+        (0.0..=upper).contains(&lower) && upper <= 1.0,
+        "D interval ({lower}, {upper})"
+    );
+
+    // A word that never occurs has no resample to learn from
+    let mut absent = CorpusWordAnalyzer::new(vec![0.0; 8], vec![10.0; 8], 80.0).unwrap();
+    let intervals = absent.bootstrap_metrics(MetricSet::DP, &config).unwrap();
+    assert_eq!(intervals.n_valid_resamples, 0);
+    assert!(intervals.interval("dp").unwrap().is_none());
+}
+
+#[test]
+fn test_bootstrap_invalid_config() {
+    let mut analyzer = create_basic_analyzer();
+    for config in [
+        BootstrapConfig {
+            n_resamples: 0,
+            ..BootstrapConfig::default()
+        },
+        BootstrapConfig {
+            confidence_level: 1.0,
+            ..BootstrapConfig::default()
+        },
+        BootstrapConfig {
+            confidence_level: f64::NAN,
+            ..BootstrapConfig::default()
+        },
+    ] {
+        assert!(matches!(
+            analyzer.bootstrap_metrics(MetricSet::DP, &config),
+            Err(DispersionError::InvalidParameter { .. })
+        ));
+    }
+}
+
+#[test]
+fn test_null_distribution_flags_clumped_word() {
+    let config = SimulationConfig {
+        n_simulations: 500,
+        seed: Some(11),
+    };
+    let selection = MetricSet::DP | MetricSet::JUILLAND_D | MetricSet::EVENNESS_DA;
+    let mut clumped =
+        CorpusWordAnalyzer::new(vec![20.0, 0.0, 0.0, 0.0], vec![10.0; 4], 40.0).unwrap();
+    let null = clumped
+        .simulate_null_distribution(selection, &config)
+        .unwrap();
+
+    assert_eq!(null.n_simulations, 500);
+    assert_eq!(null.seed, 11);
+    assert_eq!(null.observed.dp, clumped.get_dp());
+    // No simulated word puts all 20 tokens in one part
+    assert_approx_eq(null.p_value_greater.dp.unwrap(), 1.0 / 501.0, 1e-12, "DP p");
+    assert_approx_eq(
+        null.p_value_less.juilland_d.unwrap(),
+        1.0 / 501.0,
+        1e-12,
+        "D p",
+    );
+    assert_approx_eq(
+        null.p_value_less.evenness_da.unwrap(),
+        1.0 / 501.0,
+        1e-12,
+        "DA p",
+    );
+    assert!(null.z_score.dp.unwrap() > 3.0);
+    assert!(null.z_score.juilland_d.unwrap() < -3.0);
+    assert!(null.expected.carroll_d2.is_none());
+
+    // Same seed, same simulations; batch row 0 matches the single word
+    let again = clumped
+        .simulate_null_distribution(selection, &config)
+        .unwrap();
+    assert_eq!(again.expected.dp, null.expected.dp);
+    let partition = Arc::clone(clumped.get_partition());
+    let batch = CorpusWordAnalyzer::calculate_batch_null_distribution(
+        vec![vec![20.0, 0.0, 0.0, 0.0], vec![5.0; 4]],
+        &partition,
+        selection,
+        &config,
+    )
+    .unwrap();
+    assert_eq!(batch[0].std_dev.dp, null.std_dev.dp);
+    assert!(batch[1].p_value_greater.dp.unwrap() > 0.5);
+
+    // A row-major matrix of the same rows simulates identically
+    let data = [20.0, 0.0, 0.0, 0.0, 5.0, 5.0, 5.0, 5.0];
+    let dense = FrequencyMatrix::new(&data, 2, 4, MatrixLayout::RowMajor).unwrap();
+    let from_matrix = CorpusWordAnalyzer::calculate_batch_null_distribution_matrix(
+        &dense, &partition, selection, &config,
+    )
+    .unwrap();
+    for (m, b) in from_matrix.iter().zip(&batch) {
+        assert_eq!(m.expected.to_string(), b.expected.to_string());
+        assert_eq!(m.std_dev.to_string(), b.std_dev.to_string());
+        assert_eq!(m.p_value_greater.dp, b.p_value_greater.dp);
+    }
+}
+
+#[test]
+fn test_null_distribution_edge_cases() {
+    // A word that never occurs: every simulation is identical
+    let mut zero = create_zero_analyzer();
+    let config = SimulationConfig {
+        n_simulations: 20,
+        seed: Some(3),
+    };
+    let null = zero
+        .simulate_null_distribution(MetricSet::DP, &config)
+        .unwrap();
+    assert_eq!(null.std_dev.dp, Some(0.0));
+    assert!(null.z_score.dp.is_none());
+    assert_eq!(null.p_value_greater.dp, Some(1.0));
+    assert_eq!(null.p_value_less.dp, Some(1.0));
+
+    let invalid = SimulationConfig {
+        n_simulations: 0,
+        seed: None,
+    };
+    assert!(matches!(
+        zero.simulate_null_distribution(MetricSet::DP, &invalid),
+        Err(DispersionError::InvalidParameter { .. })
+    ));
+}
+
+#[test]
+fn test_batch_residuals_flag_clumped_words() {
+    let partition = Arc::new(CorpusPartition::new(vec![1000.0; 4], 4000.0).unwrap());
+    // Per frequency: three evenly spread words and one concentrated in a single part
+    let mut matrix = Vec::new();
+    for f in [4.0, 8.0, 12.0, 16.0, 20.0] {
+        for _ in 0..3 {
+            matrix.push(vec![f / 4.0; 4]);
+        }
+        matrix.push(vec![f, 0.0, 0.0, 0.0]);
+    }
+    matrix.push(vec![0.0; 4]);
+
+    let config = ResidualConfig { n_bands: 5 };
+    let results = CorpusWordAnalyzer::calculate_batch_residuals(
+        matrix.clone(),
+        &partition,
+        MetricSet::DP,
+        &config,
+    )
+    .unwrap();
+    let metrics = CorpusWordAnalyzer::calculate_batch_metrics_with_partition(
+        matrix,
+        &partition,
+        MetricSet::DP,
+    )
+    .unwrap();
+    assert_eq!(results.len(), 21);
+
+    for (word, result) in results[..20].iter().enumerate() {
+        assert_eq!(result.observed.dp, metrics[word].dp);
+        assert_eq!(result.band, Some(word / 4));
+        assert!(result.residual.juilland_d.is_none());
+        let clumped = word % 4 == 3;
+        let residual = result.residual.dp.unwrap();
+        let percentile = result.percentile.dp.unwrap();
+        if clumped {
+            assert!(residual > 0.3, "clumped word {word}: {residual}");
+            assert_approx_eq(percentile, 87.5, 1e-12, "clumped percentile");
+        } else {
+            assert!(residual <= 0.0, "even word {word}: {residual}");
+            assert_approx_eq(percentile, 37.5, 1e-12, "even percentile");
+        }
+    }
+
+    let absent = &results[20];
+    assert_eq!(absent.frequency, 0.0);
+    assert!(absent.band.is_none());
+    assert!(absent.residual.dp.is_none() && absent.percentile.dp.is_none());
+}
+
+#[test]
+fn test_residuals_invalid_input() {
+    let partition = Arc::new(CorpusPartition::new(vec![10.0, 10.0], 20.0).unwrap());
+    let err = CorpusWordAnalyzer::calculate_batch_residuals(
+        vec![vec![1.0, 2.0]],
+        &partition,
+        MetricSet::ALL,
+        &ResidualConfig { n_bands: 0 },
+    )
+    .unwrap_err();
+    assert!(matches!(
+        err,
+        DispersionError::InvalidParameter {
+            name: "n_bands",
+            ..
+        }
+    ));
+
+    let err = frequency_residuals(
+        vec![DispersionMetrics::default(); 2],
+        &[1.0],
+        &ResidualConfig::default(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, DispersionError::LengthMismatch { .. }));
+}
+
+#[test]
+fn test_word_list_ranking_and_ties() {
+    let partition = Arc::new(CorpusPartition::new(vec![100.0; 4], 400.0).unwrap());
+    let words: Vec<String> = [
+        "clumped",
+        "twin_b",
+        "even",
+        "absent",
+        "twin_a",
+        "even_small",
+    ]
+    .iter()
+    .map(ToString::to_string)
+    .collect();
+    let matrix = vec![
+        vec![20.0, 0.0, 0.0, 0.0],
+        vec![2.0; 4],
+        vec![5.0; 4],
+        vec![0.0; 4],
+        vec![2.0; 4],
+        vec![1.0; 4],
+    ];
+    let order = |list: &[WordListEntry]| -> Vec<String> {
+        list.iter().map(|entry| entry.word.clone()).collect()
+    };
+
+    // FT × DA, ties broken by frequency then word
+    let list = CorpusWordAnalyzer::calculate_batch_word_list(
+        words.clone(),
+        matrix.clone(),
+        &partition,
+        &WordListConfig::default(),
+    )
+    .unwrap();
+    assert_eq!(
+        order(&list),
+        [
+            "even",
+            "twin_a",
+            "twin_b",
+            "even_small",
+            "clumped",
+            "absent"
+        ]
+    );
+    let ranks: Vec<Option<usize>> = list.iter().map(|entry| entry.rank).collect();
+    assert_eq!(ranks, [1, 2, 3, 4, 5, 6].map(Some));
+    let top = &list[0];
+    assert_eq!(top.frequency, 20.0);
+    assert_eq!(top.evenness_da, Some(1.0));
+    assert_approx_eq(
+        top.score.unwrap(),
+        top.mean_text_frequency_ft.unwrap(),
+        1e-12,
+        "FT x DA",
+    );
+    assert_eq!(list[4].pervasiveness_pt, Some(0.25));
+
+    // Shared ranks and input-order ties
+    let config = WordListConfig {
+        tie_break: TieBreak::Input,
+        shared_ranks: true,
+        ..WordListConfig::default()
+    };
+    let list = CorpusWordAnalyzer::calculate_batch_word_list(
+        words.clone(),
+        matrix.clone(),
+        &partition,
+        &config,
+    )
+    .unwrap();
+    assert_eq!(&order(&list)[1..3], ["twin_b", "twin_a"]);
+    let ranks: Vec<Option<usize>> = list.iter().map(|entry| entry.rank).collect();
+    assert_eq!(ranks, [1, 2, 2, 4, 5, 5].map(Some));
+
+    // Ascending DP: perfectly even words tie at 0 and are ordered by frequency
+    let config = WordListConfig {
+        score: "dp".to_string(),
+        descending: false,
+        ..WordListConfig::default()
+    };
+    let list =
+        CorpusWordAnalyzer::calculate_batch_word_list(words, matrix, &partition, &config).unwrap();
+    assert_eq!(
+        order(&list),
+        [
+            "even",
+            "twin_a",
+            "twin_b",
+            "even_small",
+            "absent",
+            "clumped"
+        ]
+    );
+
+    // Words whose score was not computed are left unranked at the end
+    let scored = DispersionMetrics {
+        dp: Some(0.5),
+        ..Default::default()
+    };
+    let list = build_word_list(
+        vec!["missing".to_string(), "scored".to_string()],
+        &[DispersionMetrics::default(), scored],
+        &[10.0, 1.0],
+        &config,
+    )
+    .unwrap();
+    assert_eq!(order(&list), ["scored", "missing"]);
+    assert_eq!(list[0].rank, Some(1));
+    assert_eq!(list[1].rank, None);
+    assert_eq!(list[1].score, None);
+}
+
+#[test]
+fn test_word_list_invalid_input() {
+    let partition = Arc::new(CorpusPartition::new(vec![10.0, 10.0], 20.0).unwrap());
+    let err = CorpusWordAnalyzer::calculate_batch_word_list(
+        vec!["a".to_string()],
+        vec![vec![1.0, 2.0], vec![3.0, 4.0]],
+        &partition,
+        &WordListConfig::default(),
+    )
+    .unwrap_err();
+    assert_eq!(
+        err,
+        DispersionError::LabelCountMismatch {
+            expected: 2,
+            found: 1
+        }
+    );
+
+    let config = WordListConfig {
+        score: "prevalence".to_string(),
+        ..WordListConfig::default()
+    };
+    let err = CorpusWordAnalyzer::calculate_batch_word_list(
+        vec!["a".to_string()],
+        vec![vec![1.0, 2.0]],
+        &partition,
+        &config,
+    )
+    .unwrap_err();
+    assert!(matches!(err, DispersionError::UnknownMetric(_)));
+
+    assert_eq!(TieBreak::from_name("word"), Some(TieBreak::Word));
+    assert_eq!(TieBreak::from_name("random"), None);
+}
+
+#[test]
+fn test_frequency_table_tsv_with_size_row() {
+    let text = "\u{feff}word\tpart A\tpart B\tpart C\r\n\
+                __size__\t100\t200\t100\r\n\
+                \r\n\
+                the\t10\t20\t10\r\n\
+                \"say \"\"hi\"\"\tthere\"\t0\t3\t\r\n\
+                \"two\nlines\"\t1\t 1 \t0\r\n";
+    let config = TableConfig {
+        size_row: Some("__size__".to_string()),
+        ..TableConfig::default()
+    };
+    let table = FrequencyTable::read(text.as_bytes(), &config).unwrap();
+
+    assert_eq!(table.words(), ["the", "say \"hi\"\tthere", "two\nlines"]);
+    assert_eq!(table.part_names(), ["part A", "part B", "part C"]);
+    assert_eq!(table.n_words(), 3);
+    assert_eq!(table.n_parts(), 3);
+    // Empty cells count as 0
+    assert_eq!(table.row(1), Some(vec![0.0, 3.0, 0.0]));
+    assert_eq!(table.row(3), None);
+    assert!(!table.is_sparse());
+    assert_eq!(
+        table.data(),
+        Some(&[10.0, 20.0, 10.0, 0.0, 3.0, 0.0, 1.0, 1.0, 0.0][..])
+    );
+    assert_eq!(table.partition().part_sizes(), [100.0, 200.0, 100.0]);
+    assert_approx_eq(
+        table.partition().total_corpus_words(),
+        400.0,
+        1e-12,
+        "total from size row",
+    );
+
+    let labelled = table.calculate_metrics(MetricSet::ALL).unwrap();
+    let expected = CorpusWordAnalyzer::calculate_batch_metrics_with_partition(
+        vec![
+            vec![10.0, 20.0, 10.0],
+            vec![0.0, 3.0, 0.0],
+            vec![1.0, 1.0, 0.0],
+        ],
+        table.partition(),
+        MetricSet::ALL,
+    )
+    .unwrap();
+    assert_eq!(labelled.len(), 3);
+    for (word, metrics) in table.words().iter().zip(&expected) {
+        let found = labelled.get(word).unwrap();
+        assert_eq!(found.word.as_deref(), Some(word.as_str()));
+        assert_eq!(found.dp, metrics.dp);
+        assert_eq!(found.evenness_da, metrics.evenness_da);
+    }
+}
+
+#[test]
+fn test_frequency_table_csv_inferred_sizes() {
+    let config = TableConfig {
+        delimiter: b',',
+        has_header: false,
+        ..TableConfig::default()
+    };
+    let table = FrequencyTable::read("a,1,2\nb,3,4\n".as_bytes(), &config).unwrap();
+    assert_eq!(table.words(), ["a", "b"]);
+    assert_eq!(table.part_names(), ["part_1", "part_2"]);
+    assert_eq!(table.partition().part_sizes(), [4.0, 6.0]);
+    let matrix = table.matrix().unwrap();
+    assert_eq!((matrix.n_rows(), matrix.n_cols()), (2, 2));
+    assert_approx_eq(matrix.get(1, 0), 3.0, 1e-12, "matrix cell");
+
+    // Without quoting, quote characters are ordinary text
+    let config = TableConfig {
+        quote: None,
+        ..config
+    };
+    let table = FrequencyTable::read("\"a,1,2\n".as_bytes(), &config).unwrap();
+    assert_eq!(table.words(), ["\"a"]);
+
+    // Files are read the same way as in-memory input
+    let path = std::env::temp_dir().join(format!(
+        "corpus_dispersion_table_{}.csv",
+        std::process::id()
+    ));
+    std::fs::write(&path, "a,1,2\nb,3,4\n").unwrap();
+    let from_file = FrequencyTable::from_path(&path, &config).unwrap();
+    std::fs::remove_file(&path).unwrap();
+    assert_eq!(from_file.words(), ["a", "b"]);
+    assert_eq!(from_file.row(1), Some(vec![3.0, 4.0]));
+}
+
+#[test]
+fn test_frequency_table_malformed_input() {
+    let config = TableConfig::default();
+    let read = |text: &str| FrequencyTable::read(text.as_bytes(), &config).unwrap_err();
+
+    let err = read("word\ta\tb\nx\t1\t2\ny\t1\n");
+    assert_eq!(
+        err,
+        DispersionError::MalformedTable {
+            line: 3,
+            reason: "expected 3 fields, found 2".to_string()
+        }
+    );
+    assert!(matches!(
+        read("word\ta\nx\tmany\n"),
+        DispersionError::MalformedTable { line: 2, .. }
+    ));
+    assert!(matches!(
+        read("word\ta\n\"open\t1\n\n"),
+        DispersionError::MalformedTable { line: 2, .. }
+    ));
+    assert!(matches!(
+        read("word\ta\n\"x\"y\t1\n"),
+        DispersionError::MalformedTable { line: 2, .. }
+    ));
+    assert_eq!(read("word\n"), DispersionError::EmptyInput);
+
+    let config = TableConfig {
+        size_row: Some("SIZE".to_string()),
+        ..TableConfig::default()
+    };
+    let err = FrequencyTable::read("w\ta\nSIZE\t1\nSIZE\t2\n".as_bytes(), &config).unwrap_err();
+    assert!(matches!(
+        err,
+        DispersionError::MalformedTable { line: 3, .. }
+    ));
+
+    let err = FrequencyTable::from_path("/nonexistent/table.tsv", &config).unwrap_err();
+    assert!(matches!(err, DispersionError::Io(_)));
+
+    // Invalid frequencies are reported with their word, with or without a
+    // size row, before a part size is inferred from them
+    let text = "w\ta\tb\nok\t1\t2\nbad\t-1\t3\n";
+    for config in [config, TableConfig::default()] {
+        let err = FrequencyTable::read(text.as_bytes(), &config).unwrap_err();
+        assert_eq!(err.row(), Some(1));
+        assert_eq!(err.word(), Some("bad"));
+        assert_eq!(
+            err.kind(),
+            &DispersionError::NegativeFrequency {
+                index: 0,
+                value: -1.0
+            }
+        );
+    }
+
+    // Tables built directly are checked by the metric calculation
+    let table = FrequencyTable::new(
+        vec!["ok".to_string(), "bad".to_string()],
+        Vec::new(),
+        vec![1.0, 2.0, -1.0, 3.0],
+        vec![10.0, 10.0],
+    )
+    .unwrap();
+    let err = table.calculate_metrics(MetricSet::ALL).unwrap_err();
+    assert_eq!(err.row(), Some(1));
+    assert_eq!(err.word(), Some("bad"));
+}
+
+#[test]
+fn test_frequency_table_from_csr_matches_dense() {
+    let words = vec!["a".to_string(), "b".to_string(), "c".to_string()];
+    let part_sizes = vec![50.0, 30.0, 20.0];
+    let dense = FrequencyTable::new(
+        words.clone(),
+        Vec::new(),
+        vec![4.0, 0.0, 1.0, 0.0, 0.0, 0.0, 0.0, 2.0, 2.0],
+        part_sizes.clone(),
+    )
+    .unwrap();
+    let sparse = FrequencyTable::from_csr(
+        words.clone(),
+        Vec::new(),
+        vec![0, 2, 2, 4],
+        vec![0, 2, 1, 2],
+        vec![4.0, 1.0, 2.0, 2.0],
+        part_sizes.clone(),
+    )
+    .unwrap();
+    assert!(sparse.is_sparse());
+    assert_eq!(sparse.part_names(), ["part_1", "part_2", "part_3"]);
+    assert_eq!(sparse.row(1), Some(vec![0.0, 0.0, 0.0]));
+    assert_eq!(sparse.row(2), dense.row(2));
+    assert!(sparse.matrix().is_none());
+
+    let expected = dense.calculate_metrics(MetricSet::ALL).unwrap();
+    let labelled = sparse.calculate_metrics(MetricSet::ALL).unwrap();
+    for word in &words {
+        let (found, wanted) = (labelled.get(word).unwrap(), expected.get(word).unwrap());
+        assert_eq!(found.word, wanted.word);
+        assert_eq!(found.dp, wanted.dp);
+        assert_eq!(found.evenness_da, wanted.evenness_da);
+        assert_eq!(found.range, wanted.range);
+    }
+
+    // Buffers inconsistent with the words and parts are rejected
+    let err = FrequencyTable::from_csr(
+        words.clone(),
+        Vec::new(),
+        vec![0, 2, 4],
+        vec![0, 2, 1, 2],
+        vec![4.0, 1.0, 2.0, 2.0],
+        part_sizes.clone(),
+    )
+    .unwrap_err();
+    assert!(matches!(err, DispersionError::MalformedSparse(_)));
+    let err = FrequencyTable::from_csr(
+        words,
+        Vec::new(),
+        vec![0, 2, 2, 4],
+        vec![0, 3, 1, 2],
+        vec![4.0, 1.0, 2.0, 2.0],
+        part_sizes,
+    )
+    .unwrap_err();
+    assert!(matches!(err.kind(), DispersionError::MalformedSparse(_)));
+}
+
+#[test]
+fn test_tokenizer_unicode_words() {
+    let tokenizer = Tokenizer::default();
+    assert_eq!(
+        tokenizer.tokenize("Don’t stop -- well-known Café, naïve 42 times!"),
+        [
+            "don’t",
+            "stop",
+            "well-known",
+            "café",
+            "naïve",
+            "42",
+            "times"
+        ]
+    );
+    // Han and Kana characters are tokens of their own; Hangul keeps its spacing
+    assert_eq!(
+        tokenizer.tokenize("我爱Rust。ひらがな 한국어 단어"),
+        ["我", "爱", "rust", "ひ", "ら", "が", "な", "한국어", "단어"]
+    );
+
+    let tokenizer = Tokenizer {
+        lowercase: false,
+        keep_numbers: false,
+        joiners: String::new(),
+        split_cjk: false,
+        min_length: 2,
+    };
+    assert_eq!(
+        tokenizer.tokenize("A well-known 2024 Test 中文 x1"),
+        ["well", "known", "Test", "中文", "x1"]
+    );
+}
+
+#[test]
+fn test_build_frequency_table_from_directory() {
+    let root = std::env::temp_dir().join(format!("corpus_dispersion_dir_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("fiction/novels")).unwrap();
+    std::fs::create_dir_all(root.join("news")).unwrap();
+    std::fs::write(root.join("a.txt"), "The cat saw the dog.").unwrap();
+    std::fs::write(root.join("b.TXT"), "A dog!").unwrap();
+    std::fs::write(root.join("notes.md"), "ignored words").unwrap();
+    std::fs::write(root.join(".hidden.txt"), "ignored").unwrap();
+    std::fs::write(root.join("fiction/one.txt"), "the end").unwrap();
+    std::fs::write(root.join("fiction/novels/two.txt"), "The END the").unwrap();
+    std::fs::write(root.join("news/today.txt"), "cat").unwrap();
+
+    // Each file directly in the directory is a part
+    let table = build_frequency_table(&root, &CorpusConfig::default()).unwrap();
+    assert_eq!(table.part_names(), ["a.txt", "b.TXT"]);
+    assert_eq!(table.words(), ["a", "cat", "dog", "saw", "the"]);
+    assert_eq!(table.partition().part_sizes(), [5.0, 2.0]);
+    assert_eq!(table.row(2), Some(vec![1.0, 1.0]));
+    assert_eq!(table.row(4), Some(vec![2.0, 0.0]));
+    // Only the non-zero counts are stored
+    assert!(table.is_sparse());
+    assert_eq!(table.data(), None);
+    assert_eq!(table.sparse_matrix().unwrap().nnz(), 6);
+
+    // Each subdirectory is a part, including the files of nested directories
+    let config = CorpusConfig {
+        partition_by: PartitionBy::Directory,
+        ..CorpusConfig::default()
+    };
+    let table = build_frequency_table(&root, &config).unwrap();
+    assert_eq!(table.part_names(), ["fiction", "news"]);
+    assert_eq!(table.words(), ["cat", "end", "the"]);
+    assert_eq!(table.partition().part_sizes(), [5.0, 1.0]);
+    assert_eq!(table.row(2), Some(vec![3.0, 0.0]));
+
+    let metrics = table.calculate_metrics(MetricSet::ALL).unwrap();
+    let expected = CorpusWordAnalyzer::new(vec![3.0, 0.0], vec![5.0, 1.0], 6.0)
+        .unwrap()
+        .calculate_all_metrics();
+    assert_eq!(metrics.get("the").unwrap().dp, expected.dp);
+
+    // An empty extension list reads every file
+    let config = CorpusConfig {
+        extensions: Vec::new(),
+        ..CorpusConfig::default()
+    };
+    let table = build_frequency_table(&root, &config).unwrap();
+    assert_eq!(table.part_names(), ["a.txt", "b.TXT", "notes.md"]);
+
+    let config = CorpusConfig {
+        extensions: vec!["csv".to_string()],
+        ..CorpusConfig::default()
+    };
+    assert_eq!(
+        build_frequency_table(&root, &config).unwrap_err(),
+        DispersionError::EmptyInput
+    );
+    std::fs::remove_dir_all(&root).unwrap();
+
+    let err = build_frequency_table(&root, &CorpusConfig::default()).unwrap_err();
+    assert!(matches!(err, DispersionError::Io(_)));
+    assert_eq!(
+        PartitionBy::from_name("directory"),
+        Some(PartitionBy::Directory)
+    );
+    assert_eq!(PartitionBy::from_name("line"), None);
+}
+
+#[cfg(unix)]
+#[test]
+fn test_build_frequency_table_skips_linked_directories_and_dangling_links() {
+    use std::os::unix::fs::symlink;
+
+    let root = std::env::temp_dir().join(format!("corpus_dispersion_links_{}", std::process::id()));
+    let _ = std::fs::remove_dir_all(&root);
+    std::fs::create_dir_all(root.join("part_a/inner")).unwrap();
+    std::fs::create_dir_all(root.join("part_b")).unwrap();
+    std::fs::write(root.join("part_a/inner/one.txt"), "cat dog").unwrap();
+    std::fs::write(root.join("part_b/two.txt"), "cat").unwrap();
+    // A loop back up the tree and a link into the sibling part
+    symlink("..", root.join("part_a/up")).unwrap();
+    symlink("../../part_b", root.join("part_a/inner/sibling")).unwrap();
+    // Linked files are still read
+    symlink("../part_a/inner/one.txt", root.join("part_b/linked.txt")).unwrap();
+    // Dangling links are skipped, in a part and in the corpus directory
+    symlink("missing.txt", root.join("part_b/dangling.txt")).unwrap();
+    symlink("missing", root.join("part_a/inner/dangling")).unwrap();
+    symlink("missing", root.join("part_c")).unwrap();
+
+    let config = CorpusConfig {
+        partition_by: PartitionBy::Directory,
+        ..CorpusConfig::default()
+    };
+    let table = build_frequency_table(&root, &config).unwrap();
+    assert_eq!(table.part_names(), ["part_a", "part_b"]);
+    assert_eq!(table.words(), ["cat", "dog"]);
+    assert_eq!(table.partition().part_sizes(), [2.0, 3.0]);
+    assert_eq!(table.row(0), Some(vec![1.0, 2.0]));
+
+    std::fs::write(root.join("a.txt"), "cat").unwrap();
+    symlink("missing.txt", root.join("b.txt")).unwrap();
+    let table = build_frequency_table(&root, &CorpusConfig::default()).unwrap();
+    assert_eq!(table.part_names(), ["a.txt"]);
+    std::fs::remove_dir_all(&root).unwrap();
+}
+
+#[test]
+fn test_theoretical_bounds_unequal_sizes() {
+    let mut analyzer =
+        CorpusWordAnalyzer::new(vec![4.0, 3.0, 3.0], vec![10.0, 30.0, 60.0], 100.0).unwrap();
+    let bounds = analyzer.get_theoretical_bounds();
+    assert_eq!(bounds.most_even, vec![1.0, 3.0, 6.0]);
+
+    // The observed counts spread less than the proportional allocation, so
+    // the allocations do not bracket the raw-count spread and it is not normalized
+    let observed_sd = analyzer.get_sd_population().unwrap();
+    assert!(observed_sd < bounds.even_metrics.sd_population.unwrap());
+    for name in [
+        "sd_population",
+        "vc_population",
+        "mean_text_frequency_ft",
+        "ft_adjusted_by_pt",
+        "ft_adjusted_by_da",
+    ] {
+        assert_eq!(bounds.normalize(name, 1.0).unwrap(), None, "{name}");
+        assert_eq!(
+            analyzer.get_frequency_normalized_metric(name).unwrap(),
+            None
+        );
+    }
+
+    // Size-aware metrics are normalized; values past a bound are clamped
+    let dp = analyzer
+        .get_frequency_normalized_metric("dp")
+        .unwrap()
+        .unwrap();
+    assert!((0.0..=1.0).contains(&dp));
+    assert_eq!(bounds.normalize("dp", -1.0).unwrap(), Some(0.0));
+    assert_eq!(bounds.normalize("dp", 2.0).unwrap(), Some(1.0));
+}
+
+#[test]
+fn test_theoretical_bounds_skip_empty_parts() {
+    // An empty part can neither receive the even share nor hold the clumped tokens
+    let mut analyzer =
+        CorpusWordAnalyzer::new(vec![10.0, 0.0, 0.0], vec![50.0, 50.0, 0.0], 100.0).unwrap();
+    let bounds = analyzer.get_theoretical_bounds();
+    assert_eq!(bounds.most_even, vec![5.0, 5.0, 0.0]);
+    assert_eq!(bounds.most_clumped, vec![10.0, 0.0, 0.0]);
+    assert!(bounds.even_metrics.evenness_da.unwrap() > bounds.clumped_metrics.evenness_da.unwrap());
+    assert_eq!(
+        analyzer
+            .get_frequency_normalized_metric("evenness_da")
+            .unwrap(),
+        Some(1.0)
+    );
+    assert_approx_eq(
+        analyzer.get_dp_nofreq().unwrap(),
+        1.0,
+        1e-12,
+        "all tokens in one real part",
+    );
+
+    // The even allocation sums to f whether the total is below or above the
+    // sum of the part sizes
+    for total in [100.0, 150.0] {
+        let partition = CorpusPartition::new(vec![60.0, 60.0], total).unwrap();
+        assert_eq!(partition.most_even_distribution(10.0), vec![5.0, 5.0]);
+        assert_eq!(partition.most_even_distribution(3.0), vec![2.0, 1.0]);
+    }
+}
+
+#[test]
+fn test_dp_range_matches_full_largest_remainder() {
+    // Reference: sort every non-empty part by remainder (shares as in
+    // `most_even_distribution`) and compute DP over all parts
+    fn reference(sizes: &[f64], total: f64, f: f64) -> (Vec<f64>, f64) {
+        let size_sum: f64 = sizes.iter().sum();
+        let expected: Vec<f64> = sizes.iter().map(|&size| f * (size / size_sum)).collect();
+        let mut v: Vec<f64> = expected.iter().map(|x| x.floor()).collect();
+        let mut leftover = f - v.iter().sum::<f64>();
+        let mut order: Vec<usize> = (0..sizes.len()).filter(|&i| sizes[i] > 0.0).collect();
+        order.sort_by(|&a, &b| {
+            (expected[b] - v[b])
+                .total_cmp(&(expected[a] - v[a]))
+                .then(a.cmp(&b))
+        });
+        for &i in &order {
+            if leftover <= 0.0 {
+                break;
+            }
+            let token = leftover.min(1.0);
+            v[i] += token;
+            leftover -= token;
+        }
+        let dp = 0.5
+            * v.iter()
+                .zip(sizes)
+                .map(|(&v_i, &size)| (v_i / f - size / total).abs())
+                .sum::<f64>();
+        (v, dp)
+    }
+
+    let mut state: u64 = 12345;
+    let mut next = move || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 33
+    };
+    for _ in 0..200 {
+        let n = 1 + (next() % 12) as usize;
+        // Repeated and zero sizes exercise the tie-breaking and empty parts
+        let sizes: Vec<f64> = (0..n).map(|_| (next() % 5 * 10) as f64).collect();
+        let size_sum: f64 = sizes.iter().sum();
+        if size_sum == 0.0 {
+            continue;
+        }
+        let total = size_sum * [1.0, 0.8, 1.5][(next() % 3) as usize];
+        let partition = CorpusPartition::new(sizes.clone(), total).unwrap();
+        for f in [1.0, 2.0, 7.0, 13.0, 100.0, 2.5] {
+            let (v, min_dp) = reference(&sizes, total, f);
+            assert_eq!(
+                partition.most_even_distribution(f),
+                v,
+                "sizes {sizes:?}, f {f}"
+            );
+            let (fast_min, fast_max) = partition.dp_range(f);
+            assert_approx_eq(fast_min, min_dp, 1e-12, "min DP");
+            let clumped = partition.most_clumped_distribution(f);
+            let max_dp = 0.5
+                * clumped
+                    .iter()
+                    .zip(&sizes)
+                    .map(|(&v_i, &size)| (v_i / f - size / total).abs())
+                    .sum::<f64>();
+            assert_approx_eq(fast_max, max_dp, 1e-12, "max DP");
+        }
+    }
+}
@@ -233,6 +233,47 @@ fn test_mathematical_correctness_dp_norm() {
     );
 }
 
+#[test]
+fn test_mathematical_correctness_dp_nofreq() {
+    // 两个等大分区、f = 4：最均匀 [2, 2] 的 DP = 0，最集中 [4, 0] 的 DP = 0.5
+    let sizes = vec![50.0, 50.0];
+    let dp_nofreq = |v: Vec<f64>| {
+        CorpusWordAnalyzer::new(v, sizes.clone(), 100.0)
+            .unwrap()
+            .get_dp_nofreq()
+            .unwrap()
+    };
+    // [3, 1] 的 DP = 0.25，正好位于区间中点
+    assert!((dp_nofreq(vec![3.0, 1.0]) - 0.5).abs() < 1e-12);
+    assert!(dp_nofreq(vec![2.0, 2.0]).abs() < 1e-12);
+    assert!((dp_nofreq(vec![4.0, 0.0]) - 1.0).abs() < 1e-12);
+
+    // f = 3 时无法完全均匀：[2, 1] 已是最均匀分布，DP_nofreq = 0
+    assert!(dp_nofreq(vec![2.0, 1.0]).abs() < 1e-12);
+    assert!((dp_nofreq(vec![3.0, 0.0]) - 1.0).abs() < 1e-12);
+
+    // 只有一个分区时上下界重合，无法定义
+    let single = CorpusWordAnalyzer::new(vec![5.0], vec![10.0], 10.0).unwrap();
+    assert!(single.get_dp_nofreq().is_none());
+}
+
+#[test]
+fn test_mathematical_correctness_dp_bounds_distributions() {
+    // 最大余数法：期望值 0.7, 2.1, 4.2 → 取整 0, 2, 4，余下 1 个分给余数最大的部分
+    let partition = CorpusPartition::new(vec![10.0, 30.0, 60.0], 100.0).unwrap();
+    assert_eq!(partition.most_even_distribution(7.0), vec![1.0, 2.0, 4.0]);
+    assert_eq!(
+        partition.most_clumped_distribution(7.0),
+        vec![7.0, 0.0, 0.0]
+    );
+
+    let (min_dp, max_dp) = partition.dp_range(7.0);
+    let expected_min = 0.5
+        * ((1.0 / 7.0 - 0.1_f64).abs() + (2.0 / 7.0 - 0.3_f64).abs() + (4.0 / 7.0 - 0.6_f64).abs());
+    assert!((min_dp - expected_min).abs() < 1e-12);
+    assert!((max_dp - 0.9).abs() < 1e-12);
+}
+
 #[test]
 fn test_mathematical_correctness_kl_divergence() {
     // 测试 KL 散度计算
@@ -354,6 +395,344 @@ fn test_mathematical_correctness_adjusted_metrics() {
     );
 }
 
+#[test]
+fn test_mathematical_correctness_adjusted_frequencies() {
+    // 手算：v = [2, 3, 5]，三个等大部分，f = 10，期望频数均为 10/3
+    let mut analyzer = CorpusWordAnalyzer::new(vec![2.0, 3.0, 5.0], vec![10.0; 3], 30.0).unwrap();
+
+    // χ² = (16/9 + 1/9 + 25/9) / (10/3) = 1.4，D3 = 1 - 1.4 / 40
+    let d3 = analyzer.get_lyne_d3().unwrap();
+    assert!((d3 - 0.965).abs() < 1e-12, "Lyne D3 incorrect: {d3}");
+
+    // AF = (Σ sqrt(v_i / 3))²，S_adj = AF / f
+    let af = analyzer.get_roschengren_af().unwrap();
+    let expected_af = (2f64.sqrt() + 3f64.sqrt() + 5f64.sqrt()).powi(2) / 3.0;
+    assert!((af - expected_af).abs() < 1e-12, "Rosengren AF incorrect");
+    let s_adj = analyzer.get_roschengren_s_adj().unwrap();
+    assert!((s_adj - af / 10.0).abs() < 1e-12, "S_adj 应等于 AF / f");
+
+    // U = f × D，Um = f·D2 + (1 - D2)·f/n
+    let d = analyzer.get_juilland_d().unwrap();
+    let u = analyzer.get_juilland_u().unwrap();
+    assert!((u - 10.0 * d).abs() < 1e-12, "Juilland U incorrect");
+    assert!(
+        (u - 7.354_248_688_935_409).abs() < 1e-9,
+        "Juilland U 手算值不符: {u}"
+    );
+
+    let d2 = analyzer.get_carroll_d2().unwrap();
+    let um = analyzer.get_carroll_um().unwrap();
+    let expected_um = 10.0 * d2 + (1.0 - d2) * 10.0 / 3.0;
+    assert!((um - expected_um).abs() < 1e-12, "Carroll Um incorrect");
+    assert!((10.0 / 3.0..=10.0).contains(&um), "Um 应介于 f/n 与 f 之间");
+}
+
+#[test]
+fn test_adjusted_frequencies_proportional_distribution() {
+    // 频数与部分大小成正比：χ² = 0，D3 = 1；AF = f
+    let analyzer =
+        CorpusWordAnalyzer::new(vec![4.0, 8.0, 12.0], vec![10.0, 20.0, 30.0], 60.0).unwrap();
+
+    assert!((analyzer.get_lyne_d3().unwrap() - 1.0).abs() < 1e-12);
+    assert!((analyzer.get_roschengren_af().unwrap() - 24.0).abs() < 1e-9);
+
+    // 零频词的调整频数均为 0
+    let mut zero = CorpusWordAnalyzer::new(vec![0.0; 3], vec![10.0; 3], 30.0).unwrap();
+    let metrics = zero.calculate_all_metrics();
+    assert_eq!(metrics.lyne_d3, Some(0.0));
+    assert_eq!(metrics.roschengren_af, Some(0.0));
+    assert_eq!(metrics.juilland_u, Some(0.0));
+    assert_eq!(metrics.carroll_um, Some(0.0));
+}
+
+#[test]
+fn test_mathematical_correctness_zhang_dc() {
+    // 手算：p = [0.2, 0.3, 0.5]，DC = (Σ√p_i)² / (n · Σp_i)
+    let mut analyzer = CorpusWordAnalyzer::new(vec![2.0, 3.0, 5.0], vec![10.0; 3], 30.0).unwrap();
+    let expected = (0.2f64.sqrt() + 0.3f64.sqrt() + 0.5f64.sqrt()).powi(2) / 3.0;
+    let dc = analyzer.get_zhang_dc().unwrap();
+    assert!((dc - expected).abs() < 1e-12, "Zhang DC incorrect: {dc}");
+
+    // 均匀分布 DC = 1，完全集中于一个部分时 DC = 1/n
+    let mut uniform = CorpusWordAnalyzer::new(vec![5.0; 4], vec![10.0; 4], 40.0).unwrap();
+    assert!((uniform.get_zhang_dc().unwrap() - 1.0).abs() < 1e-12);
+    let mut clumped =
+        CorpusWordAnalyzer::new(vec![10.0, 0.0, 0.0, 0.0], vec![10.0; 4], 40.0).unwrap();
+    assert!((clumped.get_zhang_dc().unwrap() - 0.25).abs() < 1e-12);
+}
+
+#[test]
+fn test_mathematical_correctness_kl_divergence_norm() {
+    // 1 - 2^(-KL)：与部分大小成比例时为 0
+    let uniform = CorpusWordAnalyzer::new(vec![5.0; 3], vec![10.0; 3], 30.0).unwrap();
+    assert!(uniform.get_kl_divergence_norm().unwrap().abs() < 1e-12);
+
+    // 全部集中于一个部分：KL = log2(3)，归一化值 = 1 - 1/3
+    let clumped = CorpusWordAnalyzer::new(vec![9.0, 0.0, 0.0], vec![10.0; 3], 30.0).unwrap();
+    let norm = clumped.get_kl_divergence_norm().unwrap();
+    assert!(
+        (norm - 2.0 / 3.0).abs() < 1e-12,
+        "KL norm incorrect: {norm}"
+    );
+
+    let analyzer = CorpusWordAnalyzer::new(vec![2.0, 3.0, 5.0], vec![10.0; 3], 30.0).unwrap();
+    let kl = analyzer.get_kl_divergence().unwrap();
+    let norm = analyzer.get_kl_divergence_norm().unwrap();
+    assert!((norm - (1.0 - 2f64.powf(-kl))).abs() < 1e-12);
+    assert!((0.0..1.0).contains(&norm), "归一化 KL 应位于 [0, 1)");
+}
+
+#[test]
+fn test_mathematical_correctness_diversity_indices() {
+    // 手算：p = [0.2, 0.3, 0.5]，Σp = 1
+    let mut analyzer = CorpusWordAnalyzer::new(vec![2.0, 3.0, 5.0], vec![10.0; 3], 30.0).unwrap();
+
+    // Gini = Σ_{i<j}|p_i - p_j| / (n · Σp) = 0.6 / 3
+    let gini = analyzer.get_gini_coefficient().unwrap();
+    assert!((gini - 0.2).abs() < 1e-12, "Gini incorrect: {gini}");
+
+    let entropy = analyzer.get_shannon_entropy().unwrap();
+    let expected_h = -(0.2f64 * 0.2f64.ln() + 0.3 * 0.3f64.ln() + 0.5 * 0.5f64.ln());
+    assert!(
+        (entropy - expected_h).abs() < 1e-12,
+        "Shannon entropy incorrect"
+    );
+
+    // Pielou J = H / ln n，与 Carroll D2 相同
+    let pielou = analyzer.get_pielou_evenness().unwrap();
+    assert!((pielou - expected_h / 3f64.ln()).abs() < 1e-12);
+    assert!((pielou - analyzer.get_carroll_d2().unwrap()).abs() < 1e-12);
+
+    // Simpson λ = 0.04 + 0.09 + 0.25
+    let simpson = analyzer.get_simpson_index().unwrap();
+    assert!(
+        (simpson - 0.38).abs() < 1e-12,
+        "Simpson incorrect: {simpson}"
+    );
+}
+
+#[test]
+fn test_mathematical_correctness_hill_numbers() {
+    let mut analyzer = CorpusWordAnalyzer::new(vec![2.0, 3.0, 0.0], vec![10.0; 3], 30.0).unwrap();
+
+    // q = 0：出现的部分数；q = 1：exp(H)；q = 2：1 / λ
+    let h0 = analyzer.get_hill_number(0.0).unwrap().unwrap();
+    assert!((h0 - 2.0).abs() < 1e-12, "Hill q=0 应等于 range");
+    let h1 = analyzer.get_hill_number(1.0).unwrap().unwrap();
+    let entropy = analyzer.get_shannon_entropy().unwrap();
+    assert!((h1 - entropy.exp()).abs() < 1e-12);
+    let h2 = analyzer.get_hill_number(2.0).unwrap().unwrap();
+    assert!((h2 - 1.0 / 0.52).abs() < 1e-12, "Hill q=2 incorrect: {h2}");
+
+    // Hill 数随 q 单调不增
+    assert!(h0 >= h1 && h1 >= h2);
+
+    // 均匀分布下所有阶的 Hill 数都等于 n
+    let mut uniform = CorpusWordAnalyzer::new(vec![5.0; 4], vec![10.0; 4], 40.0).unwrap();
+    for q in [0.0, 0.5, 1.0, 2.0, 3.0] {
+        let hill = uniform.get_hill_number(q).unwrap().unwrap();
+        assert!((hill - 4.0).abs() < 1e-9, "均匀分布 Hill q={q} 应为 4");
+    }
+    assert!(matches!(
+        uniform.get_hill_number(f64::NAN),
+        Err(DispersionError::NonFiniteValue { .. })
+    ));
+}
+
+#[test]
+fn test_gini_coefficient_bounds() {
+    // 完全集中：Gini = (n - 1) / n；均匀：Gini = 0
+    let mut clumped = CorpusWordAnalyzer::new(vec![10.0, 0.0, 0.0], vec![10.0; 3], 30.0).unwrap();
+    assert!((clumped.get_gini_coefficient().unwrap() - 2.0 / 3.0).abs() < 1e-12);
+    let mut uniform = CorpusWordAnalyzer::new(vec![5.0; 3], vec![10.0; 3], 30.0).unwrap();
+    assert!(uniform.get_gini_coefficient().unwrap().abs() < 1e-12);
+}
+
+#[test]
+fn test_chi_square_sf_known_values() {
+    // 卡方分布临界值（α = 0.05）
+    let cases = [
+        (3.841_458_820_694_124, 1),
+        (5.991_464_547_107_979, 2),
+        (7.814_727_903_251_178, 3),
+        (18.307_038_053_275_146, 10),
+        (124.342_113_404_850_5, 100),
+    ];
+    for (statistic, df) in cases {
+        let p = chi_square_sf(statistic, df).unwrap();
+        assert!((p - 0.05).abs() < 1e-10, "df={df}: expected 0.05, got {p}");
+    }
+
+    // df = 2 时存在闭式解 exp(-x/2)
+    for x in [0.1, 1.0, 4.0, 30.0] {
+        let p = chi_square_sf(x, 2).unwrap();
+        assert!((p - (-x / 2.0f64).exp()).abs() < 1e-12, "df=2, x={x}");
+    }
+
+    assert_eq!(chi_square_sf(0.0, 3), Some(1.0));
+    assert_eq!(chi_square_sf(1.0, 0), None);
+}
+
+#[test]
+fn test_mathematical_correctness_goodness_of_fit() {
+    // 手算：v = [2, 3, 5]，期望频数均为 10/3
+    let analyzer = CorpusWordAnalyzer::new(vec![2.0, 3.0, 5.0], vec![10.0; 3], 30.0).unwrap();
+    let fit = analyzer.get_goodness_of_fit();
+
+    assert!((fit.chi_square - 1.4).abs() < 1e-12, "chi-square incorrect");
+    let expected_g = 2.0 * (2.0 * 0.6f64.ln() + 3.0 * 0.9f64.ln() + 5.0 * 1.5f64.ln());
+    assert!((fit.g_statistic - expected_g).abs() < 1e-12, "G incorrect");
+    assert_eq!(fit.degrees_of_freedom, 2);
+    assert!((fit.chi_square_p_value.unwrap() - (-0.7f64).exp()).abs() < 1e-12);
+    assert!((fit.g_p_value.unwrap() - (-expected_g / 2.0).exp()).abs() < 1e-12);
+
+    // Lyne D3 与同一 χ² 一致
+    let d3 = analyzer.get_lyne_d3().unwrap();
+    assert!((d3 - (1.0 - fit.chi_square / 40.0)).abs() < 1e-12);
+
+    // 与部分大小成正比：统计量为 0，p 值为 1
+    let proportional =
+        CorpusWordAnalyzer::new(vec![4.0, 8.0, 12.0], vec![10.0, 20.0, 30.0], 60.0).unwrap();
+    let fit = proportional.get_goodness_of_fit();
+    assert!(fit.chi_square.abs() < 1e-12 && fit.g_statistic.abs() < 1e-12);
+    assert!((fit.chi_square_p_value.unwrap() - 1.0).abs() < 1e-12);
+
+    // 高度集中的分布显著偏离期望
+    let clumped = CorpusWordAnalyzer::new(vec![60.0, 0.0, 0.0], vec![10.0; 3], 30.0).unwrap();
+    let fit = clumped.get_goodness_of_fit();
+    assert!(fit.chi_square_p_value.unwrap() < 1e-20);
+    assert!(fit.g_p_value.unwrap() < 1e-20);
+}
+
+#[test]
+fn test_null_distribution_expected_frequency() {
+    // 多项分布零模型：E[v_i] = f·s_i，因此 E[FT] = (1/n) Σ f·s_i / size_i = f / N
+    let mut analyzer = CorpusWordAnalyzer::new(
+        vec![30.0, 10.0, 0.0, 20.0],
+        vec![100.0, 200.0, 300.0, 400.0],
+        1000.0,
+    )
+    .unwrap();
+    let config = SimulationConfig {
+        n_simulations: 4000,
+        seed: Some(2024),
+    };
+    let null = analyzer
+        .simulate_null_distribution(MetricSet::MEAN_TEXT_FREQUENCY_FT, &config)
+        .unwrap();
+    let expected = null.expected.mean_text_frequency_ft.unwrap();
+    assert!(
+        (expected - 60.0 / 1000.0).abs() < 1e-3,
+        "模拟均值应接近 f / N: {expected}"
+    );
+}
+
+#[test]
+fn test_null_distribution_large_frequency() {
+    // f = 10^6，两个等大分区：X ~ B(f, 1/2)，DP = |X/f − 1/2|
+    // E[DP] = √(2·f·p·q/π) / f = √(f / (2π)) / f ≈ 3.989e-4，标准差 ≈ 0.603·500/f
+    let mut analyzer =
+        CorpusWordAnalyzer::new(vec![500_000.0, 500_000.0], vec![50.0, 50.0], 100.0).unwrap();
+    let config = SimulationConfig {
+        n_simulations: 2000,
+        seed: Some(7),
+    };
+    let null = analyzer
+        .simulate_null_distribution(MetricSet::DP, &config)
+        .unwrap();
+    let expected_dp = (1e6 / (2.0 * std::f64::consts::PI)).sqrt() / 1e6;
+    let mean = null.expected.dp.unwrap();
+    let sd = null.std_dev.dp.unwrap();
+    // 均值的标准误约为 6.7e-6
+    assert!(
+        (mean - expected_dp).abs() < 3e-5,
+        "二项分布抽样的 DP 均值 {mean} 应接近 {expected_dp}"
+    );
+    let expected_sd = (1.0 - 2.0 / std::f64::consts::PI).sqrt() * 500.0 / 1e6;
+    assert!(
+        (sd - expected_sd).abs() < 0.1 * expected_sd,
+        "DP 标准差 {sd} 应接近 {expected_sd}"
+    );
+
+    // 不等大分区：X_1 ~ B(f, 0.1)，FT 的模拟均值仍应为 f / N
+    let mut skewed =
+        CorpusWordAnalyzer::new(vec![1000.0, 0.0], vec![100.0, 900.0], 1000.0).unwrap();
+    let null = skewed
+        .simulate_null_distribution(MetricSet::MEAN_TEXT_FREQUENCY_FT, &config)
+        .unwrap();
+    let ft = null.expected.mean_text_frequency_ft.unwrap();
+    assert!((ft - 1.0).abs() < 2e-3, "模拟均值应接近 f / N: {ft}");
+}
+
+#[test]
+fn test_frequency_residuals_trend() {
+    // 频率 [1, 1, 10, 100, 100, 0]，请求三个频段，但相同频率不拆分，只得到 {1, 1} 和 {10, 100, 100}
+    // 频段节点：(ln 1, median(0.8, 0.6)) = (0, 0.7)，(ln 100, median(0.5, 0.2, 0.4)) = (ln 100, 0.4)
+    let dp = [0.8, 0.6, 0.5, 0.2, 0.4, 0.0];
+    let metrics = dp
+        .iter()
+        .map(|&value| DispersionMetrics {
+            dp: Some(value),
+            ..Default::default()
+        })
+        .collect();
+    let results = frequency_residuals(
+        metrics,
+        &[1.0, 1.0, 10.0, 100.0, 100.0, 0.0],
+        &ResidualConfig { n_bands: 3 },
+    )
+    .unwrap();
+
+    let bands: Vec<Option<usize>> = results.iter().map(|r| r.band).collect();
+    assert_eq!(
+        bands,
+        vec![Some(0), Some(0), Some(1), Some(1), Some(1), None]
+    );
+
+    // 节点处的预测值即频段中位数
+    assert!(
+        (results[0].predicted.dp.unwrap() - 0.7).abs() < 1e-12,
+        "频段 0 预测"
+    );
+    assert!(
+        (results[0].residual.dp.unwrap() - 0.1).abs() < 1e-12,
+        "频段 0 残差"
+    );
+    assert!(
+        (results[0].percentile.dp.unwrap() - 75.0).abs() < 1e-12,
+        "频段 0 百分位"
+    );
+    assert!(
+        (results[1].percentile.dp.unwrap() - 25.0).abs() < 1e-12,
+        "频段 0 百分位"
+    );
+
+    // ln 10 位于两个节点正中：预测 0.7 + 0.5 × (0.4 − 0.7) = 0.55
+    assert!(
+        (results[2].predicted.dp.unwrap() - 0.55).abs() < 1e-12,
+        "插值预测"
+    );
+    assert!(
+        (results[2].residual.dp.unwrap() + 0.05).abs() < 1e-12,
+        "插值残差"
+    );
+    assert!(
+        (results[2].percentile.dp.unwrap() - 250.0 / 3.0).abs() < 1e-12,
+        "频段 1 百分位"
+    );
+    assert!(
+        (results[3].predicted.dp.unwrap() - 0.4).abs() < 1e-12,
+        "频段 1 预测"
+    );
+    assert!(
+        (results[3].percentile.dp.unwrap() - 50.0 / 3.0).abs() < 1e-12,
+        "频段 1 百分位"
+    );
+
+    // 未出现的词不参与拟合
+    assert!(results[5].predicted.dp.is_none());
+}
+
 #[test]
 fn test_mathematical_correctness_extreme_cases() {
     // 测试极端情况的数学正确性
@@ -412,3 +791,128 @@ fn test_mathematical_correctness_all_metrics_consistency() {
     assert!(metrics.ft_adjusted_by_pt.is_some());
     assert!(metrics.ft_adjusted_by_da.is_some());
 }
+
+/// 朴素 O(n²) 的 DA 计算，用作排序前缀和实现的参照
+#[allow(clippy::cast_precision_loss)]
+fn naive_evenness_da(p: &[f64]) -> f64 {
+    let n = p.len();
+    let mean_p = p.iter().sum::<f64>() / n as f64;
+    let mut sum_abs_diff = 0.0;
+    for i in 0..n {
+        for j in (i + 1)..n {
+            sum_abs_diff += (p[i] - p[j]).abs();
+        }
+    }
+    let num_pairs = (n * (n - 1)) / 2;
+    (1.0 - (sum_abs_diff / num_pairs as f64) / (2.0 * mean_p)).clamp(0.0, 1.0)
+}
+
+#[test]
+fn test_evenness_da_matches_naive_pairwise() {
+    // 含重复值、零值和不等长分区的情形
+    let cases: Vec<(Vec<f64>, Vec<f64>)> = vec![
+        (
+            vec![1.0, 2.0, 3.0, 4.0, 5.0],
+            vec![9.0, 10.0, 10.0, 10.0, 11.0],
+        ),
+        (vec![0.0, 7.0, 0.0, 7.0, 3.0, 3.0], vec![10.0; 6]),
+        (vec![1.0, 0.0], vec![5.0, 50.0]),
+        (vec![4.0, 4.0, 4.0, 4.0], vec![1.0, 2.0, 3.0, 4.0]),
+    ];
+    for (v, sizes) in cases {
+        let total = sizes.iter().sum();
+        let mut analyzer = CorpusWordAnalyzer::new(v, sizes, total).unwrap();
+        let expected = naive_evenness_da(analyzer.get_normalized_frequencies());
+        let da = analyzer.get_evenness_da().unwrap();
+        assert!(
+            (da - expected).abs() < 1e-12,
+            "DA mismatch: expected {expected}, got {da}"
+        );
+    }
+}
+
+#[test]
+fn test_evenness_da_matches_naive_many_parts() {
+    // 大量分区（伪随机、确定性）下与朴素实现一致
+    let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+    let mut next = || {
+        state = state
+            .wrapping_mul(6_364_136_223_846_793_005)
+            .wrapping_add(1_442_695_040_888_963_407);
+        state >> 33
+    };
+    let n = 2000;
+    #[allow(clippy::cast_precision_loss)]
+    let v: Vec<f64> = (0..n)
+        .map(|_| {
+            if next() % 3 == 0 {
+                0.0
+            } else {
+                (next() % 50) as f64
+            }
+        })
+        .collect();
+    #[allow(clippy::cast_precision_loss)]
+    let sizes: Vec<f64> = (0..n).map(|_| (100 + next() % 900) as f64).collect();
+    let total = sizes.iter().sum();
+
+    let mut analyzer = CorpusWordAnalyzer::new(v, sizes, total).unwrap();
+    let expected = naive_evenness_da(analyzer.get_normalized_frequencies());
+    let da = analyzer.get_evenness_da().unwrap();
+    assert!(
+        (da - expected).abs() < 1e-9,
+        "DA mismatch: expected {expected}, got {da}"
+    );
+}
+
+#[test]
+fn test_mathematical_correctness_position_metrics_clumped() {
+    // N = 100，5 次出现全部相邻：距离为 96（环绕）, 1, 1, 1, 1，v = N/f = 20
+    let analyzer = PositionAnalyzer::new(vec![0, 1, 2, 3, 4], 100).unwrap();
+    assert_eq!(analyzer.get_distances(), &[96.0, 1.0, 1.0, 1.0, 1.0]);
+
+    // ARF = (min(96, 20) + 4) / 20 = 1.2
+    assert!((analyzer.get_arf().unwrap() - 1.2).abs() < 1e-12);
+    // AWT = (1 + (96² + 4) / 100) / 2 = 46.6
+    assert!((analyzer.get_awt().unwrap() - 46.6).abs() < 1e-12);
+    // ALD = 0.96 · log10(96)
+    assert!((analyzer.get_ald().unwrap() - 0.96 * 96.0_f64.log10()).abs() < 1e-12);
+}
+
+#[test]
+fn test_mathematical_correctness_position_metrics_even() {
+    // 均匀间隔：所有距离都等于 v = 20，ARF 等于频次本身
+    let analyzer = PositionAnalyzer::new(vec![0, 20, 40, 60, 80], 100).unwrap();
+    assert!((analyzer.get_arf().unwrap() - 5.0).abs() < 1e-12);
+    // AWT = (1 + 5 · 400 / 100) / 2 = 10.5
+    assert!((analyzer.get_awt().unwrap() - 10.5).abs() < 1e-12);
+    // ALD = log10(20)
+    assert!((analyzer.get_ald().unwrap() - 20.0_f64.log10()).abs() < 1e-12);
+
+    // 平移不改变环形距离
+    let shifted = PositionAnalyzer::new(vec![7, 27, 47, 67, 87], 100).unwrap();
+    assert_eq!(shifted.get_arf(), analyzer.get_arf());
+}
+
+#[test]
+fn test_mathematical_correctness_self_dispersion_and_burstiness() {
+    // 均匀间隔：最近邻距离 20，随机期望 N/2f = 10，自分散度为 2；距离无方差，突发度为 -1
+    let even = PositionAnalyzer::new(vec![0, 20, 40, 60, 80], 100).unwrap();
+    assert!((even.get_self_dispersion().unwrap() - 2.0).abs() < 1e-12);
+    assert!((even.get_mean_distance().unwrap() - 20.0).abs() < 1e-12);
+    assert!(even.get_distance_variance().unwrap().abs() < 1e-12);
+    assert!((even.get_burstiness().unwrap() + 1.0).abs() < 1e-12);
+
+    // 全部相邻：距离 96, 1, 1, 1, 1，最近邻距离均为 1
+    let clumped = PositionAnalyzer::new(vec![0, 1, 2, 3, 4], 100).unwrap();
+    assert!((clumped.get_self_dispersion().unwrap() - 0.1).abs() < 1e-12);
+    // 方差 = (76² + 4 · 19²) / 5 = 1444，σ = 38，B = (38 - 20) / (38 + 20)
+    assert!((clumped.get_distance_variance().unwrap() - 1444.0).abs() < 1e-9);
+    assert!((clumped.get_burstiness().unwrap() - 18.0 / 58.0).abs() < 1e-12);
+
+    // 单次出现时最近邻和突发度无定义
+    let single = PositionAnalyzer::new(vec![10], 100).unwrap();
+    assert!(single.get_self_dispersion().is_none());
+    assert!(single.get_burstiness().is_none());
+    assert!((single.get_mean_distance().unwrap() - 100.0).abs() < 1e-12);
+}